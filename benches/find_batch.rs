@@ -0,0 +1,70 @@
+//! Compares UnionFind::find_batch with one find per query on a forest much larger than the
+//! cache, for both backends, and prints how many times faster the batch is. Run with
+//! `cargo bench --bench find_batch`.
+
+use std::{hint::black_box, time::{Duration, Instant}};
+
+use hash_unionfind::{Backend, DenseBackend, UnionFind};
+
+const ELEMENTS: u32 = 1 << 22;
+
+// A xorshift generator, so the bench needs no dependencies and every run sees the same forest.
+fn random(state: &mut u64) -> u32 {
+    *state ^= *state << 13;
+    *state ^= *state >> 7;
+    *state ^= *state << 17;
+    (*state >> 32) as u32
+}
+
+// Random unions over all elements, leaving trees of some depth since nothing is queried.
+fn build<B: Backend<Key = u32>>(mut uf: UnionFind<u32, B>) -> UnionFind<u32, B> {
+    uf.insert_many(0..ELEMENTS);
+    let mut state = 0x9e37_79b9_7f4a_7c15;
+    for _ in 0..ELEMENTS - ELEMENTS / 16 {
+        let (x, y) = (random(&mut state) % ELEMENTS, random(&mut state) % ELEMENTS);
+        uf.union_ids(uf.id(&x).unwrap(), uf.id(&y).unwrap());
+    }
+    uf
+}
+
+// Each way of finding runs this many times on a fresh copy of the forest, keeping the fastest
+// run, so a noisy neighbour does not decide the comparison.
+const ROUNDS: usize = 5;
+
+fn best_of<B: Backend<Key = u32> + Clone>(uf: &UnionFind<u32, B>, run: impl Fn(&UnionFind<u32, B>)) -> Duration {
+    (0..ROUNDS)
+        .map(|_| {
+            let uf = uf.clone();
+            let start = Instant::now();
+            run(&uf);
+            start.elapsed()
+        })
+        .min()
+        .unwrap()
+}
+
+fn time<B: Backend<Key = u32> + Clone>(name: &str, uf: &UnionFind<u32, B>, queries: &[u32]) {
+    let one_by_one = best_of(uf, |uf| {
+        for q in queries {
+            black_box(uf.find(q));
+        }
+    });
+    let batch = best_of(uf, |uf| {
+        black_box(uf.find_batch(queries));
+    });
+
+    let per_query = |elapsed: Duration| elapsed.as_nanos() as f64 / queries.len() as f64;
+    println!(
+        "{name:>6}: find {:6.1} ns/query, find_batch {:6.1} ns/query, {:.2}x",
+        per_query(one_by_one),
+        per_query(batch),
+        one_by_one.as_secs_f64() / batch.as_secs_f64(),
+    );
+}
+
+fn main() {
+    let mut state = 0x2545_f491_4f6c_dd1d;
+    let queries: Vec<u32> = (0..ELEMENTS).map(|_| random(&mut state) % ELEMENTS).collect();
+    time("dense", &build(UnionFind::with_backend(DenseBackend::default())), &queries);
+    time("hash", &build(UnionFind::new()), &queries);
+}
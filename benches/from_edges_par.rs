@@ -0,0 +1,47 @@
+//! Times UnionFind::from_edges_par on a random graph for every thread count up to the
+//! available parallelism, against building the same components with one union per edge.
+//! Speedups need as many cores, on one core every count only adds the cost of sharding. Run
+//! with `cargo bench --bench from_edges_par`.
+
+use std::{hint::black_box, thread, time::Instant};
+
+use hash_unionfind::UnionFind;
+
+const ELEMENTS: u64 = 1 << 21;
+const EDGES: usize = 1 << 22;
+
+// A xorshift generator, so the bench needs no dependencies and every run sees the same graph.
+fn random(state: &mut u64) -> u64 {
+    *state ^= *state << 13;
+    *state ^= *state >> 7;
+    *state ^= *state << 17;
+    *state >> 32
+}
+
+fn main() {
+    let mut state = 0x9e37_79b9_7f4a_7c15;
+    let edges: Vec<(u64, u64)> =
+        (0..EDGES).map(|_| (random(&mut state) % ELEMENTS, random(&mut state) % ELEMENTS)).collect();
+
+    let start = Instant::now();
+    let mut sequential = UnionFind::new();
+    for (x, y) in &edges {
+        sequential.union_insert(*x, *y);
+    }
+    let baseline = start.elapsed();
+    println!("sequential: {:8.1} ms", baseline.as_secs_f64() * 1e3);
+
+    let cores = thread::available_parallelism().map_or(1, usize::from);
+    let counts = (0..).map(|p| 1 << p).take_while(|&threads| threads < cores).chain([cores]);
+    for threads in counts {
+        let start = Instant::now();
+        let uf = black_box(UnionFind::from_edges_par(&edges, threads));
+        let elapsed = start.elapsed();
+        assert_eq!(sequential.num_classes(), uf.num_classes());
+        println!(
+            "{threads:>3} threads: {:8.1} ms, {:5.2}x the sequential build",
+            elapsed.as_secs_f64() * 1e3,
+            baseline.as_secs_f64() / elapsed.as_secs_f64(),
+        );
+    }
+}
@@ -0,0 +1,176 @@
+//! Derive macros for `hash-unionfind`, re-exported by it behind the `derive` feature.
+
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::{parse_macro_input, spanned::Spanned, Data, DeriveInput, Error, Fields, Type};
+
+/// Derives the traits a `UnionFind` key needs for a newtype around a single field: `Clone`,
+/// `PartialEq`, `Eq`, `Hash` and `Debug`, all forwarding to the field.
+///
+/// More impls are opted into with `#[union_find_key(...)]`:
+/// - `display` forwards `Display` to the field.
+/// - `borrow` implements `Borrow<Field>`, which is consistent since `Hash` and `Eq` forward.
+/// - `from` implements `From<Field>`, for wrapping raw ids.
+///
+/// # Examples
+///
+/// ```
+/// use hash_unionfind::{UnionFind, UnionFindKey};
+///
+/// #[derive(UnionFindKey)]
+/// #[union_find_key(display, from)]
+/// struct UserId(u64);
+///
+/// let mut uf = UnionFind::new();
+/// uf.insert_many([1, 2, 3].map(UserId::from));
+/// uf.union(&UserId(1), &UserId(3));
+/// assert_eq!(uf.find(&UserId(3)).unwrap().to_string(), "1");
+/// assert_eq!(format!("{:?}", UserId(2)), "UserId(2)");
+/// ```
+///
+/// Named fields and generic wrappers work as well, the impls are bounded on the field type.
+///
+/// ```
+/// use std::borrow::Borrow;
+/// use hash_unionfind::UnionFindKey;
+///
+/// #[derive(UnionFindKey)]
+/// #[union_find_key(borrow)]
+/// struct Tagged<T> {
+///     raw: T,
+/// }
+///
+/// let key = Tagged { raw: "a".to_string() };
+/// assert!(key == key.clone());
+/// let raw: &String = key.borrow();
+/// assert_eq!(raw, "a");
+/// ```
+#[proc_macro_derive(UnionFindKey, attributes(union_find_key))]
+pub fn derive_union_find_key(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand(input).unwrap_or_else(Error::into_compile_error).into()
+}
+
+#[derive(Default)]
+struct Options {
+    display: bool,
+    borrow: bool,
+    from: bool,
+}
+
+fn options(input: &DeriveInput) -> syn::Result<Options> {
+    let mut options = Options::default();
+    for attr in input.attrs.iter().filter(|a| a.path().is_ident("union_find_key")) {
+        attr.parse_nested_meta(|meta| {
+            let flag = if meta.path.is_ident("display") {
+                &mut options.display
+            } else if meta.path.is_ident("borrow") {
+                &mut options.borrow
+            } else if meta.path.is_ident("from") {
+                &mut options.from
+            } else {
+                return Err(meta.error("expected `display`, `borrow` or `from`"));
+            };
+            *flag = true;
+            Ok(())
+        })?;
+    }
+    Ok(options)
+}
+
+fn expand(input: DeriveInput) -> syn::Result<TokenStream2> {
+    let options = options(&input)?;
+    let fields = match &input.data {
+        Data::Struct(data) => &data.fields,
+        _ => return Err(Error::new(input.span(), "UnionFindKey can only be derived for structs")),
+    };
+    let field = match fields.iter().collect::<Vec<_>>()[..] {
+        [field] => field,
+        _ => return Err(Error::new(fields.span(), "UnionFindKey needs exactly one field")),
+    };
+    let inner: &Type = &field.ty;
+    // `self.0` for tuple structs, `self.name` otherwise, and the matching constructors.
+    let (access, construct, construct_clone) = match (&field.ident, fields) {
+        (Some(name), _) => (
+            quote!(#name),
+            quote!({ #name: value }),
+            quote!({ #name: ::core::clone::Clone::clone(&self.#name) }),
+        ),
+        (None, Fields::Unnamed(_)) => (quote!(0), quote!((value)), quote!((::core::clone::Clone::clone(&self.0)))),
+        (None, _) => unreachable!("unnamed fields belong to tuple structs"),
+    };
+
+    let name = &input.ident;
+    let name_str = name.to_string();
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let bounded = |bound: TokenStream2| {
+        let mut clause = where_clause.cloned().unwrap_or_else(|| syn::parse_quote!(where));
+        clause.predicates.push(syn::parse_quote!(#inner: #bound));
+        clause
+    };
+    let (clone, eq, hash, debug) = (
+        bounded(quote!(::core::clone::Clone)),
+        bounded(quote!(::core::cmp::Eq)),
+        bounded(quote!(::core::hash::Hash)),
+        bounded(quote!(::core::fmt::Debug)),
+    );
+
+    let mut out = quote! {
+        impl #impl_generics ::core::clone::Clone for #name #ty_generics #clone {
+            fn clone(&self) -> Self {
+                Self #construct_clone
+            }
+        }
+
+        impl #impl_generics ::core::cmp::PartialEq for #name #ty_generics #eq {
+            fn eq(&self, other: &Self) -> bool {
+                self.#access == other.#access
+            }
+        }
+
+        impl #impl_generics ::core::cmp::Eq for #name #ty_generics #eq {}
+
+        impl #impl_generics ::core::hash::Hash for #name #ty_generics #hash {
+            fn hash<H: ::core::hash::Hasher>(&self, state: &mut H) {
+                ::core::hash::Hash::hash(&self.#access, state)
+            }
+        }
+
+        impl #impl_generics ::core::fmt::Debug for #name #ty_generics #debug {
+            fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+                f.debug_tuple(#name_str).field(&self.#access).finish()
+            }
+        }
+    };
+
+    if options.display {
+        let display = bounded(quote!(::core::fmt::Display));
+        out.extend(quote! {
+            impl #impl_generics ::core::fmt::Display for #name #ty_generics #display {
+                fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+                    ::core::fmt::Display::fmt(&self.#access, f)
+                }
+            }
+        });
+    }
+    if options.borrow {
+        out.extend(quote! {
+            impl #impl_generics ::core::borrow::Borrow<#inner> for #name #ty_generics #where_clause {
+                fn borrow(&self) -> &#inner {
+                    &self.#access
+                }
+            }
+        });
+    }
+    if options.from {
+        out.extend(quote! {
+            impl #impl_generics ::core::convert::From<#inner> for #name #ty_generics #where_clause {
+                fn from(value: #inner) -> Self {
+                    Self #construct
+                }
+            }
+        });
+    }
+    Ok(out)
+}
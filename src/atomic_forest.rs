@@ -0,0 +1,69 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+// A forest over dense indices whose parents are atomic, so finds and unions from many threads
+// never block each other: unions link roots with a compare and swap and retry if another
+// thread got there first, and finds compress by path halving. Linking goes by a fixed pseudo
+// random priority of the roots instead of rank, which keeps trees logarithmic in expectation
+// without extra state to agree on.
+#[derive(Debug, Default)]
+pub(crate) struct AtomicForest {
+    parents: Vec<AtomicUsize>,
+}
+
+// The linking priority of an index, a bijective mix so no two roots tie.
+fn priority(i: usize) -> u64 {
+    let mut z = (i as u64).wrapping_add(0x9e37_79b9_7f4a_7c15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    z ^ (z >> 31)
+}
+
+impl AtomicForest {
+    // A forest of len singletons.
+    pub(crate) fn with_len(len: usize) -> Self {
+        Self { parents: (0..len).map(AtomicUsize::new).collect() }
+    }
+
+    // Adds a singleton at the next index.
+    #[cfg_attr(not(feature = "concurrent"), allow(dead_code))]
+    pub(crate) fn push(&mut self) {
+        self.parents.push(AtomicUsize::new(self.parents.len()));
+    }
+
+    #[cfg_attr(not(feature = "concurrent"), allow(dead_code))]
+    pub(crate) fn is_root(&self, i: usize) -> bool {
+        self.parents[i].load(Ordering::Acquire) == i
+    }
+
+    // Walks to the root of i, halving the path on the way.
+    pub(crate) fn root(&self, mut i: usize) -> usize {
+        loop {
+            let parent = self.parents[i].load(Ordering::Acquire);
+            if parent == i {
+                return i;
+            }
+            let grandparent = self.parents[parent].load(Ordering::Acquire);
+            if grandparent != parent {
+                // Losing the race to another halving or a link is fine, both keep the forest valid.
+                let _ = self.parents[i].compare_exchange(parent, grandparent, Ordering::AcqRel, Ordering::Acquire);
+            }
+            i = grandparent;
+        }
+    }
+
+    // Unions the trees of x and y, returning the root of the merged tree and whether this
+    // call linked them, false if they already were one tree.
+    pub(crate) fn union(&self, x: usize, y: usize) -> (usize, bool) {
+        loop {
+            let (x, y) = (self.root(x), self.root(y));
+            if x == y {
+                return (x, false);
+            }
+            let (child, parent) = if priority(x) < priority(y) { (x, y) } else { (y, x) };
+            // Fails if child stopped being a root since it was found, then retry from the top.
+            if self.parents[child].compare_exchange(child, parent, Ordering::AcqRel, Ordering::Acquire).is_ok() {
+                return (parent, true);
+            }
+        }
+    }
+}
@@ -0,0 +1,377 @@
+use std::{
+    borrow::Borrow,
+    collections::hash_map::RandomState,
+    fmt::Debug,
+    hash::{BuildHasher, Hash, Hasher},
+};
+
+use indexmap::IndexSet;
+
+use crate::{small, wide, Small};
+
+/// Where a [UnionFind](crate::UnionFind) keeps its keys: a mapping between keys and dense
+/// positions, which index the parent forest.
+///
+/// The forest algorithms are the same whatever the backend, so the layout can be chosen per
+/// key type: [HashBackend] for any hashable key, [DenseBackend] for small integer keys.
+///
+/// Positions are exactly `0..len`, handed out in insertion order, and the union-find keeps
+/// its nodes in a vector next to the backend by position. Removing a key moves the keys
+/// after it down by one, as [Backend::shift_remove] describes.
+pub trait Backend {
+    type Key;
+
+    /// The position of key, None if missing.
+    fn position<Q: Hash + Eq + ?Sized>(&self, key: &Q) -> Option<usize>
+    where
+        Self::Key: Borrow<Q>;
+
+    /// The key at position, None past [Backend::len].
+    fn key(&self, position: usize) -> Option<&Self::Key>;
+
+    /// Adds key at position [Backend::len] if it is new, returning its position and whether
+    /// it was new.
+    fn insert(&mut self, key: Self::Key) -> (usize, bool);
+
+    /// Removes and returns the last key inserted.
+    fn pop(&mut self) -> Option<Self::Key>;
+
+    /// Removes key, returning its position and the stored key. The keys after it move down
+    /// one position, keeping their order.
+    fn shift_remove<Q: Hash + Eq + ?Sized>(&mut self, key: &Q) -> Option<(usize, Self::Key)>
+    where
+        Self::Key: Borrow<Q>;
+
+    /// Moves the key at position `order[i]` to position i for every i, order being a
+    /// permutation of the positions.
+    fn permute(&mut self, order: &[usize]);
+
+    /// The number of keys, one past the largest position.
+    fn len(&self) -> usize;
+
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// The number of keys the backend holds without reallocating.
+    fn capacity(&self) -> usize;
+
+    fn reserve(&mut self, additional: usize);
+
+    fn shrink_to_fit(&mut self);
+
+    /// All keys by position.
+    fn into_keys(self) -> Vec<Self::Key>;
+}
+
+/// The default layout of [UnionFind](crate::UnionFind): keys hashed with S into an insertion
+/// ordered set.
+#[derive(Debug, Clone)]
+pub struct HashBackend<T, S = RandomState> {
+    keys: IndexSet<T, S>,
+}
+
+impl<T, S: Default> Default for HashBackend<T, S> {
+    fn default() -> Self {
+        Self { keys: IndexSet::default() }
+    }
+}
+
+impl<T, S> HashBackend<T, S> {
+    /// An empty backend hashing with hasher, with room for capacity keys.
+    pub fn with_capacity_and_hasher(capacity: usize, hasher: S) -> Self {
+        Self { keys: IndexSet::with_capacity_and_hasher(capacity, hasher) }
+    }
+
+    pub fn hasher(&self) -> &S {
+        self.keys.hasher()
+    }
+}
+
+impl<T: Hash + Eq, S: BuildHasher> Backend for HashBackend<T, S> {
+    type Key = T;
+
+    fn position<Q: Hash + Eq + ?Sized>(&self, key: &Q) -> Option<usize>
+    where
+        T: Borrow<Q>,
+    {
+        self.keys.get_index_of(key)
+    }
+
+    fn key(&self, position: usize) -> Option<&T> {
+        self.keys.get_index(position)
+    }
+
+    fn insert(&mut self, key: T) -> (usize, bool) {
+        self.keys.insert_full(key)
+    }
+
+    fn pop(&mut self) -> Option<T> {
+        self.keys.pop()
+    }
+
+    fn shift_remove<Q: Hash + Eq + ?Sized>(&mut self, key: &Q) -> Option<(usize, T)>
+    where
+        T: Borrow<Q>,
+    {
+        self.keys.shift_remove_full(key)
+    }
+
+    fn permute(&mut self, order: &[usize]) {
+        let mut keys: Vec<Option<T>> = self.keys.drain(..).map(Some).collect();
+        self.keys.extend(order.iter().map(|&old| keys[old].take().unwrap()));
+    }
+
+    fn len(&self) -> usize {
+        self.keys.len()
+    }
+
+    fn capacity(&self) -> usize {
+        self.keys.capacity()
+    }
+
+    fn reserve(&mut self, additional: usize) {
+        self.keys.reserve(additional);
+    }
+
+    fn shrink_to_fit(&mut self) {
+        self.keys.shrink_to_fit();
+    }
+
+    fn into_keys(self) -> Vec<T> {
+        self.keys.into_iter().collect()
+    }
+}
+
+/// Keys that index the slots of a [DenseBackend].
+///
+/// Hashing a key must write [DenseKey::index] as a single integer, as the integer types do,
+/// so lookups by a borrowed key find the slot without hashing.
+pub trait DenseKey: Copy + Eq {
+    fn index(self) -> usize;
+}
+
+macro_rules! dense_key {
+    ($($t:ty),*) => {
+        $(impl DenseKey for $t {
+            fn index(self) -> usize {
+                usize::try_from(self).expect("key does not fit an index")
+            }
+        })*
+    };
+}
+
+dense_key!(u8, u16, u32, u64, usize);
+
+// Records the integer a key writes when hashed, the slot of a DenseKey.
+#[derive(Default)]
+struct Capture {
+    value: u64,
+    writes: usize,
+}
+
+impl Capture {
+    fn of<Q: Hash + ?Sized>(key: &Q) -> Option<usize> {
+        let mut capture = Capture::default();
+        key.hash(&mut capture);
+        if capture.writes == 1 {
+            usize::try_from(capture.value).ok()
+        } else {
+            None
+        }
+    }
+
+    fn record(&mut self, value: u64) {
+        self.value = value;
+        self.writes += 1;
+    }
+}
+
+impl Hasher for Capture {
+    fn finish(&self) -> u64 {
+        self.value
+    }
+
+    fn write(&mut self, _: &[u8]) {
+        // Not a single integer, fall back to comparing keys.
+        self.writes += 2;
+    }
+
+    fn write_u8(&mut self, i: u8) {
+        self.record(i.into());
+    }
+
+    fn write_u16(&mut self, i: u16) {
+        self.record(i.into());
+    }
+
+    fn write_u32(&mut self, i: u32) {
+        self.record(i.into());
+    }
+
+    fn write_u64(&mut self, i: u64) {
+        self.record(i);
+    }
+
+    fn write_usize(&mut self, i: usize) {
+        self.record(i as u64);
+    }
+}
+
+/// A layout without hashing for integer like keys: a slot per key value holds the position
+/// of the key. Memory is proportional to the largest key, so the keys should be dense, such
+/// as ids handed out by a counter.
+///
+/// # Examples
+///
+/// ```
+/// use hash_unionfind::{DenseBackend, UnionFind};
+///
+/// let mut uf = UnionFind::with_backend(DenseBackend::default());
+/// uf.insert_many([7u32, 3, 5]);
+/// uf.union(&3, &5);
+/// assert_eq!(Some(3), uf.find(&5).as_deref().copied());
+/// assert_eq!(vec![vec![3, 5], vec![7]], uf.to_canonical_vec());
+/// ```
+#[derive(Debug, Clone)]
+pub struct DenseBackend<K> {
+    // One more than the position of the key with each value, 0 for missing keys.
+    slots: Vec<Small>,
+    keys: Vec<K>,
+}
+
+impl<K> Default for DenseBackend<K> {
+    fn default() -> Self {
+        Self { slots: Vec::new(), keys: Vec::new() }
+    }
+}
+
+impl<K: DenseKey> DenseBackend<K> {
+    fn slot(&self, value: usize) -> Option<usize> {
+        match self.slots.get(value) {
+            Some(&slot) if slot != 0 => Some(wide(slot) - 1),
+            _ => None,
+        }
+    }
+}
+
+impl<K: DenseKey> Backend for DenseBackend<K> {
+    type Key = K;
+
+    fn position<Q: Hash + Eq + ?Sized>(&self, key: &Q) -> Option<usize>
+    where
+        K: Borrow<Q>,
+    {
+        match Capture::of(key) {
+            Some(value) => self.slot(value).filter(|&position| self.keys[position].borrow() == key),
+            None => self.keys.iter().position(|k| k.borrow() == key),
+        }
+    }
+
+    fn key(&self, position: usize) -> Option<&K> {
+        self.keys.get(position)
+    }
+
+    fn insert(&mut self, key: K) -> (usize, bool) {
+        let value = key.index();
+        if let Some(position) = self.slot(value) {
+            return (position, false);
+        }
+        if value >= self.slots.len() {
+            self.slots.resize(value + 1, 0);
+        }
+        self.keys.push(key);
+        self.slots[value] = small(self.keys.len());
+        (self.keys.len() - 1, true)
+    }
+
+    fn pop(&mut self) -> Option<K> {
+        let key = self.keys.pop()?;
+        self.slots[key.index()] = 0;
+        Some(key)
+    }
+
+    fn shift_remove<Q: Hash + Eq + ?Sized>(&mut self, key: &Q) -> Option<(usize, K)>
+    where
+        K: Borrow<Q>,
+    {
+        let position = self.position(key)?;
+        let key = self.keys.remove(position);
+        self.slots[key.index()] = 0;
+        for moved in &self.keys[position..] {
+            self.slots[moved.index()] -= 1;
+        }
+        Some((position, key))
+    }
+
+    fn permute(&mut self, order: &[usize]) {
+        self.keys = order.iter().map(|&old| self.keys[old]).collect();
+        for (position, key) in self.keys.iter().enumerate() {
+            self.slots[key.index()] = small(position + 1);
+        }
+    }
+
+    fn len(&self) -> usize {
+        self.keys.len()
+    }
+
+    fn capacity(&self) -> usize {
+        self.keys.capacity()
+    }
+
+    fn reserve(&mut self, additional: usize) {
+        self.keys.reserve(additional);
+    }
+
+    fn shrink_to_fit(&mut self) {
+        self.slots.shrink_to_fit();
+        self.keys.shrink_to_fit();
+    }
+
+    fn into_keys(self) -> Vec<K> {
+        self.keys
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::UnionFind;
+
+    #[test]
+    fn backends_agree() {
+        let mut dense = UnionFind::with_backend(DenseBackend::<u16>::default());
+        let mut hashed = UnionFind::new();
+        // Sparse keys leave slots without keys in the dense layout.
+        for i in 0..40 {
+            let key = i * 3;
+            dense.insert(key);
+            hashed.insert(key);
+        }
+        assert_eq!(0, dense.insert_many([9]));
+        for i in 0..30 {
+            let (x, y) = (i * 21 % 120, i * 33 % 120);
+            assert_eq!(dense.union(&x, &y).is_some(), hashed.union(&x, &y).is_some());
+        }
+        assert_eq!(None, dense.find(&1));
+        assert_eq!((40, hashed.num_classes()), (dense.size(), dense.num_classes()));
+        assert_eq!(hashed.to_canonical_vec(), dense.to_canonical_vec());
+        assert_eq!(hashed.remove(&60), dense.remove(&60));
+        assert!(dense.make_singleton(&9));
+        assert!(hashed.make_singleton(&9));
+        dense.reorder_by_class().unwrap();
+        assert_eq!(hashed.to_canonical_vec(), dense.to_canonical_vec());
+        for x in (0..120).step_by(3) {
+            assert_eq!(hashed.class_size(&x), dense.class_size(&x));
+            assert_eq!(hashed.find(&x), dense.find(&x));
+        }
+
+        let mut backend = DenseBackend::default();
+        assert_eq!((0, true), backend.insert(5u8));
+        assert_eq!((1, true), backend.insert(2));
+        assert_eq!((0, false), backend.insert(5));
+        let reopened = UnionFind::with_backend(backend);
+        assert_eq!((2, 2), (reopened.size(), reopened.num_classes()));
+        assert_eq!(Some(1), reopened.id(&2).map(|id| id.index()));
+    }
+}
@@ -0,0 +1,204 @@
+use std::{
+    borrow::Borrow,
+    fmt::Debug,
+    hash::Hash,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        RwLock,
+    },
+};
+
+use indexmap::IndexSet;
+
+use crate::{atomic_forest::AtomicForest, UnionFind};
+
+/// A union-find supporting concurrent [ConcurrentUnionFind::find] and
+/// [ConcurrentUnionFind::union] from many threads.
+///
+/// Parents are atomic indices into the elements, so finds and unions never block each other:
+/// unions link roots with a compare and swap and retry if another thread got there first,
+/// and finds compress by path halving. Linking goes by a fixed pseudo random priority of the
+/// roots instead of rank, which keeps trees logarithmic in expectation without extra state
+/// to agree on.
+///
+/// Inserting takes a write lock, so it waits for running operations and blocks new ones.
+/// Insert the elements up front for the best parallelism.
+///
+/// # Examples
+///
+/// ```
+/// use hash_unionfind::ConcurrentUnionFind;
+///
+/// let uf = ConcurrentUnionFind::new();
+/// uf.insert_many(0..1000);
+/// std::thread::scope(|s| {
+///     for offset in 0..4 {
+///         let uf = &uf;
+///         s.spawn(move || {
+///             for i in (offset..999).step_by(4) {
+///                 uf.union(&i, &(i + 1));
+///             }
+///         });
+///     }
+/// });
+/// assert_eq!(1, uf.num_classes());
+/// assert_eq!(Some(true), uf.equiv(&0, &999));
+/// ```
+#[derive(Debug)]
+pub struct ConcurrentUnionFind<T> {
+    inner: RwLock<Inner<T>>,
+    // The number of disjoint classes, kept up to date by insert and union.
+    classes: AtomicUsize,
+}
+
+#[derive(Debug)]
+struct Inner<T> {
+    // The elements, whose positions are their indices in the forest.
+    keys: IndexSet<T>,
+    forest: AtomicForest,
+}
+
+impl<T: Hash + Eq> Default for ConcurrentUnionFind<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Hash + Eq> ConcurrentUnionFind<T> {
+    pub fn new() -> Self {
+        Self {
+            inner: RwLock::new(Inner { keys: IndexSet::new(), forest: AtomicForest::default() }),
+            classes: AtomicUsize::new(0),
+        }
+    }
+
+    fn read(&self) -> std::sync::RwLockReadGuard<'_, Inner<T>> {
+        self.inner.read().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    pub fn size(&self) -> usize {
+        self.read().keys.len()
+    }
+
+    /// The number of disjoint classes. Concurrent unions may make it stale right away.
+    pub fn num_classes(&self) -> usize {
+        self.classes.load(Ordering::Acquire)
+    }
+
+    /// Inserts t as a singleton, returning whether it was new.
+    pub fn insert(&self, t: T) -> bool {
+        self.insert_many(std::iter::once(t)) == 1
+    }
+
+    /// Inserts every element as a singleton under a single write lock. Returns how many of
+    /// the elements were new.
+    pub fn insert_many<I: IntoIterator<Item = T>>(&self, iter: I) -> usize {
+        let mut inner = self.inner.write().unwrap_or_else(|poisoned| poisoned.into_inner());
+        let before = inner.keys.len();
+        for t in iter {
+            if inner.keys.insert(t) {
+                inner.forest.push();
+            }
+        }
+        let added = inner.keys.len() - before;
+        self.classes.fetch_add(added, Ordering::AcqRel);
+        added
+    }
+}
+
+impl<T: Hash + Eq + Clone> ConcurrentUnionFind<T> {
+    /// The leader of the class of x, or None if x is missing. Leaders may change as soon as
+    /// a concurrent union links the class.
+    pub fn find<Q: Hash + Eq + ?Sized>(&self, x: &Q) -> Option<T>
+    where
+        T: Borrow<Q>,
+    {
+        let inner = self.read();
+        let root = inner.forest.root(inner.keys.get_index_of(x)?);
+        Some(inner.keys[root].clone())
+    }
+
+    /// Unions the classes of x and y, returning the leader of the merged class, or None if
+    /// one of them is missing.
+    pub fn union<Q: Hash + Eq + ?Sized>(&self, x: &Q, y: &Q) -> Option<T>
+    where
+        T: Borrow<Q>,
+    {
+        let inner = self.read();
+        let (x, y) = (inner.keys.get_index_of(x)?, inner.keys.get_index_of(y)?);
+        let (root, merged) = inner.forest.union(x, y);
+        if merged {
+            self.classes.fetch_sub(1, Ordering::AcqRel);
+        }
+        Some(inner.keys[root].clone())
+    }
+
+    /// Whether x and y are in the same class, None if one of them is missing. A true answer
+    /// stays true, a false one may be outdated by concurrent unions.
+    pub fn equiv<Q: Hash + Eq + ?Sized>(&self, x: &Q, y: &Q) -> Option<bool>
+    where
+        T: Borrow<Q>,
+    {
+        let inner = self.read();
+        let (mut x, mut y) = (inner.keys.get_index_of(x)?, inner.keys.get_index_of(y)?);
+        loop {
+            (x, y) = (inner.forest.root(x), inner.forest.root(y));
+            if x == y {
+                return Some(true);
+            }
+            // The roots differ, which is only conclusive if x was not linked meanwhile.
+            if inner.forest.is_root(x) {
+                return Some(false);
+            }
+        }
+    }
+}
+
+impl<T: Hash + Eq + Clone + Debug> ConcurrentUnionFind<T> {
+    /// The single threaded [UnionFind] with the same partition, keeping insertion order.
+    pub fn into_union_find(self) -> UnionFind<T> {
+        let inner = self.inner.into_inner().unwrap_or_else(|poisoned| poisoned.into_inner());
+        let mut uf = UnionFind::new();
+        uf.insert_many(inner.keys.iter().cloned());
+        for (i, t) in inner.keys.iter().enumerate() {
+            uf.union(t, &inner.keys[inner.forest.root(i)]);
+        }
+        uf
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn matches_sequential() {
+        let n = 2000;
+        let edges: Vec<(usize, usize)> =
+            (0..n).map(|i| (i, (i * 7919 + 13) % n)).filter(|(a, b)| a % 5 != b % 3).collect();
+        let mut expected = UnionFind::new();
+        expected.insert_many(0..n);
+        for (a, b) in &edges {
+            expected.union(a, b);
+        }
+
+        let uf = ConcurrentUnionFind::new();
+        uf.insert_many(0..n);
+        std::thread::scope(|s| {
+            for chunk in edges.chunks(edges.len() / 8 + 1) {
+                let uf = &uf;
+                s.spawn(move || {
+                    for (a, b) in chunk {
+                        uf.union(a, b);
+                        assert_eq!(Some(true), uf.equiv(a, b));
+                    }
+                });
+            }
+        });
+        assert_eq!(expected.num_classes(), uf.num_classes());
+        assert_eq!(None, uf.find(&n));
+        assert!(!uf.insert(0));
+        let uf = uf.into_union_find();
+        assert_eq!(expected.to_canonical_vec(), uf.to_canonical_vec());
+    }
+}
@@ -0,0 +1,262 @@
+use core::{cell::Cell, fmt::Debug};
+
+use crate::{small, wide, Small};
+
+/// One entry of the storage used by [StorageUnionFind].
+///
+/// Users supplying their own buffer to [BufferUnionFind] allocate these, e.g. as a
+/// `static` or stack array of `Slot::default()`.
+#[derive(Debug, Clone)]
+pub struct Slot<T> {
+    element: Option<T>,
+    // Index of the parent slot, a slot pointing at itself is a leader.
+    // Cell allows path compression from find(&self), like the hash-keyed UnionFind.
+    parent: Cell<Small>,
+    // Number of elements in the class, only meaningful on leaders.
+    size: Small,
+}
+
+impl<T> Default for Slot<T> {
+    fn default() -> Self {
+        Self {
+            element: None,
+            parent: Cell::new(0),
+            size: 1,
+        }
+    }
+}
+
+/// Backing storage of a [StorageUnionFind]: a fixed number of [Slot]s that is never resized.
+pub trait Storage {
+    type Element;
+
+    fn slots(&self) -> &[Slot<Self::Element>];
+
+    fn slots_mut(&mut self) -> &mut [Slot<Self::Element>];
+}
+
+impl<T, const N: usize> Storage for [Slot<T>; N] {
+    type Element = T;
+
+    fn slots(&self) -> &[Slot<T>] {
+        self
+    }
+
+    fn slots_mut(&mut self) -> &mut [Slot<T>] {
+        self
+    }
+}
+
+impl<T> Storage for &mut [Slot<T>] {
+    type Element = T;
+
+    fn slots(&self) -> &[Slot<T>] {
+        self
+    }
+
+    fn slots_mut(&mut self) -> &mut [Slot<T>] {
+        self
+    }
+}
+
+/// A union-find over a fixed amount of storage that never allocates.
+///
+/// Elements are located by linear search, so this is meant for small capacities (a few
+/// hundred), where a scan over the slots is cheap and no hashing or allocator is required.
+/// Inserting into a full structure fails and hands the element back instead of panicking.
+///
+/// Use it through [FixedUnionFind] for inline storage or [BufferUnionFind] for a
+/// user-supplied buffer.
+#[derive(Debug, Clone)]
+pub struct StorageUnionFind<S: Storage> {
+    slots: S,
+    len: usize,
+}
+
+/// A [StorageUnionFind] with inline, compile-time capacity `N`.
+///
+/// # Examples
+///
+/// ```
+/// use hash_unionfind::FixedUnionFind;
+///
+/// let mut uf: FixedUnionFind<u8, 2> = FixedUnionFind::new();
+/// uf.insert(1).unwrap();
+/// uf.insert(2).unwrap();
+/// assert_eq!(Err(3), uf.insert(3));
+///
+/// uf.union(&1, &2);
+/// assert_eq!(uf.find(&1), uf.find(&2));
+/// ```
+pub type FixedUnionFind<T, const N: usize> = StorageUnionFind<[Slot<T>; N]>;
+
+/// A [StorageUnionFind] over a buffer supplied by the caller, for targets without an allocator.
+///
+/// # Examples
+///
+/// ```
+/// use hash_unionfind::{BufferUnionFind, Slot};
+///
+/// let mut buffer: [Slot<u32>; 4] = Default::default();
+/// let mut uf = BufferUnionFind::new(&mut buffer);
+/// uf.insert(7).unwrap();
+/// uf.insert(8).unwrap();
+/// uf.union(&7, &8);
+/// assert_eq!(uf.find(&7), uf.find(&8));
+/// ```
+pub type BufferUnionFind<'a, T> = StorageUnionFind<&'a mut [Slot<T>]>;
+
+impl<T, const N: usize> Default for FixedUnionFind<T, N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T, const N: usize> FixedUnionFind<T, N> {
+    pub fn new() -> Self {
+        Self {
+            slots: core::array::from_fn(|_| Slot::default()),
+            len: 0,
+        }
+    }
+}
+
+impl<'a, T> BufferUnionFind<'a, T> {
+    /// Uses `buffer` as the storage, its length is the capacity. Previous contents are ignored.
+    pub fn new(buffer: &'a mut [Slot<T>]) -> Self {
+        Self {
+            slots: buffer,
+            len: 0,
+        }
+    }
+}
+
+impl<S: Storage> StorageUnionFind<S>
+where
+    S::Element: Eq + Clone + Debug,
+{
+    /// The number of elements currently stored.
+    pub fn size(&self) -> usize {
+        self.len
+    }
+
+    /// The maximal number of elements.
+    pub fn capacity(&self) -> usize {
+        self.slots.slots().len()
+    }
+
+    pub fn is_full(&self) -> bool {
+        self.len == self.capacity()
+    }
+
+    fn index_of(&self, t: &S::Element) -> Option<usize> {
+        self.slots.slots()[..self.len].iter().position(|s| s.element.as_ref() == Some(t))
+    }
+
+    /// Create a new set from the element t.
+    /// Inserting an existing element is a no-op. If the structure is full the element is
+    /// returned back as the error.
+    pub fn insert(&mut self, t: S::Element) -> Result<(), S::Element> {
+        if self.index_of(&t).is_some() {
+            return Ok(());
+        }
+        if self.is_full() {
+            return Err(t);
+        }
+        let i = self.len;
+        self.slots.slots_mut()[i] = Slot {
+            element: Some(t),
+            parent: Cell::new(small(i)),
+            size: 1,
+        };
+        self.len += 1;
+        Ok(())
+    }
+
+    fn find_index(&self, mut i: usize) -> usize {
+        let slots = self.slots.slots();
+        // Path halving keeps the walk iterative and the storage fixed.
+        while wide(slots[i].parent.get()) != i {
+            slots[i].parent.set(slots[wide(slots[i].parent.get())].parent.get());
+            i = wide(slots[i].parent.get());
+        }
+        i
+    }
+
+    /// Find the leader of the set that t is in.
+    pub fn find(&self, t: &S::Element) -> Option<&S::Element> {
+        let i = self.index_of(t)?;
+        let leader = self.find_index(i);
+        self.slots.slots()[leader].element.as_ref()
+    }
+
+    /// Given two elements, unions their classes making the bigger class the leader.
+    /// If one of the items is missing returns None.
+    pub fn union(&mut self, x: &S::Element, y: &S::Element) -> Option<&S::Element> {
+        let x = self.index_of(x)?;
+        let y = self.index_of(y)?;
+        let mut x = self.find_index(x);
+        let mut y = self.find_index(y);
+        let slots = self.slots.slots_mut();
+        if x != y {
+            if slots[y].size > slots[x].size {
+                core::mem::swap(&mut x, &mut y);
+            }
+            slots[y].parent.set(small(x));
+            slots[x].size += slots[y].size;
+        }
+        slots[x].element.as_ref()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fixed_union_find() {
+        let mut uf: FixedUnionFind<usize, 10> = FixedUnionFind::new();
+        for i in 0..10 {
+            uf.insert(i).unwrap();
+        }
+        assert!(uf.is_full());
+        assert_eq!(Ok(()), uf.insert(3));
+        assert_eq!(Err(10), uf.insert(10));
+
+        uf.union(&0, &1);
+        uf.union(&0, &2);
+        uf.union(&0, &3);
+        uf.union(&6, &7);
+        uf.union(&6, &8);
+        uf.union(&6, &9);
+
+        let expected = [0, 0, 0, 0, 4, 5, 6, 6, 6, 6];
+        for (i, leader) in expected.iter().enumerate() {
+            assert_eq!(uf.find(&i), Some(leader));
+        }
+        assert_eq!(None, uf.find(&10));
+        assert_eq!(None, uf.union(&0, &10));
+    }
+
+    #[test]
+    fn buffer_reuse() {
+        let mut buffer: [Slot<&str>; 3] = Default::default();
+        {
+            let mut uf = BufferUnionFind::new(&mut buffer);
+            uf.insert("a").unwrap();
+            uf.insert("b").unwrap();
+            uf.union(&"a", &"b");
+        }
+        // A new structure over the same buffer starts empty.
+        let mut uf = BufferUnionFind::new(&mut buffer);
+        assert_eq!(0, uf.size());
+        assert_eq!(None, uf.find(&"a"));
+        uf.insert("b").unwrap();
+        uf.insert("c").unwrap();
+        uf.insert("a").unwrap();
+        assert_eq!(Err("d"), uf.insert("d"));
+        assert_ne!(uf.find(&"a"), uf.find(&"b"));
+        uf.union(&"c", &"a");
+        assert_eq!(Some(&"c"), uf.find(&"a"));
+    }
+}
@@ -0,0 +1,185 @@
+use std::{fmt::Debug, hash::Hash};
+
+use tokio::sync::{mpsc, oneshot};
+use tokio_stream::{Stream, StreamExt};
+
+use crate::UnionFind;
+
+/// An operation applied by [Ingestor].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum UnionOp<T> {
+    Insert(T),
+    /// Unions the classes of both elements, inserting whichever is missing first.
+    Union(T, T),
+}
+
+/// The error returned by [QueryHandle] once the [Ingestor] finished and no longer answers queries.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct IngestClosed;
+
+enum Query<T> {
+    Find(T, oneshot::Sender<Option<T>>),
+    Equiv(T, T, oneshot::Sender<Option<bool>>),
+    NumClasses(oneshot::Sender<usize>),
+}
+
+/// Applies a stream of [UnionOp]s to a [UnionFind] it owns, answering [QueryHandle] queries in
+/// between operations.
+///
+/// The next operation is only pulled once the previous one was applied, so a slow consumer
+/// applies backpressure to the stream. Since [UnionFind] is not `Send`, the future returned by
+/// [Ingestor::run] has to be driven on the current thread, e.g. with
+/// `tokio::task::spawn_local` inside a `LocalSet`. The query handles are `Send` and can be
+/// used from any task.
+///
+/// # Examples
+///
+/// ```
+/// use hash_unionfind::{Ingestor, UnionFind, UnionOp};
+///
+/// let runtime = tokio::runtime::Builder::new_current_thread().build().unwrap();
+/// let local = tokio::task::LocalSet::new();
+/// let ops = tokio_stream::iter(vec![UnionOp::Union(1, 2), UnionOp::Insert(3)]);
+/// let (ingestor, handle) = Ingestor::new(UnionFind::new(), ops);
+/// let uf = local.block_on(&runtime, async move {
+///     let task = tokio::task::spawn_local(ingestor.run());
+///     let uf = task.await.unwrap();
+///     assert!(handle.num_classes().await.is_err());
+///     uf
+/// });
+/// assert_eq!(2, uf.num_classes());
+/// ```
+pub struct Ingestor<T: Hash + Eq + Clone + Debug, S> {
+    uf: UnionFind<T>,
+    ops: S,
+    queries: mpsc::Receiver<Query<T>>,
+}
+
+/// Queries the [UnionFind] owned by an [Ingestor] while it runs. Answers reflect every
+/// operation applied so far. Queries fail with [IngestClosed] once the stream is exhausted.
+#[derive(Debug)]
+pub struct QueryHandle<T> {
+    queries: mpsc::Sender<Query<T>>,
+}
+
+impl<T> Clone for QueryHandle<T> {
+    fn clone(&self) -> Self {
+        Self {
+            queries: self.queries.clone(),
+        }
+    }
+}
+
+// The number of pending queries before senders wait for the ingestor.
+const QUERY_BUFFER: usize = 64;
+
+impl<T, S> Ingestor<T, S>
+where
+    T: Hash + Eq + Clone + Debug,
+    S: Stream<Item = UnionOp<T>> + Unpin,
+{
+    /// Creates an ingestor applying `ops` to `uf`, and a handle to query it.
+    pub fn new(uf: UnionFind<T>, ops: S) -> (Self, QueryHandle<T>) {
+        let (sender, queries) = mpsc::channel(QUERY_BUFFER);
+        (Self { uf, ops, queries }, QueryHandle { queries: sender })
+    }
+
+    /// Applies operations until the stream ends and returns the resulting union-find.
+    /// Pending queries are answered before each operation.
+    pub async fn run(mut self) -> UnionFind<T> {
+        loop {
+            tokio::select! {
+                biased;
+                Some(query) = self.queries.recv() => self.answer(query),
+                op = self.ops.next() => match op {
+                    Some(op) => self.apply(op),
+                    None => break,
+                },
+            }
+        }
+        self.uf
+    }
+
+    fn apply(&mut self, op: UnionOp<T>) {
+        match op {
+            UnionOp::Insert(t) => {
+                self.uf.insert(t);
+            }
+            UnionOp::Union(x, y) => {
+                self.uf.union_insert(x, y);
+            }
+        }
+    }
+
+    fn answer(&self, query: Query<T>) {
+        // A dropped receiver means the asker is no longer interested.
+        match query {
+            Query::Find(t, reply) => {
+                let _ = reply.send(self.uf.find(&t).map(|leader| leader.as_ref().clone()));
+            }
+            Query::Equiv(x, y, reply) => {
+                let leaders = self.uf.find(&x).zip(self.uf.find(&y));
+                let _ = reply.send(leaders.map(|(x, y)| x == y));
+            }
+            Query::NumClasses(reply) => {
+                let _ = reply.send(self.uf.num_classes());
+            }
+        }
+    }
+}
+
+impl<T> QueryHandle<T> {
+    async fn ask<R>(&self, query: impl FnOnce(oneshot::Sender<R>) -> Query<T>) -> Result<R, IngestClosed> {
+        let (reply, answer) = oneshot::channel();
+        self.queries.send(query(reply)).await.map_err(|_| IngestClosed)?;
+        answer.await.map_err(|_| IngestClosed)
+    }
+
+    /// The leader of t's class, None if t was not inserted yet.
+    pub async fn find(&self, t: T) -> Result<Option<T>, IngestClosed> {
+        self.ask(|reply| Query::Find(t, reply)).await
+    }
+
+    /// Whether x and y are in the same class, None if one of them was not inserted yet.
+    pub async fn equiv(&self, x: T, y: T) -> Result<Option<bool>, IngestClosed> {
+        self.ask(|reply| Query::Equiv(x, y, reply)).await
+    }
+
+    pub async fn num_classes(&self) -> Result<usize, IngestClosed> {
+        self.ask(Query::NumClasses).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::task::LocalSet;
+    use tokio_stream::wrappers::ReceiverStream;
+
+    #[test]
+    fn ingest_with_queries() {
+        let runtime = tokio::runtime::Builder::new_current_thread().build().unwrap();
+        LocalSet::new().block_on(&runtime, async {
+            let (ops, stream) = mpsc::channel(1);
+            let (ingestor, handle) = Ingestor::new(UnionFind::new(), ReceiverStream::new(stream));
+            let task = tokio::task::spawn_local(ingestor.run());
+
+            ops.send(UnionOp::Union(1, 2)).await.unwrap();
+            ops.send(UnionOp::Insert(3)).await.unwrap();
+            ops.send(UnionOp::Union(2, 4)).await.unwrap();
+            // Operations are applied as soon as they leave the channel, so once there is
+            // room again all of them were applied.
+            ops.reserve().await.unwrap();
+            assert_eq!(Ok(Some(true)), handle.equiv(1, 4).await);
+            assert_eq!(Ok(Some(false)), handle.equiv(1, 3).await);
+            assert_eq!(Ok(None), handle.find(5).await);
+            assert_eq!(Ok(2), handle.num_classes().await);
+
+            drop(ops);
+            let uf = task.await.unwrap();
+            assert_eq!(4, uf.size());
+            assert_eq!(uf.find(&1), uf.find(&4));
+            assert_eq!(Err(IngestClosed), handle.clone().find(1).await);
+        });
+    }
+}
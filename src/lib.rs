@@ -1,3 +1,103 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+#[cfg(feature = "std")]
+mod atomic_forest;
+#[cfg(feature = "std")]
+mod backend;
+#[cfg(feature = "concurrent")]
+mod concurrent;
+mod fixed;
+#[cfg(feature = "tokio")]
+mod ingest;
+#[cfg(feature = "std")]
+mod partition;
+#[cfg(feature = "opentelemetry")]
+mod telemetry;
+#[cfg(feature = "std")]
 mod unionfind;
 
-pub use unionfind::UnionFind;
\ No newline at end of file
+#[cfg(feature = "std")]
+pub use backend::{Backend, DenseBackend, DenseKey, HashBackend};
+#[cfg(feature = "concurrent")]
+pub use concurrent::ConcurrentUnionFind;
+pub use fixed::{BufferUnionFind, FixedUnionFind, Slot, Storage, StorageUnionFind};
+#[cfg(feature = "std")]
+pub use partition::{NaivePartition, Partition};
+#[cfg(feature = "std")]
+pub use unionfind::{
+    UnionFind, Agglomerative, ArcUnionFind, ByMin, ByRank, BySize, CategoryCounts, Checkpoint, CheckpointOpen,
+    ClassCache, ClassInfo, ClassState, ComponentCounts, Contradiction, DataUnionFind, DedupByClass, EqModulo,
+    Fingerprint, FingerprintCollision, FingerprintUnionFind, FrozenPartition, HashModulo, Histogram, Id, IntoPairs,
+    Justification, KeepFirst, KeyError, LeaderHandle, MergeEvent, MergeHistory, MergeNode, MinLeaderUnionFind,
+    OffsetConflict, OutlivesSolver, ParityUnionFind, PartitionMismatch, ProofUnionFind, QuotientMap, Relation, Stats,
+    UnionFindIterExt, UnionPolicy, Update, WeightedUnionFind,
+};
+#[cfg(feature = "arc-swap")]
+pub use unionfind::SnapshotPublisher;
+#[cfg(feature = "persistent")]
+pub use unionfind::PersistentUnionFind;
+#[cfg(feature = "std")]
+pub use unionfind::connectivity;
+#[cfg(feature = "serde")]
+pub use unionfind::groups as serde_groups;
+#[cfg(feature = "ffi")]
+pub use unionfind::ffi;
+#[cfg(feature = "derive")]
+pub use hash_unionfind_derive::UnionFindKey;
+#[cfg(feature = "tokio")]
+pub use ingest::{IngestClosed, Ingestor, QueryHandle, UnionOp};
+#[cfg(feature = "opentelemetry")]
+pub use telemetry::StatsExporter;
+
+/// Builds a [UnionFind] with the given classes, the first element of every class leading it.
+///
+/// Classes are written as braced, comma separated lists of expressions. They are expected to be
+/// disjoint; classes sharing an element end up merged.
+///
+/// # Examples
+///
+/// ```
+/// use hash_unionfind::uf;
+///
+/// let uf = uf! { {"a", "b", "c"}, {"d", "e"}, {"f"} };
+/// assert_eq!(3, uf.num_classes());
+/// assert_eq!(Some("a"), uf.find(&"c").as_deref().copied());
+/// assert_eq!(uf.find(&"d"), uf.find(&"e"));
+/// ```
+#[cfg(feature = "std")]
+#[macro_export]
+macro_rules! uf {
+    ($({ $first:expr $(, $member:expr)* $(,)? }),* $(,)?) => {{
+        #[allow(unused_mut)]
+        let mut uf = $crate::UnionFind::new();
+        $(
+            let first = $first;
+            uf.insert(::core::clone::Clone::clone(&first));
+            $(
+                let member = $member;
+                uf.insert(::core::clone::Clone::clone(&member));
+                uf.union(&first, &member);
+            )*
+        )*
+        uf
+    }};
+}
+
+// Per-entry indices, sizes and ranks. The `compact` feature halves them on 64-bit targets.
+#[cfg(feature = "compact")]
+pub(crate) type Small = u32;
+#[cfg(not(feature = "compact"))]
+pub(crate) type Small = usize;
+
+// Converts a count or index into [Small], panicking instead of silently truncating.
+pub(crate) fn small(n: usize) -> Small {
+    #[allow(clippy::useless_conversion, clippy::unnecessary_fallible_conversions)]
+    Small::try_from(n).expect("index does not fit the compact representation")
+}
+
+// Widens [Small] back for indexing.
+pub(crate) fn wide(n: Small) -> usize {
+    #[allow(clippy::unnecessary_cast)]
+    let n = n as usize;
+    n
+}
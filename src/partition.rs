@@ -0,0 +1,180 @@
+use std::{collections::HashSet, fmt::Debug, hash::Hash};
+
+use crate::UnionFind;
+
+/// The operations shared by the partition structures of this crate, so they can be used and
+/// tested interchangeably.
+pub trait Partition<T> {
+    /// Create a new set from the element t, doing nothing if it already exists.
+    fn insert(&mut self, t: T);
+
+    /// Unions the classes of x and y. Returns false if one of them is missing.
+    fn union(&mut self, x: &T, y: &T) -> bool;
+
+    /// Whether x and y are in the same class, None if one of them is missing.
+    fn equiv(&self, x: &T, y: &T) -> Option<bool>;
+
+    fn contains(&self, t: &T) -> bool;
+
+    /// The number of elements.
+    fn size(&self) -> usize;
+
+    fn num_classes(&self) -> usize;
+
+    /// All classes with their members, in unspecified order.
+    fn blocks(&self) -> Vec<Vec<T>>;
+}
+
+impl<T: Hash + Eq + Clone + Debug> Partition<T> for UnionFind<T> {
+    fn insert(&mut self, t: T) {
+        UnionFind::insert(self, t);
+    }
+
+    fn union(&mut self, x: &T, y: &T) -> bool {
+        UnionFind::union(self, x, y).is_some()
+    }
+
+    fn equiv(&self, x: &T, y: &T) -> Option<bool> {
+        Some(self.find(x)? == self.find(y)?)
+    }
+
+    fn contains(&self, t: &T) -> bool {
+        self.find(t).is_some()
+    }
+
+    fn size(&self) -> usize {
+        UnionFind::size(self)
+    }
+
+    fn num_classes(&self) -> usize {
+        UnionFind::num_classes(self)
+    }
+
+    fn blocks(&self) -> Vec<Vec<T>> {
+        self.group_by(self.elements(), |t| t).map(|(_, members)| members).collect()
+    }
+}
+
+/// A deliberately simple partition, a list of disjoint sets, which is obviously correct and
+/// slow (every operation is linear). It serves as a reference implementation for
+/// differential and model based tests of the optimized structures.
+#[derive(Debug, Clone)]
+pub struct NaivePartition<T> {
+    sets: Vec<HashSet<T>>,
+}
+
+impl<T> Default for NaivePartition<T> {
+    fn default() -> Self {
+        Self { sets: Vec::new() }
+    }
+}
+
+impl<T: Hash + Eq> NaivePartition<T> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn position(&self, t: &T) -> Option<usize> {
+        self.sets.iter().position(|s| s.contains(t))
+    }
+}
+
+impl<T: Hash + Eq + Clone> Partition<T> for NaivePartition<T> {
+    fn insert(&mut self, t: T) {
+        if !self.contains(&t) {
+            self.sets.push(HashSet::from([t]));
+        }
+    }
+
+    fn union(&mut self, x: &T, y: &T) -> bool {
+        let (Some(x), Some(y)) = (self.position(x), self.position(y)) else {
+            return false;
+        };
+        if x != y {
+            let absorbed = self.sets.swap_remove(x.max(y));
+            self.sets[x.min(y)].extend(absorbed);
+        }
+        true
+    }
+
+    fn equiv(&self, x: &T, y: &T) -> Option<bool> {
+        Some(self.position(x)? == self.position(y)?)
+    }
+
+    fn contains(&self, t: &T) -> bool {
+        self.position(t).is_some()
+    }
+
+    fn size(&self) -> usize {
+        self.sets.iter().map(|s| s.len()).sum()
+    }
+
+    fn num_classes(&self) -> usize {
+        self.sets.len()
+    }
+
+    fn blocks(&self) -> Vec<Vec<T>> {
+        self.sets.iter().map(|s| s.iter().cloned().collect()).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Sorted blocks, so partitions can be compared regardless of order.
+    fn normalized<P: Partition<u32>>(p: &P) -> Vec<Vec<u32>> {
+        let mut blocks = p.blocks();
+        for b in &mut blocks {
+            b.sort();
+        }
+        blocks.sort();
+        blocks
+    }
+
+    fn differential<P: Partition<u32> + Default>(seed: u64) {
+        let mut state = seed;
+        let mut next = |bound: u64| {
+            state = state.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+            ((state >> 33) % bound) as u32
+        };
+        let mut model = NaivePartition::new();
+        let mut tested = P::default();
+        for _ in 0..400 {
+            let (x, y) = (next(60), next(60));
+            match next(3) {
+                0 => {
+                    model.insert(x);
+                    tested.insert(x);
+                }
+                1 => assert_eq!(model.union(&x, &y), tested.union(&x, &y)),
+                _ => assert_eq!(model.equiv(&x, &y), tested.equiv(&x, &y)),
+            }
+            assert_eq!(model.contains(&x), tested.contains(&x));
+            assert_eq!(model.size(), tested.size());
+            assert_eq!(model.num_classes(), tested.num_classes());
+        }
+        assert_eq!(normalized(&model), normalized(&tested));
+    }
+
+    #[test]
+    fn union_find_matches_naive() {
+        for seed in 0..20 {
+            differential::<UnionFind<u32>>(seed);
+        }
+    }
+
+    #[test]
+    fn naive_partition() {
+        let mut p = NaivePartition::new();
+        p.insert(1);
+        p.insert(2);
+        p.insert(3);
+        assert!(p.union(&1, &3));
+        assert!(!p.union(&1, &4));
+        assert_eq!(Some(true), p.equiv(&3, &1));
+        assert_eq!(Some(false), p.equiv(&2, &1));
+        assert_eq!(None, p.equiv(&2, &4));
+        assert_eq!(vec![vec![1, 3], vec![2]], normalized(&p));
+    }
+}
@@ -0,0 +1,131 @@
+use std::{cell::RefCell, fmt::Debug, hash::Hash, rc::Rc, time::Duration};
+
+use opentelemetry::metrics::{Counter, Gauge, Meter};
+
+use crate::{Backend, Stats, UnionFind};
+
+/// Publishes [Stats] of a [UnionFind] as OpenTelemetry metrics on a [Meter].
+///
+/// Elements and classes are gauges, merges a monotonic counter from which the backend
+/// derives the merge rate. These are kept as counters by the union-find and recorded on
+/// every tick. The largest class and the average find depth are gauges as well, but they
+/// come from [UnionFind::stats], which walks every element, so they are only recorded
+/// once asked for with [StatsExporter::full_stats_every]. Metric names are prefixed with
+/// `unionfind.`.
+///
+/// # Examples
+///
+/// ```
+/// use std::{cell::RefCell, rc::Rc, time::Duration};
+/// use hash_unionfind::{DenseBackend, StatsExporter, UnionFind};
+///
+/// let uf = Rc::new(RefCell::new(UnionFind::with_backend(DenseBackend::default())));
+/// // The counters every tick, the walk over all elements every hundredth.
+/// let exporter = StatsExporter::new(&opentelemetry::global::meter("dedup")).full_stats_every(100);
+///
+/// let runtime = tokio::runtime::Builder::new_current_thread().enable_time().build().unwrap();
+/// let local = tokio::task::LocalSet::new();
+/// local.block_on(&runtime, async {
+///     let task = tokio::task::spawn_local(exporter.run(uf.clone(), Duration::from_millis(10)));
+///     uf.borrow_mut().insert_many(0u32..2);
+///     uf.borrow_mut().union(&0, &1);
+///     // The exporter stops once it holds the last reference.
+///     drop(uf);
+///     task.await.unwrap();
+/// });
+/// ```
+#[derive(Debug)]
+pub struct StatsExporter {
+    elements: Gauge<u64>,
+    classes: Gauge<u64>,
+    largest_class: Gauge<u64>,
+    average_find_depth: Gauge<f64>,
+    merges: Counter<u64>,
+    // The merge count already added to the counter.
+    exported_merges: u64,
+    // Every how many ticks run records the full stats, 0 for never.
+    full_stats_every: u32,
+}
+
+impl StatsExporter {
+    pub fn new(meter: &Meter) -> Self {
+        Self {
+            elements: meter.u64_gauge("unionfind.elements").with_description("Elements in the union-find").init(),
+            classes: meter.u64_gauge("unionfind.classes").with_description("Disjoint classes").init(),
+            largest_class: meter
+                .u64_gauge("unionfind.largest_class")
+                .with_description("Elements in the largest class")
+                .init(),
+            average_find_depth: meter
+                .f64_gauge("unionfind.average_find_depth")
+                .with_description("Mean number of parents between an element and its leader")
+                .init(),
+            merges: meter.u64_counter("unionfind.merges").with_description("Effective unions").init(),
+            exported_merges: 0,
+            full_stats_every: 0,
+        }
+    }
+
+    /// Makes [StatsExporter::run] record the full stats on the first tick and every ticks
+    /// ticks after, instead of the counters alone. Each time takes time proportional to the
+    /// total find depth, see [UnionFind::stats]. 0, the default, never records them.
+    pub fn full_stats_every(mut self, ticks: u32) -> Self {
+        self.full_stats_every = ticks;
+        self
+    }
+
+    /// Records one set of stats. The merge counter grows by the merges since the last
+    /// record, so all records should come from the same union-find.
+    pub fn record(&mut self, stats: &Stats) {
+        self.elements.record(stats.elements as u64, &[]);
+        self.classes.record(stats.classes as u64, &[]);
+        self.largest_class.record(stats.largest_class as u64, &[]);
+        self.average_find_depth.record(stats.average_find_depth, &[]);
+        self.add_merges(stats.merges);
+    }
+
+    /// Records the elements, classes and merges of uf, which takes O(1), leaving the largest
+    /// class and the average find depth as they were last recorded. Like [StatsExporter::record],
+    /// all records should come from the same union-find.
+    pub fn record_counters<T: Hash + Eq + Clone + Debug, B: Backend<Key = T>>(&mut self, uf: &UnionFind<T, B>) {
+        self.elements.record(uf.size() as u64, &[]);
+        self.classes.record(uf.num_classes() as u64, &[]);
+        self.add_merges(uf.num_merges());
+    }
+
+    fn add_merges(&mut self, merges: u64) {
+        self.merges.add(merges.saturating_sub(self.exported_merges), &[]);
+        self.exported_merges = self.exported_merges.max(merges);
+    }
+
+    /// Records the counters of uf every period, and its full stats as often as asked for with
+    /// [StatsExporter::full_stats_every], until the exporter holds the last reference to it.
+    /// Ticks that find uf mutably borrowed are skipped.
+    ///
+    /// As [UnionFind] is not `Send`, the future has to be driven on the thread owning uf,
+    /// e.g. with `tokio::task::spawn_local`. The runtime needs the time driver enabled.
+    pub async fn run<T: Hash + Eq + Clone + Debug, B: Backend<Key = T>>(
+        mut self,
+        uf: Rc<RefCell<UnionFind<T, B>>>,
+        period: Duration,
+    ) {
+        let mut ticks = tokio::time::interval(period);
+        ticks.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+        // Recorded ticks until the next full stats.
+        let mut until_full = 0;
+        while Rc::strong_count(&uf) > 1 {
+            ticks.tick().await;
+            if let Ok(uf) = uf.try_borrow() {
+                if self.full_stats_every == 0 {
+                    self.record_counters(&uf);
+                } else if until_full == 0 {
+                    self.record(&uf.stats());
+                    until_full = self.full_stats_every - 1;
+                } else {
+                    self.record_counters(&uf);
+                    until_full -= 1;
+                }
+            }
+        }
+    }
+}
@@ -1,13 +1,186 @@
-use std::{fmt::Debug, hash::Hash, cell::RefCell, rc::Rc};
+use std::{
+    borrow::Borrow,
+    cell::{OnceCell, Ref, RefCell, RefMut},
+    collections::hash_map::RandomState,
+    fmt::Debug,
+    hash::{BuildHasher, Hash},
+    ops::Deref,
+    rc::Rc,
+};
 use indexmap::IndexMap;
 
+use crate::{Backend, HashBackend};
+use forest::Forest;
 
-type Rank = usize;
+mod arc;
+mod balance;
+mod bulk;
+mod cache;
+mod checkpoint;
+mod clustering;
+mod compare;
+pub mod connectivity;
+mod counts;
+mod data;
+mod dirty;
+mod dot;
+mod events;
+mod explain;
+#[cfg(feature = "ffi")]
+pub mod ffi;
+mod fingerprint;
+mod forest;
+mod frozen;
+mod grouping;
+mod handle;
+#[cfg(feature = "serde")]
+pub mod groups;
+mod histogram;
+mod history;
+mod id;
+mod iter;
+mod members;
+mod min;
+mod modulo;
+mod offsets;
+mod outlives;
+mod parity;
+mod stats;
+mod updates;
+mod validate;
+mod verify;
+mod weights;
+#[cfg(feature = "ascent")]
+mod lattice;
+mod parallel;
+mod policy;
+#[cfg(feature = "persistent")]
+mod persistent;
+mod quotient;
+mod remove;
+#[cfg(feature = "rand")]
+mod sample;
+
+pub use arc::ArcUnionFind;
+pub use balance::ClassInfo;
+pub use cache::ClassCache;
+pub use checkpoint::{Checkpoint, CheckpointOpen};
+pub use clustering::Agglomerative;
+pub use counts::ComponentCounts;
+pub use data::DataUnionFind;
+pub use events::MergeEvent;
+pub use explain::{Justification, ProofUnionFind};
+pub use fingerprint::{Fingerprint, FingerprintCollision, FingerprintUnionFind};
+pub use frozen::FrozenPartition;
+#[cfg(feature = "arc-swap")]
+pub use frozen::SnapshotPublisher;
+pub use grouping::{DedupByClass, UnionFindIterExt};
+pub use handle::LeaderHandle;
+pub use histogram::{CategoryCounts, Histogram};
+pub use history::{ClassState, MergeHistory, MergeNode};
+pub use id::Id;
+pub use iter::IntoPairs;
+pub use min::MinLeaderUnionFind;
+pub use modulo::{EqModulo, HashModulo};
+pub use offsets::{OffsetConflict, WeightedUnionFind};
+pub use outlives::{Contradiction, OutlivesSolver};
+pub use parity::{ParityUnionFind, Relation};
+#[cfg(feature = "persistent")]
+pub use persistent::PersistentUnionFind;
+pub use policy::{ByMin, ByRank, BySize, KeepFirst, UnionPolicy};
+pub use quotient::QuotientMap;
+pub use stats::Stats;
+pub use updates::Update;
+pub use validate::KeyError;
+pub use verify::PartitionMismatch;
+
+// The number of queries find_batch walks up the forest together, enough independent reads
+// to keep the memory system busy without spilling the lanes out of registers.
+const BATCH_LANES: usize = 16;
+
+type Index = crate::Small;
+type Rank = crate::Small;
+type Size = crate::Small;
+
+#[derive(Debug, Clone)]
+struct Node<T> {
+    // The index of the maybe updated leader.
+    parent: Index,
+    // Upper bound on the height of the tree, only meaningful on leaders.
+    rank: Rank,
+    // The number of elements in the class, only meaningful on leaders.
+    size: Size,
+    // Bumped on every change to the class, only meaningful on leaders.
+    version: u64,
+    // The index of the next member of the class, members form a cycle spliced together by union.
+    next: Index,
+    // A shared copy of the key, only made once the element is handed out as a leader, so the
+    // keys of the other elements are stored once.
+    handle: OnceCell<Rc<T>>,
+}
+
+impl<T> Node<T> {
+    fn new(index: usize) -> Self {
+        let index = crate::small(index);
+        Self {
+            parent: index,
+            next: index,
+            rank: 0,
+            size: 1,
+            version: 0,
+            handle: OnceCell::new(),
+        }
+    }
+}
+
+// The map as borrowed by a read API: exclusively when free, so walks compress, and shared
+// when something up the stack, such as a callback run by one of our own methods, still
+// holds a shared borrow, so reentrant reads walk without compressing instead of panicking.
+enum Access<'a, T, B> {
+    Exclusive(RefMut<'a, Forest<T, B>>),
+    Shared(Ref<'a, Forest<T, B>>),
+}
+
+impl<T, B> Deref for Access<'_, T, B> {
+    type Target = Forest<T, B>;
+
+    fn deref(&self) -> &Forest<T, B> {
+        match self {
+            Access::Exclusive(ps) => ps,
+            Access::Shared(ps) => ps,
+        }
+    }
+}
+
+impl<T: Hash + Eq + Clone + Debug, B: Backend<Key = T>> Access<'_, T, B> {
+    // The index of the leader of index, compressing the path when exclusive.
+    fn leader(&mut self, index: usize) -> usize {
+        match self {
+            Access::Exclusive(ps) => UnionFind::find_index_in(ps, index),
+            Access::Shared(ps) => UnionFind::root_index_in(ps, index),
+        }
+    }
+
+    // Points the path from index straight at its leader when exclusive.
+    fn compress(&mut self, index: usize, leader: usize) {
+        if let Access::Exclusive(ps) = self {
+            UnionFind::compress_in(ps, index, leader);
+        }
+    }
+}
 
 /// A type that can be used as an id in a union-find data structure.
 /// 
 /// This trait is implemented for hashable types, as a way to have a single object unionfind on complex data.
 /// 
+/// Lookups take any borrowed form of the key, as [HashMap::get](std::collections::HashMap::get)
+/// does. Unsized keys such as `str` or `[u8]` are stored behind a shared pointer, so a
+/// `UnionFind<Rc<str>>` is queried with plain `&str` slices.
+/// 
+/// Keys live in a [Backend], which maps them to the positions the forest links by. The
+/// default [HashBackend] takes any hashable key, [UnionFind::with_backend] picks another
+/// layout such as [DenseBackend](crate::DenseBackend) for small integer keys.
+/// 
 /// # Examples
 /// 
 /// ```
@@ -31,17 +204,113 @@ type Rank = usize;
 /// uf.union(&"a", &"d");
 /// 
 /// assert_eq!(uf.find(&"a"), uf.find(&"e"));
-#[derive(Debug, Clone, Default)]
-#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
-pub struct UnionFind<T: Hash + Eq + Clone + Debug> {
-    // The parents of each node. The index is T and we keep the maybe updated leader + rank.
-    parents: RefCell<IndexMap<T, (Rc<T>, Rank)>>,
+#[derive(Debug, Clone)]
+pub struct UnionFind<T: Hash + Eq + Clone + Debug, B = HashBackend<T>> {
+    // The parents of each node. Every key is stored once, in the backend, and the forest
+    // links nodes by the position of their key, with the class data on leaders.
+    parents: RefCell<Forest<T, B>>,
+    // The number of disjoint classes, kept up to date by insert and union.
+    classes: usize,
+    // Source of class versions, increased on every effective union, split and rollback.
+    clock: u64,
+    // The number of effective unions since creation, increased by link alone.
+    unions: u64,
+    // Element and class weights by index, only kept once an element was weighted.
+    weights: Option<Vec<weights::Weight>>,
+    // Leaders that lost leadership since the last take_leader_remap, only kept once
+    // track_leader_remap was called.
+    deposed: Option<Vec<Index>>,
+    // Leaders of classes created or changed since the last take_dirty, maybe deposed since,
+    // only kept once track_dirty was called.
+    dirty: Option<Vec<Index>>,
+    // Merge lineage, only kept once record_history was called.
+    history: Option<MergeHistory<T>>,
+    // Pending changes to the element to leader relation, only kept once track_updates was called.
+    updates: Option<Vec<Update<T>>>,
+    // Members of every class in a stable order by leader, only kept once index_members was called.
+    member_index: Option<IndexMap<Index, Vec<Index>>>,
+    // Effective unions since the last take_merges, only kept once track_merges was called.
+    merges: Option<Vec<MergeEvent<T>>>,
+    // Changes since the oldest open checkpoint, only kept while one is open.
+    journal: Option<checkpoint::Journal>,
 }
 
 impl<T: Hash + Eq + Clone + Debug> UnionFind<T> {
     pub fn new() -> Self {
+        Self::with_hasher(RandomState::new())
+    }
+
+    /// An empty union-find with room for capacity elements before it reallocates.
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self::with_capacity_and_hasher(capacity, RandomState::new())
+    }
+}
+
+impl<T: Hash + Eq + Clone + Debug> Default for UnionFind<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Hash + Eq + Clone + Debug, S: BuildHasher> UnionFind<T, HashBackend<T, S>> {
+    /// An empty union-find hashing its elements with hasher, for keys whose hashing
+    /// dominates, or to make iteration order independent of the process.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::{collections::hash_map::DefaultHasher, hash::BuildHasherDefault};
+    /// use hash_unionfind::UnionFind;
+    ///
+    /// let mut uf = UnionFind::with_hasher(BuildHasherDefault::<DefaultHasher>::default());
+    /// uf.insert_many(0..4);
+    /// uf.union(&0, &3);
+    /// assert_eq!(uf.find(&0), uf.find(&3));
+    /// ```
+    pub fn with_hasher(hasher: S) -> Self {
+        Self::with_capacity_and_hasher(0, hasher)
+    }
+
+    /// An empty union-find hashing with hasher, with room for capacity elements.
+    pub fn with_capacity_and_hasher(capacity: usize, hasher: S) -> Self {
+        Self::with_backend(HashBackend::with_capacity_and_hasher(capacity, hasher))
+    }
+}
+
+impl<T: Hash + Eq + Clone + Debug, B: Backend<Key = T>> UnionFind<T, B> {
+    /// A union-find keeping its keys in backend, in which the keys already there are
+    /// singletons.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use hash_unionfind::{DenseBackend, UnionFind};
+    ///
+    /// let mut uf = UnionFind::with_backend(DenseBackend::default());
+    /// uf.insert_many(0u32..4);
+    /// uf.union(&0, &3);
+    /// assert_eq!(uf.find(&0), uf.find(&3));
+    /// ```
+    pub fn with_backend(backend: B) -> Self {
+        Self::from_forest(Forest::new(backend))
+    }
+
+    // A union-find over a forest built elsewhere.
+    fn from_forest(parents: Forest<T, B>) -> Self {
+        let classes = (0..parents.len()).filter(|&i| crate::wide(parents[i].parent) == i).count();
         Self {
-            parents: RefCell::new(IndexMap::new()),
+            classes,
+            unions: (parents.len() - classes) as u64,
+            dirty: None,
+            parents: RefCell::new(parents),
+            clock: 0,
+            weights: None,
+            deposed: None,
+            history: None,
+            updates: None,
+            member_index: None,
+            merges: None,
+            journal: None,
         }
     }
 
@@ -49,69 +318,631 @@ impl<T: Hash + Eq + Clone + Debug> UnionFind<T> {
         self.parents.borrow().len()
     }
 
-    // Create a new set from the element t.
-    pub fn insert(&mut self, t: T) {
-        if self.parents.borrow().contains_key(&t) {
-            return;
+    /// The number of elements the union-find can hold without reallocating.
+    pub fn capacity(&self) -> usize {
+        self.parents.borrow().capacity()
+    }
+
+    /// Reserves room for at least additional more elements, to pay for growing the map once
+    /// before a bulk insertion. [UnionFind::insert_many] reserves from the size hint itself.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use hash_unionfind::UnionFind;
+    ///
+    /// let mut uf = UnionFind::with_capacity(10);
+    /// uf.reserve(1000);
+    /// assert!(uf.capacity() >= 1000);
+    /// for i in 0..1000 {
+    ///     uf.insert(i);
+    /// }
+    /// ```
+    pub fn reserve(&mut self, additional: usize) {
+        self.parents.get_mut().reserve(additional);
+    }
+
+    // Clones of all elements, in insertion order.
+    pub(crate) fn elements(&self) -> Vec<T> {
+        self.parents.borrow().keys().cloned().collect()
+    }
+
+    /// The number of disjoint classes. This is maintained incrementally and is O(1).
+    pub fn num_classes(&self) -> usize {
+        self.classes
+    }
+
+    /// The number of effective unions since creation, as in [Stats::merges], but O(1) as it
+    /// is kept as a counter.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use hash_unionfind::UnionFind;
+    ///
+    /// let mut uf = UnionFind::new();
+    /// uf.insert_many(0..4);
+    /// uf.union(&0, &1);
+    /// uf.union(&1, &0);
+    /// assert_eq!(1, uf.num_merges());
+    /// assert_eq!(uf.stats().merges, uf.num_merges());
+    /// ```
+    pub fn num_merges(&self) -> u64 {
+        self.unions
+    }
+
+    /// Whether all elements are in one class, false when there are none, for loops that merge
+    /// until everything is connected.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use hash_unionfind::UnionFind;
+    ///
+    /// let mut uf = UnionFind::new();
+    /// assert!(!uf.is_single_class());
+    /// uf.insert_many(0..3);
+    /// let mut edges = [(0, 1), (1, 2), (0, 2)].into_iter();
+    /// while !uf.is_single_class() {
+    ///     let (x, y) = edges.next().unwrap();
+    ///     uf.union(&x, &y);
+    /// }
+    /// assert_eq!(Some((0, 2)), edges.next());
+    /// ```
+    pub fn is_single_class(&self) -> bool {
+        self.classes == 1
+    }
+
+    // Create a new set from the element t, returning its handle, see [Id].
+    pub fn insert(&mut self, t: T) -> Id {
+        let ps = self.parents.get_mut();
+        let (index, new) = ps.insert_full(t);
+        if !new {
+            return Id::new(index);
+        }
+        #[cfg(debug_assertions)]
+        Self::check_new_key(ps.get_index(index).unwrap().0);
+        if let Some(updates) = &mut self.updates {
+            let key = ps.get_index(index).unwrap().0;
+            updates.push(((key.clone(), key.clone()), 1));
         }
-        let rc_t = Rc::new(t.clone());
-        self.parents.borrow_mut().insert(t, (rc_t, 1));
+        if let Some(member_index) = &mut self.member_index {
+            member_index.insert(crate::small(index), vec![crate::small(index)]);
+        }
+        if let Some(weights) = &mut self.weights {
+            weights.push(weights::Weight::default());
+        }
+        self.mark_dirty(index);
+        self.classes += 1;
+        self.journal_insert();
+        Id::new(index)
     }
 
-    fn inner_find(&self, current: &T) -> Option<(Rc<T>, Rank)> {
-        // If the current node is not in the map, it is not in the union-find.
-        // All other cases node will point to parent or itself.
-        if !self.parents.borrow().contains_key(current) {
-            return None;
+    /// Inserts every element of `iter` as a singleton, pre-sizing the map from the iterator's
+    /// size hint. Returns how many of the elements were new.
+    #[doc(alias = "insert_all")]
+    pub fn insert_many<I: IntoIterator<Item = T>>(&mut self, iter: I) -> usize {
+        let iter = iter.into_iter();
+        self.reserve(iter.size_hint().0);
+        let before = self.size();
+        for t in iter {
+            self.insert(t);
         }
+        self.size() - before
+    }
 
-        let mut ps = self.parents.borrow_mut();
-        let mut old = current;
-        let mut current = &ps[old].0;
-        let mut current_rank = &ps[old].1;
-        let mut to_update = vec![];
-        while current.as_ref() != old {
-            to_update.push(old.clone());
-            old = current.as_ref();
-            current = &ps[old].0;
-            current_rank = &ps[old].1;
+    // Borrows the map for a read API, see Access.
+    fn access(&self) -> Access<'_, T, B> {
+        match self.parents.try_borrow_mut() {
+            Ok(ps) => Access::Exclusive(ps),
+            Err(_) => Access::Shared(self.parents.try_borrow().unwrap_or_else(|_| validate::reentered())),
         }
-        
-        let current = current.clone();
-        let current_rank = *current_rank;
-        for u in to_update {
-            // It is actually unneccessary to update rank
-            ps.insert(u.clone(), (current.clone(), current_rank));
+    }
+
+    // Finds the index of the leader of current and reads the map at it, compressing only
+    // when the map is free, see Access.
+    fn with_leader<Q, R>(&self, current: &Q, read: impl FnOnce(&Forest<T, B>, usize) -> R) -> Option<R>
+    where
+        Q: Hash + Eq + ?Sized,
+        T: Borrow<Q>,
+    {
+        let mut ps = self.access();
+        let index = ps.get_index_of(current)?;
+        let leader = ps.leader(index);
+        Some(read(&ps, leader))
+    }
+
+    // The shared copy of the key at index, made on first use. Only leaders keep theirs, as
+    // there are few of them and they are returned over and over, and link drops it.
+    fn handle_in(ps: &Forest<T, B>, index: usize) -> &Rc<T> {
+        let (key, node) = ps.get_index(index).unwrap();
+        node.handle.get_or_init(|| Rc::new(key.clone()))
+    }
+
+    // A shared copy of the key at index for members, reusing the handle it has, if any,
+    // without keeping a new one.
+    fn copy_in(ps: &Forest<T, B>, index: usize) -> Rc<T> {
+        let (key, node) = ps.get_index(index).unwrap();
+        node.handle.get().cloned().unwrap_or_else(|| Rc::new(key.clone()))
+    }
+
+    // The index of the leader of the element at index, with path compression on an already
+    // borrowed map. It only rewrites indices, no key is hashed, compared or cloned.
+    fn find_index_in(ps: &mut Forest<T, B>, index: usize) -> usize {
+        let leader = Self::root_index_in(ps, index);
+        Self::compress_in(ps, index, leader);
+        leader
+    }
+
+    // Points every element on the path from index to its leader straight at the leader.
+    fn compress_in(ps: &mut Forest<T, B>, mut index: usize, leader: usize) {
+        while index != leader {
+            let node = &mut ps[index];
+            index = crate::wide(std::mem::replace(&mut node.parent, crate::small(leader)));
+        }
+    }
+
+    // The indices of all members of the class led by leader, following the member cycle.
+    fn member_indices_in(ps: &Forest<T, B>, leader: usize) -> Vec<usize> {
+        let mut members = vec![leader];
+        let mut current = crate::wide(ps[leader].next);
+        while current != leader {
+            members.push(current);
+            current = crate::wide(ps[current].next);
+        }
+        members
+    }
+
+    // Walks to the leader without compressing, for use under a shared borrow.
+    fn root_index_in(ps: &Forest<T, B>, mut index: usize) -> usize {
+        loop {
+            let parent = crate::wide(ps[index].parent);
+            if parent == index {
+                return index;
+            }
+            index = parent;
         }
-        
-        Some((current, current_rank))
+    }
+
+    // Like root_index_in from a key, returning the shared copy of the leader.
+    fn root_in<'a, Q: Hash + Eq + ?Sized>(ps: &'a Forest<T, B>, current: &Q) -> Option<&'a Rc<T>>
+    where
+        T: Borrow<Q>,
+    {
+        Some(Self::handle_in(ps, Self::root_index_in(ps, ps.get_index_of(current)?)))
     }
 
     // Find the leader of the set that t is in. This is amortized to O(log*(n))
-    // This uses [RefCell], and is therefore unsafe to call concurrently.
-    // TODO: Make this safe to call concurrently using atomic keys.
-    pub fn find(&self, current: &T) -> Option<Rc<T>> {
-        self.inner_find(current).map(|(leader, _)| leader)
+    // This uses [RefCell], and is therefore unsafe to call concurrently, see
+    // ConcurrentUnionFind for that.
+    pub fn find<Q: Hash + Eq + ?Sized>(&self, current: &Q) -> Option<Rc<T>>
+    where
+        T: Borrow<Q>,
+    {
+        self.with_leader(current, |ps, leader| Self::handle_in(ps, leader).clone())
+    }
+
+    /// Like [UnionFind::find], but only walks to the leader, never writing to the map, so
+    /// repeated calls do not get faster. It only takes a shared borrow, which is released
+    /// before returning, so a structure that is no longer modified can be read this way
+    /// from anywhere without ever needing exclusive access.
+    ///
+    /// The union-find holds [Rc] and [RefCell], so it is never shared between threads;
+    /// [UnionFind::freeze] gives a `Sync` copy of the partition for that.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use hash_unionfind::uf;
+    ///
+    /// let uf = uf! { {1, 2, 3} };
+    /// let depth_before = uf.stats().average_find_depth;
+    /// assert_eq!(Some(1.into()), uf.find_no_compress(&3));
+    /// assert_eq!(depth_before, uf.stats().average_find_depth);
+    /// ```
+    pub fn find_no_compress<Q: Hash + Eq + ?Sized>(&self, current: &Q) -> Option<Rc<T>>
+    where
+        T: Borrow<Q>,
+    {
+        let ps = self.parents.try_borrow().unwrap_or_else(|_| validate::reentered());
+        Self::root_in(&ps, current).cloned()
     }
 
-    /// Given two ids, unions the two eclasses making the bigger class the leader.
+    /// Whether x and y are in the same class, None if one of them is missing. Both finds run
+    /// under a single borrow of the map, comparing the indices of the leaders.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use hash_unionfind::UnionFind;
+    ///
+    /// let mut uf = UnionFind::new();
+    /// uf.insert_many(0..3);
+    /// uf.union(&0, &1);
+    /// assert_eq!(Some(true), uf.same_set(&1, &0));
+    /// assert_eq!(Some(false), uf.same_set(&1, &2));
+    /// assert_eq!(None, uf.same_set(&1, &3));
+    /// ```
+    pub fn same_set<Q: Hash + Eq + ?Sized>(&self, x: &Q, y: &Q) -> Option<bool>
+    where
+        T: Borrow<Q>,
+    {
+        let mut ps = self.access();
+        let (x, y) = (ps.get_index_of(x)?, ps.get_index_of(y)?);
+        let x = ps.leader(x);
+        // Short-circuits when y is a member pointing straight at the leader of x.
+        if crate::wide(ps[y].parent) == x {
+            return Some(true);
+        }
+        Some(ps.leader(y) == x)
+    }
+
+    /// Like [UnionFind::find], also returning the parents visited on the way from x to its
+    /// leader before path compression, ending with the leader. The path is empty if x leads.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use hash_unionfind::UnionFind;
+    ///
+    /// let mut uf = UnionFind::new();
+    /// uf.insert_many(0..4);
+    /// uf.union(&0, &1);
+    /// uf.union(&2, &3);
+    /// uf.union(&0, &2);
+    /// let (leader, path) = uf.find_traced(&3).unwrap();
+    /// assert_eq!(0, *leader);
+    /// assert_eq!(vec![2, 0], path.iter().map(|p| **p).collect::<Vec<_>>());
+    ///
+    /// // The traced find compressed the path.
+    /// assert_eq!(1, uf.find_traced(&3).unwrap().1.len());
+    /// ```
+    pub fn find_traced<Q: Hash + Eq + ?Sized>(&self, current: &Q) -> Option<(Rc<T>, Vec<Rc<T>>)>
+    where
+        T: Borrow<Q>,
+    {
+        let mut ps = self.access();
+        let mut path = Vec::new();
+        let start = ps.get_index_of(current)?;
+        let mut index = start;
+        while crate::wide(ps[index].parent) != index {
+            index = crate::wide(ps[index].parent);
+            path.push(Self::copy_in(&ps, index));
+        }
+        ps.compress(start, index);
+        Some((Self::handle_in(&ps, index).clone(), path))
+    }
+
+    /// Finds the leaders of a batch of queries, in order, borrowing the map once for the whole
+    /// batch instead of once per query.
+    ///
+    /// The queries are walked up the forest in groups of sixteen, one parent step of
+    /// every query in the group at a time, so the reads of the group are independent and
+    /// their cache misses overlap instead of following one another. This is interleaving in
+    /// plain scalar code, there are no SIMD gathers. Paths are compressed once every query of
+    /// the group reached its leader. This pays off when the forest is much larger than the
+    /// cache, with either backend; `cargo bench --bench find_batch` compares it with one
+    /// [find](Self::find) per query.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use hash_unionfind::{DenseBackend, UnionFind};
+    ///
+    /// let mut uf = UnionFind::with_backend(DenseBackend::default());
+    /// uf.insert_many(0u32..100);
+    /// for i in 1..50 {
+    ///     uf.union(&(i - 1), &i);
+    /// }
+    /// let queries: Vec<u32> = (0..100).rev().collect();
+    /// let leaders = uf.find_batch(&queries);
+    /// assert_eq!(queries.iter().map(|q| uf.find(q)).collect::<Vec<_>>(), leaders);
+    /// ```
+    pub fn find_batch(&self, queries: &[T]) -> Vec<Option<Rc<T>>> {
+        let mut ps = self.access();
+        let mut leaders = Vec::with_capacity(queries.len());
+        for group in queries.chunks(BATCH_LANES) {
+            let mut starts = [None; BATCH_LANES];
+            let mut current = [0; BATCH_LANES];
+            let mut lanes = 0;
+            for (start, q) in starts.iter_mut().zip(group) {
+                *start = ps.get_index_of(q);
+                if let Some(index) = *start {
+                    current[lanes] = index;
+                    lanes += 1;
+                }
+            }
+            Self::roots_interleaved_in(&ps, &mut current[..lanes]);
+            let mut roots = current[..lanes].iter();
+            for start in &starts[..group.len()] {
+                leaders.push(start.map(|start| {
+                    let leader = *roots.next().unwrap();
+                    ps.compress(start, leader);
+                    Self::handle_in(&ps, leader).clone()
+                }));
+            }
+        }
+        leaders
+    }
+
+    // Replaces every index with the index of its leader, one parent step of all of them per
+    // round, so the reads of a round do not wait on each other.
+    fn roots_interleaved_in(ps: &Forest<T, B>, current: &mut [usize]) {
+        loop {
+            let mut moved = false;
+            for index in current.iter_mut() {
+                let parent = crate::wide(ps[*index].parent);
+                moved |= parent != *index;
+                *index = parent;
+            }
+            if !moved {
+                return;
+            }
+        }
+    }
+
+    /// Physically reorders the backing storage so members of each class are contiguous.
+    /// Classes keep the order of their first member, and members keep their relative order.
+    /// As a side effect every element points directly at its leader afterwards.
+    ///
+    /// Returns [CheckpointOpen] and leaves the storage as it is while a checkpoint is open,
+    /// as moving the elements invalidates the positions it recorded.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use hash_unionfind::{CheckpointOpen, UnionFind};
+    ///
+    /// let mut uf = UnionFind::new();
+    /// uf.insert_many(0..4);
+    /// uf.union(&0, &2);
+    /// let checkpoint = uf.snapshot();
+    /// assert_eq!(Err(CheckpointOpen), uf.reorder_by_class());
+    /// uf.commit(checkpoint);
+    /// assert_eq!(Ok(()), uf.reorder_by_class());
+    /// assert_eq!(Some(1), uf.id(&2).map(|id| id.index()));
+    /// ```
+    pub fn reorder_by_class(&mut self) -> Result<(), CheckpointOpen> {
+        if self.journal.is_some() {
+            return Err(CheckpointOpen);
+        }
+        let ps = self.parents.get_mut();
+        let mut class_of_leader: IndexMap<usize, usize> = IndexMap::new();
+        let mut classes = Vec::with_capacity(ps.len());
+        for i in 0..ps.len() {
+            let leader = Self::find_index_in(ps, i);
+            let next = class_of_leader.len();
+            classes.push(*class_of_leader.entry(leader).or_insert(next));
+        }
+        let mut order: Vec<usize> = (0..ps.len()).collect();
+        order.sort_by_key(|&i| classes[i]);
+        // The new position of every old index, to carry the links over.
+        let mut moved_to = vec![0; ps.len()];
+        for (new, &old) in order.iter().enumerate() {
+            moved_to[old] = new;
+        }
+        ps.permute(&order);
+        for node in ps.values_mut() {
+            node.parent = crate::small(moved_to[crate::wide(node.parent)]);
+            node.next = crate::small(moved_to[crate::wide(node.next)]);
+        }
+        for d in self.deposed.iter_mut().chain(&mut self.dirty).flatten() {
+            *d = crate::small(moved_to[crate::wide(*d)]);
+        }
+        if let Some(weights) = &mut self.weights {
+            *weights = order.iter().map(|&old| weights[old]).collect();
+        }
+        if let Some(index) = &mut self.member_index {
+            let moved = |i: &mut Index| *i = crate::small(moved_to[crate::wide(*i)]);
+            *index = index
+                .drain(..)
+                .map(|(mut leader, mut members)| {
+                    moved(&mut leader);
+                    members.iter_mut().for_each(moved);
+                    (leader, members)
+                })
+                .collect();
+        }
+        Ok(())
+    }
+
+    /// Given two ids, unions the two eclasses by rank, the leader of the class with the higher
+    /// rank (x on ties) becomes the leader. If one of the items is missing returns None.
+    pub fn union<Q: Hash + Eq + ?Sized>(&mut self, x: &Q, y: &Q) -> Option<Rc<T>>
+    where
+        T: Borrow<Q>,
+    {
+        let ps = self.parents.get_mut();
+        let mut x = Self::find_index_in(ps, ps.get_index_of(x)?);
+        let mut y = Self::find_index_in(ps, ps.get_index_of(y)?);
+        if x != y {
+            if ps[y].rank > ps[x].rank {
+                std::mem::swap(&mut x, &mut y);
+            }
+            self.link(x, y);
+        }
+        Some(Self::handle_in(self.parents.get_mut(), x).clone())
+    }
+
+    /// Like [UnionFind::union], inserting x and y first if they are missing, so it always
+    /// returns the new leader. Each key is hashed once, the union then goes through the
+    /// handles [UnionFind::insert] returns.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use hash_unionfind::UnionFind;
+    ///
+    /// let mut uf = UnionFind::new();
+    /// for (x, y) in [("a", "b"), ("c", "b")] {
+    ///     uf.union_insert(x, y);
+    /// }
+    /// assert_eq!("a", *uf.union_insert("c", "c"));
+    /// assert_eq!((3, 1), (uf.size(), uf.num_classes()));
+    /// ```
+    pub fn union_insert(&mut self, x: T, y: T) -> Rc<T> {
+        let (x, y) = (self.insert(x), self.insert(y));
+        let leader = self.union_ids(x, y).unwrap();
+        Self::handle_in(self.parents.get_mut(), leader.index()).clone()
+    }
+
+    /// Like [UnionFind::union], and calls `on_union(uf, kept, absorbed)` with the two leaders
+    /// after an effective union. No borrow is held during the call, so the callback may use
+    /// any read API, such as [UnionFind::find] or [UnionFind::class_size].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use hash_unionfind::UnionFind;
+    ///
+    /// let mut uf = UnionFind::new();
+    /// uf.insert_many(0..4);
+    /// uf.union(&0, &1);
+    /// let mut merged = vec![];
+    /// uf.union_notify(&2, &1, |uf, kept, absorbed| {
+    ///     merged.push((**kept, **absorbed, uf.class_size(absorbed).unwrap()));
+    /// });
+    /// assert_eq!(vec![(0, 2, 3)], merged);
+    /// ```
+    pub fn union_notify<Q: Hash + Eq + ?Sized, F>(&mut self, x: &Q, y: &Q, on_union: F) -> Option<Rc<T>>
+    where
+        T: Borrow<Q>,
+        F: FnOnce(&Self, &Rc<T>, &Rc<T>),
+    {
+        let (x, y) = (self.find(x)?, self.find(y)?);
+        let leader = self.union::<T>(&x, &y)?;
+        if x != y {
+            let absorbed = if leader == x { y } else { x };
+            on_union(self, &leader, &absorbed);
+        }
+        Some(leader)
+    }
+
+    // Attaches the class led by y under the leader x, both distinct leader indices.
+    fn link(&mut self, x: usize, y: usize) -> usize {
+        let ps = self.parents.get_mut();
+        let (x_node, y_node) = (&ps[x], &ps[y]);
+        let rank = x_node.rank.max(y_node.rank + 1);
+        let size = x_node.size.checked_add(y_node.size).expect("class size overflow");
+        self.journal_link(x, y);
+        self.clock += 1;
+        self.unions += 1;
+        self.record_updates(x, y);
+        let ps = self.parents.get_mut();
+        // Splicing the member cycles is swapping the successors of the two leaders.
+        let x_next = ps[x].next;
+        let y_node = &mut ps[y];
+        y_node.parent = crate::small(x);
+        let y_next = std::mem::replace(&mut y_node.next, x_next);
+        let x_node = &mut ps[x];
+        x_node.next = y_next;
+        x_node.rank = rank;
+        x_node.size = size;
+        x_node.version = self.clock;
+        self.classes -= 1;
+        if let Some(weights) = &mut self.weights {
+            weights[x].class += weights[y].class;
+        }
+        self.record_merge(x, y);
+        self.record_members(x, y);
+        self.log_merge(x, y);
+        // Only leaders keep a shared copy of their key.
+        self.parents.get_mut()[y].handle.take();
+        if let Some(deposed) = &mut self.deposed {
+            deposed.push(crate::small(y));
+        }
+        self.mark_dirty(x);
+        x
+    }
+
+    /// The number of elements in the class of x, or None if x is missing.
+    ///
+    /// The size is an exact count kept on every leader next to the rank, so this costs a
+    /// find. Use [UnionFind::class_rank] for the linking bound instead.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use hash_unionfind::UnionFind;
+    ///
+    /// let mut uf = UnionFind::new();
+    /// uf.insert_many(0..5);
+    /// uf.union(&0, &1);
+    /// uf.union(&1, &2);
+    /// assert_eq!((Some(3), Some(1)), (uf.class_size(&2), uf.class_rank(&2)));
+    /// assert_eq!(Some(1), uf.class_size(&4));
+    /// ```
+    pub fn class_size<Q: Hash + Eq + ?Sized>(&self, x: &Q) -> Option<usize>
+    where
+        T: Borrow<Q>,
+    {
+        self.with_leader(x, |ps, leader| crate::wide(ps[leader].size))
+    }
+
+    /// The rank of the class of x, an upper bound on the height of its tree used for linking.
+    /// Returns None if x is missing.
+    pub fn class_rank<Q: Hash + Eq + ?Sized>(&self, x: &Q) -> Option<usize>
+    where
+        T: Borrow<Q>,
+    {
+        self.with_leader(x, |ps, leader| crate::wide(ps[leader].rank))
+    }
+
+    /// A version of the class of x, which increases whenever the class grows or its leader
+    /// changes. Caches keyed by leader can store it and compare to detect staleness.
+    /// Returns None if x is missing.
+    pub fn class_version<Q: Hash + Eq + ?Sized>(&self, x: &Q) -> Option<u64>
+    where
+        T: Borrow<Q>,
+    {
+        self.with_leader(x, |ps, leader| ps[leader].version)
+    }
+
+    /// Starts recording the leaders that lose leadership, for [UnionFind::take_leader_remap].
+    /// Does nothing if already recording.
+    ///
+    /// Recording costs a push per effective union until the next take.
+    pub fn track_leader_remap(&mut self) {
+        if self.deposed.is_none() {
+            self.deposed = Some(Vec::new());
+        }
+    }
+
+    /// Returns a map from every leader that lost leadership since the last call to its current
+    /// leader, or None if not recording. Chains of merges are already composed, so each value
+    /// is a leader at the time of the call.
+    pub fn take_leader_remap(&mut self) -> Option<IndexMap<Rc<T>, Rc<T>>> {
+        let deposed = std::mem::take(self.deposed.as_mut()?);
+        let ps = self.parents.get_mut();
+        let remap = deposed.into_iter().map(|old| {
+            let new = Self::find_index_in(ps, crate::wide(old));
+            (Self::copy_in(ps, crate::wide(old)), Self::handle_in(ps, new).clone())
+        });
+        Some(remap.collect())
+    }
+
+    /// Stops recording and drops the pending deposed leaders.
+    pub fn stop_leader_remap(&mut self) {
+        self.deposed = None;
+    }
+
+    /// Reports what [UnionFind::union] would do on x and y without merging anything:
+    /// the element that would lead the merged class and the size of that class.
     /// If one of the items is missing returns None.
-    pub fn union(&mut self, x: &T, y: &T) -> Option<Rc<T>> {
-        let (mut x, x_rank) = self.inner_find(x)?;
-        let (mut y, y_rank) = self.inner_find(y)?;
+    pub fn preview_union<Q: Hash + Eq + ?Sized>(&self, x: &Q, y: &Q) -> Option<(Rc<T>, usize)>
+    where
+        T: Borrow<Q>,
+    {
+        let x = self.with_leader(x, |_, leader| leader)?;
+        let y = self.with_leader(y, |_, leader| leader)?;
+        let ps = self.parents.try_borrow().unwrap_or_else(|_| validate::reentered());
+        let x_size = crate::wide(ps[x].size);
         if x == y {
-            return Some(x);
+            return Some((Self::handle_in(&ps, x).clone(), x_size));
         }
-        if y_rank > x_rank {
-            std::mem::swap(&mut x, &mut y);
-        }
-        let x = x;
-        let y = y;
-        let mut ps = self.parents.borrow_mut();
-        let new_x_res = ps[x.as_ref()].0.clone();
-        *ps.get_mut(y.as_ref()).unwrap() = (new_x_res.clone(), x_rank + y_rank);
-        *ps.get_mut(x.as_ref()).unwrap() = (new_x_res.clone(), x_rank + y_rank);
-        Some(x)
+        // Same tie breaking as union, x wins unless y has a strictly higher rank.
+        let leader = if ps[y].rank > ps[x].rank { y } else { x };
+        Some((Self::handle_in(&ps, leader).clone(), x_size + crate::wide(ps[y].size)))
     }
 }
 
@@ -140,8 +971,8 @@ mod tests {
 
         // indexes:         0, 1, 2, 3, 4, 5, 6, 7, 8, 9
         let expected = vec![0, 0, 0, 0, 4, 5, 6, 6, 6, 6];
-        for i in 0..n {
-            assert_eq!(uf.find(&i).unwrap().as_ref(), &expected[i]);
+        for (i, leader) in expected.iter().enumerate() {
+            assert_eq!(uf.find(&i).unwrap().as_ref(), leader);
         }
     }
 
@@ -171,4 +1002,285 @@ mod tests {
         assert_eq!(uf.find(&"a"), uf.find(&"e"));
         assert_eq!(&"a", uf.find(&"a").unwrap().as_ref());
     }
+
+    #[test]
+    fn find_batch_matches_find() {
+        let mut uf = UnionFind::new();
+        for i in 0..6 {
+            uf.insert(i);
+        }
+        uf.union(&0, &1);
+        uf.union(&2, &1);
+        uf.union(&4, &5);
+
+        let queries = [0, 1, 2, 3, 4, 5, 6];
+        let expected: Vec<_> = queries.iter().map(|q| uf.find(q)).collect();
+        assert_eq!(expected, uf.find_batch(&queries));
+        assert_eq!(None, uf.find_batch(&queries)[6]);
+
+        // Several groups over deep trees with missing keys in between, compressing all paths.
+        let mut dense = UnionFind::with_backend(crate::DenseBackend::default());
+        dense.insert_many(0u32..64);
+        for step in [1, 2, 4, 8, 16, 32] {
+            for i in (0..64u32).step_by(2 * step) {
+                dense.union(&(i + step as u32), &i);
+            }
+        }
+        let queries: Vec<u32> = (0..80).rev().collect();
+        let before = dense.clone();
+        let leaders = dense.find_batch(&queries);
+        assert_eq!(queries.iter().map(|q| before.find(q)).collect::<Vec<_>>(), leaders);
+        assert_eq!(63.0 / 64.0, dense.stats().average_find_depth);
+    }
+
+    #[test]
+    fn preview_union() {
+        let mut uf = UnionFind::new();
+        uf.insert_many(0..5);
+        uf.union(&3, &4);
+
+        assert_eq!(Some((Rc::new(0), 2)), uf.preview_union(&0, &1));
+        assert_eq!(Some((Rc::new(3), 3)), uf.preview_union(&0, &4));
+        assert_eq!(Some((Rc::new(3), 2)), uf.preview_union(&4, &3));
+        assert_eq!(None, uf.preview_union(&0, &9));
+        assert_eq!(4, uf.num_classes());
+
+        let (leader, _) = uf.preview_union(&1, &4).unwrap();
+        assert_eq!(Some(leader), uf.union(&1, &4));
+    }
+
+    #[test]
+    fn class_version() {
+        let mut uf = UnionFind::new();
+        uf.insert_many(0..4);
+        assert_eq!(None, uf.class_version(&9));
+        let v0 = uf.class_version(&0).unwrap();
+        let v2 = uf.class_version(&2).unwrap();
+
+        uf.union(&0, &1);
+        let v01 = uf.class_version(&1).unwrap();
+        assert!(v01 > v0);
+        assert_eq!(Some(v01), uf.class_version(&0));
+        assert_eq!(Some(v2), uf.class_version(&2));
+
+        // Repeated unions inside a class are not changes.
+        uf.union(&1, &0);
+        assert_eq!(Some(v01), uf.class_version(&0));
+
+        uf.union(&2, &3);
+        uf.union(&3, &0);
+        let v = uf.class_version(&2).unwrap();
+        assert!(v > v01);
+        assert_eq!(Some(v), uf.class_version(&0));
+    }
+
+    #[test]
+    fn leader_remap() {
+        let mut uf = UnionFind::new();
+        uf.insert_many(0..6);
+        assert_eq!(None, uf.take_leader_remap());
+        uf.track_leader_remap();
+        assert!(uf.take_leader_remap().unwrap().is_empty());
+
+        uf.union(&0, &1);
+        uf.union(&2, &3);
+        uf.union(&0, &2);
+        uf.union(&1, &3);
+        let remap = uf.take_leader_remap().unwrap();
+        assert_eq!(3, remap.len());
+        assert_eq!(Some(&Rc::new(0)), remap.get(&1));
+        assert_eq!(Some(&Rc::new(0)), remap.get(&2));
+        assert_eq!(Some(&Rc::new(0)), remap.get(&3));
+        assert!(uf.take_leader_remap().unwrap().is_empty());
+
+        // The previous leader of the winning class can lose in a later batch.
+        uf.union(&4, &5);
+        uf.union(&5, &0);
+        let remap = uf.take_leader_remap().unwrap();
+        assert_eq!(2, remap.len());
+        assert_eq!(Some(&Rc::new(0)), remap.get(&4));
+        assert_eq!(Some(&Rc::new(0)), remap.get(&5));
+    }
+
+    #[test]
+    fn size_and_rank() {
+        let mut uf = UnionFind::new();
+        uf.insert_many(0..8);
+        assert_eq!(Some(1), uf.class_size(&0));
+        assert_eq!(Some(0), uf.class_rank(&0));
+        assert_eq!(None, uf.class_size(&8));
+
+        // A star of four elements has rank 1, a pair of pairs has rank 2.
+        uf.union(&0, &1);
+        uf.union(&0, &2);
+        uf.union(&0, &3);
+        assert_eq!(Some(4), uf.class_size(&3));
+        assert_eq!(Some(1), uf.class_rank(&3));
+        uf.union(&4, &5);
+        uf.union(&6, &7);
+        uf.union(&4, &6);
+        assert_eq!(Some(4), uf.class_size(&7));
+        assert_eq!(Some(2), uf.class_rank(&7));
+
+        // Linking is by rank, so the bigger star is attached under the higher ranked class.
+        assert_eq!(Some(Rc::new(4)), uf.union(&0, &4));
+        assert_eq!(Some(8), uf.class_size(&0));
+        assert_eq!(Some(2), uf.class_rank(&0));
+    }
+
+    #[test]
+    fn insert_many() {
+        let mut uf = UnionFind::new();
+        uf.insert(3);
+        assert_eq!(4, uf.insert_many(0..5));
+        assert_eq!(0, uf.insert_many(vec![1, 2, 2]));
+        assert_eq!(5, uf.size());
+        assert_eq!(5, uf.num_classes());
+        uf.union_pairs((1..5).map(|i| (0, i)));
+        assert!(uf.is_single_class());
+    }
+
+    #[test]
+    fn reorder_by_class() {
+        let mut uf = UnionFind::new();
+        for i in 0..6 {
+            uf.insert(i);
+        }
+        uf.union(&4, &1);
+        uf.union(&0, &5);
+        uf.union(&3, &1);
+        uf.reorder_by_class().unwrap();
+
+        let order: Vec<usize> = uf.parents.borrow().keys().copied().collect();
+        assert_eq!(order, vec![0, 5, 1, 3, 4, 2]);
+        assert_eq!(uf.find(&3), uf.find(&4));
+        assert_eq!(uf.find(&0), uf.find(&5));
+        assert_ne!(uf.find(&0), uf.find(&1));
+        assert_eq!(3, uf.num_classes());
+    }
+
+    #[test]
+    fn uf_macro() {
+        let uf = crate::uf! { {3, 1, 2}, {4}, {5, 6,}, };
+        assert_eq!(vec![vec![1, 2, 3], vec![4], vec![5, 6]], uf.to_canonical_vec());
+        assert_eq!(Some(Rc::new(3)), uf.find(&2));
+        assert_eq!(Some(Rc::new(5)), uf.find(&6));
+
+        let empty: UnionFind<u8> = crate::uf! {};
+        assert_eq!(0, empty.size());
+    }
+
+    #[test]
+    fn reads_under_a_live_borrow() {
+        let mut uf = crate::uf! { {0, 1, 2}, {3, 4} };
+        uf.union_notify(&4, &1, |uf, kept, absorbed| {
+            assert_eq!(Some(kept.clone()), uf.find(absorbed));
+            assert_eq!(Some(5), uf.class_size(&4));
+        });
+
+        // As when a callback runs inside a method reading the map, reads walk instead.
+        let ps = uf.parents.borrow();
+        assert_eq!(Some(Rc::new(3)), uf.find(&2));
+        assert_eq!(Some(2), uf.class_info(&1).map(|info| info.rank));
+        assert_eq!(None, uf.class_version(&9));
+        assert_eq!(Some(true), uf.same_set(&0, &4));
+        assert_eq!(Some(Rc::new(3)), uf.find_no_compress(&0));
+        assert_eq!(Some(Rc::new(3)), uf.find_traced(&0).map(|(leader, _)| leader));
+        assert_eq!(vec![Some(Rc::new(3)), None], uf.find_batch(&[1, 9]));
+        assert_eq!(4, uf.substitution(false).len());
+        assert_eq!(5, uf.freeze().size());
+        uf.compress_all();
+        drop(ps);
+        assert_eq!((Some(true), None), (uf.same_set(&2, &4), uf.same_set(&2, &9)));
+        assert_eq!(Some(Rc::new(3)), uf.find(&2));
+    }
+
+    #[test]
+    fn unsized_keys() {
+        let mut uf: UnionFind<Rc<str>> = UnionFind::new();
+        let source = String::from("let x = y + z");
+        uf.insert_many(source.split(' ').map(Rc::from));
+        uf.union("x", "y");
+        assert_eq!(uf.find("y"), uf.find(&source[4..5]));
+        assert_eq!(Some(2), uf.class_size("x"));
+        assert_eq!(None, uf.find("w"));
+        assert!(uf.same_class("x", "y") && uf.same_class("w", "w") && !uf.same_class("x", "w"));
+        let hash = |key: &str| {
+            let mut hasher = std::collections::hash_map::DefaultHasher::new();
+            uf.hash_key(key, &mut hasher);
+            std::hash::Hasher::finish(&hasher)
+        };
+        assert_eq!(hash("x"), hash("y"));
+
+        // The wrappers look up by borrowed keys as well.
+        let mut totals = crate::QuotientMap::new(|a: u32, b| a + b);
+        totals.insert(String::from("a"), 1);
+        totals.insert(String::from("b"), 2);
+        totals.union("a", "b");
+        assert_eq!(Some(&3), totals.get("b"));
+        assert_eq!(Some(true), uf.freeze().equiv("x", "y"));
+
+        let mut bytes: UnionFind<std::sync::Arc<[u8]>> = UnionFind::new();
+        bytes.insert_many([b"ab".as_slice(), b"cd"].map(std::sync::Arc::from));
+        assert_eq!(Some(Rc::new(std::sync::Arc::from(b"ab".as_slice()))), bytes.union(b"ab".as_slice(), b"cd"));
+    }
+
+    #[test]
+    fn find_traced() {
+        let mut uf = UnionFind::new();
+        uf.insert_many(0..8);
+        for (x, y) in [(0, 1), (2, 3), (4, 5), (6, 7), (0, 2), (4, 6), (0, 4)] {
+            uf.union(&x, &y);
+        }
+        let (leader, path) = uf.find_traced(&7).unwrap();
+        assert_eq!(Rc::new(0), leader);
+        assert_eq!(vec![Rc::new(6), Rc::new(4), Rc::new(0)], path);
+        assert_eq!(vec![Rc::new(0)], uf.find_traced(&7).unwrap().1);
+        assert_eq!((Rc::new(0), vec![]), uf.find_traced(&0).unwrap());
+        assert_eq!(None, uf.find_traced(&8));
+    }
+
+    #[test]
+    fn keys_are_cloned_per_leader() {
+        thread_local!(static CLONES: std::cell::Cell<usize> = const { std::cell::Cell::new(0) });
+
+        #[derive(Debug, PartialEq, Eq, Hash)]
+        struct Counted(u32);
+
+        impl Clone for Counted {
+            fn clone(&self) -> Self {
+                CLONES.with(|clones| clones.set(clones.get() + 1));
+                Counted(self.0)
+            }
+        }
+
+        let mut uf = UnionFind::new();
+        let ids: Vec<Id> = (0..64).map(|i| uf.insert(Counted(i))).collect();
+        let inserted = CLONES.with(|clones| clones.get());
+        for pair in ids.windows(2) {
+            uf.union_ids(pair[1], pair[0]);
+        }
+        uf.compress_all();
+        assert_eq!(inserted, CLONES.with(|clones| clones.get()));
+
+        // Handing out the leader makes one shared copy, which later finds reuse.
+        for i in 0..64 {
+            assert_eq!(Counted(1), *uf.find(&Counted(i)).unwrap());
+        }
+        assert_eq!(inserted + 1, CLONES.with(|clones| clones.get()));
+
+        // Members are lent out of the map, indexed or not.
+        assert!(uf.kth_member(&Counted(0), 63).is_some());
+        uf.index_members();
+        assert_eq!(64, uf.members(&Counted(5)).count());
+        assert_eq!(inserted + 1, CLONES.with(|clones| clones.get()));
+    }
+
+    #[test]
+    fn node_size() {
+        // Four links and counters, the class version and the leader handle: weights and the
+        // opt-in recordings live next to the map.
+        let expected = 4 * std::mem::size_of::<Index>() + 16;
+        assert_eq!(expected, std::mem::size_of::<Node<u64>>());
+    }
 }
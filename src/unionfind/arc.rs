@@ -0,0 +1,218 @@
+use std::{borrow::Borrow, fmt::Debug, hash::Hash, sync::Arc};
+use indexmap::{map::Entry, IndexMap};
+
+use crate::Backend;
+
+use super::{Rank, Size, UnionFind};
+
+#[derive(Debug, Clone)]
+struct ArcNode<T> {
+    parent: Arc<T>,
+    // As in UnionFind, only meaningful on leaders.
+    rank: Rank,
+    size: Size,
+}
+
+/// A union-find handing out leaders as `Arc<T>`, which is `Send` and `Sync` for `Send` and
+/// `Sync` keys, so it can move between threads and its leaders can be sent over channels.
+///
+/// It has no interior mutability: [ArcUnionFind::find] walks to the leader without
+/// compressing, which union by rank keeps logarithmic, and [ArcUnionFind::union] and
+/// [ArcUnionFind::compress] compress through `&mut self`. Only the core operations are
+/// provided, convert from and to a [UnionFind] for the rest.
+///
+/// # Examples
+///
+/// ```
+/// use std::{sync::mpsc, thread};
+/// use hash_unionfind::ArcUnionFind;
+///
+/// let mut uf = ArcUnionFind::new();
+/// uf.insert_many(["a", "b", "c"]);
+/// uf.union(&"a", &"b");
+///
+/// let (send, receive) = mpsc::channel();
+/// thread::spawn(move || send.send((uf.find(&"b"), uf)).unwrap());
+/// let (leader, uf) = receive.recv().unwrap();
+/// assert_eq!(leader, uf.find(&"a"));
+/// ```
+#[derive(Debug, Clone)]
+pub struct ArcUnionFind<T> {
+    parents: IndexMap<T, ArcNode<T>>,
+    classes: usize,
+}
+
+impl<T: Hash + Eq> Default for ArcUnionFind<T> {
+    fn default() -> Self {
+        Self { parents: IndexMap::new(), classes: 0 }
+    }
+}
+
+impl<T: Hash + Eq + Clone> ArcUnionFind<T> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn size(&self) -> usize {
+        self.parents.len()
+    }
+
+    pub fn num_classes(&self) -> usize {
+        self.classes
+    }
+
+    pub fn insert(&mut self, t: T) {
+        if let Entry::Vacant(entry) = self.parents.entry(t) {
+            let parent = Arc::new(entry.key().clone());
+            entry.insert(ArcNode { parent, rank: 0, size: 1 });
+            self.classes += 1;
+        }
+    }
+
+    /// Inserts every element as a singleton. Returns how many of the elements were new.
+    pub fn insert_many<I: IntoIterator<Item = T>>(&mut self, iter: I) -> usize {
+        let before = self.size();
+        for t in iter {
+            self.insert(t);
+        }
+        self.size() - before
+    }
+
+    // Walks to the leader without compressing.
+    fn root<Q: Hash + Eq + ?Sized>(&self, x: &Q) -> Option<&Arc<T>>
+    where
+        T: Borrow<Q>,
+    {
+        let (mut key, mut node) = self.parents.get_key_value(x)?;
+        while node.parent.as_ref() != key {
+            (key, node) = self.parents.get_key_value(node.parent.as_ref()).unwrap();
+        }
+        Some(&node.parent)
+    }
+
+    // The leader of x, pointing every element on the way straight at it.
+    fn find_compress<Q: Hash + Eq + ?Sized>(&mut self, x: &Q) -> Option<Arc<T>>
+    where
+        T: Borrow<Q>,
+    {
+        let leader = self.root(x)?.clone();
+        let mut current = self.parents.get_index_of(x).unwrap();
+        while self.parents[current].parent != leader {
+            let next = self.parents.get_index_of(self.parents[current].parent.as_ref()).unwrap();
+            self.parents[current].parent = leader.clone();
+            current = next;
+        }
+        Some(leader)
+    }
+
+    /// The leader of the class of x, or None if x is missing.
+    pub fn find<Q: Hash + Eq + ?Sized>(&self, x: &Q) -> Option<Arc<T>>
+    where
+        T: Borrow<Q>,
+    {
+        self.root(x).cloned()
+    }
+
+    /// Whether x and y are in the same class, None if one of them is missing.
+    pub fn equiv<Q: Hash + Eq + ?Sized>(&self, x: &Q, y: &Q) -> Option<bool>
+    where
+        T: Borrow<Q>,
+    {
+        Some(self.root(x)? == self.root(y)?)
+    }
+
+    /// The number of elements in the class of x, or None if x is missing.
+    pub fn class_size<Q: Hash + Eq + ?Sized>(&self, x: &Q) -> Option<usize>
+    where
+        T: Borrow<Q>,
+    {
+        Some(crate::wide(self.parents[self.root(x)?.as_ref()].size))
+    }
+
+    /// Unions the classes of x and y by rank as [UnionFind::union] does, returning the new
+    /// leader, or None if one of them is missing.
+    pub fn union<Q: Hash + Eq + ?Sized>(&mut self, x: &Q, y: &Q) -> Option<Arc<T>>
+    where
+        T: Borrow<Q>,
+    {
+        let mut x = self.find_compress(x)?;
+        let mut y = self.find_compress(y)?;
+        if x == y {
+            return Some(x);
+        }
+        if self.parents[y.as_ref()].rank > self.parents[x.as_ref()].rank {
+            std::mem::swap(&mut x, &mut y);
+        }
+        let y_node = &mut self.parents[y.as_ref()];
+        y_node.parent = x.clone();
+        let (y_rank, y_size) = (y_node.rank, y_node.size);
+        let x_node = &mut self.parents[x.as_ref()];
+        x_node.rank = x_node.rank.max(y_rank + 1);
+        x_node.size = x_node.size.checked_add(y_size).expect("class size overflow");
+        self.classes -= 1;
+        Some(x)
+    }
+
+    /// Points every element directly at its leader.
+    pub fn compress(&mut self) {
+        for i in 0..self.parents.len() {
+            let parent = self.parents[i].parent.clone();
+            self.parents[i].parent = self.root(parent.as_ref()).unwrap().clone();
+        }
+    }
+}
+
+impl<T: Hash + Eq + Clone + Debug, B: Backend<Key = T>> From<&UnionFind<T, B>> for ArcUnionFind<T> {
+    fn from(uf: &UnionFind<T, B>) -> Self {
+        let mut arc = Self::new();
+        let ps = uf.parents.borrow();
+        for t in ps.keys() {
+            arc.insert(t.clone());
+        }
+        for (i, t) in ps.keys().enumerate() {
+            let (leader, _) = ps.get_index(UnionFind::<T, B>::root_index_in(&ps, i)).unwrap();
+            arc.union(leader, t);
+        }
+        arc
+    }
+}
+
+impl<T: Hash + Eq + Clone + Debug> From<&ArcUnionFind<T>> for UnionFind<T> {
+    fn from(arc: &ArcUnionFind<T>) -> Self {
+        let mut uf = UnionFind::new();
+        uf.insert_many(arc.parents.keys().cloned());
+        for t in arc.parents.keys() {
+            uf.union(t, arc.root(t).unwrap().as_ref());
+        }
+        uf
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn send_sync<T: Send + Sync>(_: &T) {}
+
+    #[test]
+    fn matches_union_find() {
+        let mut uf = UnionFind::new();
+        let mut arc = ArcUnionFind::new();
+        uf.insert_many(0..64);
+        assert_eq!(64, arc.insert_many(0..64));
+        for i in 0..63 {
+            let (x, y) = (i * 17 % 64, i * 5 % 64);
+            assert_eq!(uf.union(&x, &y).map(|l| *l), arc.union(&x, &y).map(|l| *l));
+        }
+        send_sync(&arc);
+        let leader = arc.find(&3).unwrap();
+        send_sync(&leader);
+        assert_eq!((uf.num_classes(), uf.class_size(&3)), (arc.num_classes(), arc.class_size(&3)));
+        assert_eq!(Some(uf.find(&0) == uf.find(&1)), arc.equiv(&0, &1));
+        assert_eq!(None, arc.find(&64));
+
+        arc.compress();
+        assert_eq!(uf.to_canonical_vec(), UnionFind::from(&arc).to_canonical_vec());
+        assert_eq!(uf.to_canonical_vec(), UnionFind::from(&ArcUnionFind::from(&uf)).to_canonical_vec());
+    }
+}
@@ -0,0 +1,97 @@
+use std::{borrow::Borrow, cmp::Ordering, fmt::Debug, hash::Hash, rc::Rc};
+
+use crate::Backend;
+
+use super::{forest::Forest, weights::Weight, UnionFind};
+
+/// A snapshot of the data kept on a class leader, with the leader as `L`.
+///
+/// [UnionFind::class_info] returns it owning a shared copy of the leader, `ClassInfo<Rc<T>>`,
+/// while [UnionPolicy](super::UnionPolicy) policies see `ClassInfo<&T>`, borrowing the keys
+/// from the union-find, so choosing a leader never copies a key.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ClassInfo<L> {
+    pub leader: L,
+    /// The number of elements.
+    pub size: usize,
+    /// The upper bound on the tree height used by [UnionFind::union].
+    pub rank: usize,
+    /// The total weight, see [UnionFind::insert_weighted].
+    pub weight: f64,
+}
+
+impl<T: Hash + Eq + Clone + Debug, B: Backend<Key = T>> UnionFind<T, B> {
+    /// The data of the class of x, or None if x is missing.
+    pub fn class_info<Q: Hash + Eq + ?Sized>(&self, x: &Q) -> Option<ClassInfo<Rc<T>>>
+    where
+        T: Borrow<Q>,
+    {
+        self.with_leader(x, |ps, leader| {
+            Self::info_in(ps, self.weights.as_deref(), leader, Self::handle_in(ps, leader).clone())
+        })
+    }
+
+    // The data of the class led by the element at leader, with the weight table if any, and
+    // with named as its leader.
+    pub(super) fn info_in<L>(ps: &Forest<T, B>, weights: Option<&[Weight]>, leader: usize, named: L) -> ClassInfo<L> {
+        let node = &ps[leader];
+        ClassInfo {
+            size: crate::wide(node.size),
+            rank: crate::wide(node.rank),
+            weight: weights.map_or(0.0, |weights| weights[leader].class),
+            leader: named,
+        }
+    }
+
+    /// Unions the classes of x and y letting `key` decide the linking: the class with the
+    /// larger key keeps its leader and the other is attached under it, x wins ties.
+    /// If one of the items is missing returns None.
+    ///
+    /// Unlike [UnionFind::union] this gives no bound on tree height, which is only as good as
+    /// the key makes it.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use hash_unionfind::UnionFind;
+    ///
+    /// let mut uf = UnionFind::new();
+    /// uf.insert_weighted("small", 1.0);
+    /// uf.insert_weighted("heavy", 50.0);
+    /// let leader = uf.union_by_key(&"small", &"heavy", |class| class.weight);
+    /// assert_eq!("heavy", *leader.unwrap());
+    /// ```
+    pub fn union_by_key<Q: Hash + Eq + ?Sized, K, F>(&mut self, x: &Q, y: &Q, mut key: F) -> Option<Rc<T>>
+    where
+        T: Borrow<Q>,
+        K: PartialOrd,
+        F: FnMut(&ClassInfo<&T>) -> K,
+    {
+        let policy = |x: &ClassInfo<&T>, y: &ClassInfo<&T>| key(y).partial_cmp(&key(x)) != Some(Ordering::Greater);
+        self.union_with(x, y, policy)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn union_by_key() {
+        let mut uf = UnionFind::new();
+        uf.insert_many(0..6);
+        uf.union(&0, &1);
+        uf.union(&0, &2);
+        assert_eq!(Some(ClassInfo { leader: Rc::new(0), size: 3, rank: 1, weight: 0.0 }), uf.class_info(&2));
+
+        // Smaller class wins when the key is the negated size.
+        assert_eq!(Some(Rc::new(3)), uf.union_by_key(&1, &3, |c| -(c.size as i64)));
+        assert_eq!(Some(4), uf.class_size(&0));
+        assert_eq!(Some(2), uf.class_rank(&0));
+
+        let leader = uf.union_by_key(&4, &5, |c| *c.leader).unwrap();
+        assert_eq!(5, *leader);
+        assert_eq!(Some(Rc::new(5)), uf.find(&4));
+        assert_eq!(None, uf.union_by_key(&4, &9, |c| c.size));
+    }
+}
@@ -0,0 +1,233 @@
+use std::{fmt::Debug, hash::Hash, ops::ControlFlow};
+
+use crate::Backend;
+
+use super::UnionFind;
+
+impl<T: Hash + Eq + Clone + Debug, B: Backend<Key = T>> UnionFind<T, B> {
+    /// Unions every pair in order, skipping pairs with missing elements. Returns the number
+    /// of effective unions.
+    #[doc(alias = "union_all")]
+    pub fn union_pairs<I: IntoIterator<Item = (T, T)>>(&mut self, pairs: I) -> usize {
+        match self.union_pairs_with(pairs, |_| ControlFlow::Continue(())) {
+            ControlFlow::Continue(merges) | ControlFlow::Break(merges) => merges,
+        }
+    }
+
+    /// Like [UnionFind::union_pairs], calling `progress` with the number of pairs done after
+    /// every pair. Once it breaks no more pairs are applied and the merges so far are returned
+    /// in [ControlFlow::Break], the partition keeping the unions of the pairs done.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::ops::ControlFlow;
+    /// use hash_unionfind::UnionFind;
+    ///
+    /// let mut uf = UnionFind::new();
+    /// uf.insert_many(0..100);
+    /// let done = uf.union_pairs_with((1..100).map(|i| (0, i)), |done| {
+    ///     if done == 10 { ControlFlow::Break(()) } else { ControlFlow::Continue(()) }
+    /// });
+    /// assert_eq!(ControlFlow::Break(10), done);
+    /// assert_eq!(90, uf.num_classes());
+    /// ```
+    pub fn union_pairs_with<I, F>(&mut self, pairs: I, mut progress: F) -> ControlFlow<usize, usize>
+    where
+        I: IntoIterator<Item = (T, T)>,
+        F: FnMut(usize) -> ControlFlow<()>,
+    {
+        let before = self.num_classes();
+        for (done, (x, y)) in pairs.into_iter().enumerate() {
+            self.union(&x, &y);
+            if progress(done + 1).is_break() {
+                return ControlFlow::Break(before - self.num_classes());
+            }
+        }
+        ControlFlow::Continue(before - self.num_classes())
+    }
+
+    /// Combines the partition of other into this one, inserting its elements and unioning
+    /// the classes they overlap. Returns the number of effective unions.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use hash_unionfind::UnionFind;
+    ///
+    /// let mut left = UnionFind::from_edge_list([(0, 1), (2, 3)]);
+    /// let right = UnionFind::from_edge_list([(1, 2), (4, 5)]);
+    /// assert_eq!(2, left.absorb(right));
+    /// assert_eq!(left.find(&0), left.find(&3));
+    /// assert_eq!((6, 2), (left.size(), left.num_classes()));
+    /// ```
+    pub fn absorb<R: Backend<Key = T>>(&mut self, other: UnionFind<T, R>) -> usize {
+        let (size, classes) = (self.size(), self.num_classes());
+        for (leader, members) in other.into_groups() {
+            let leader = self.insert(leader.as_ref().clone());
+            for member in members {
+                let member = self.insert(member);
+                self.union_ids(leader, member);
+            }
+        }
+        // Every new element arrived as a class of its own.
+        classes + (self.size() - size) - self.num_classes()
+    }
+
+    /// Points every element directly at its leader, so later finds are a single step.
+    pub fn compress_all(&self) {
+        let _ = self.compress_all_with(|_| ControlFlow::Continue(()));
+    }
+
+    /// Like [UnionFind::compress_all], calling `progress` with the number of elements done
+    /// after every element, in insertion order. Stopping early leaves the rest uncompressed,
+    /// which is still a valid forest.
+    pub fn compress_all_with<F: FnMut(usize) -> ControlFlow<()>>(&self, mut progress: F) -> ControlFlow<()> {
+        for i in 0..self.size() {
+            self.access().leader(i);
+            // The borrow is released while the callback runs, so it may query the partition.
+            if progress(i + 1).is_break() {
+                return ControlFlow::Break(());
+            }
+        }
+        ControlFlow::Continue(())
+    }
+
+    /// Compresses every path as [UnionFind::compress_all] does and releases spare capacity
+    /// with [UnionFind::shrink_to_fit], to pay for both once before freezing, serializing
+    /// or keeping the structure around. Finds are a single step until the next union.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use hash_unionfind::UnionFind;
+    ///
+    /// let mut uf = UnionFind::from_edge_list((0..100).map(|i| (i, i + 1)));
+    /// uf.normalize();
+    /// assert_eq!(100.0 / 101.0, uf.stats().average_find_depth);
+    /// ```
+    pub fn normalize(&mut self) {
+        self.flatten();
+        self.shrink_to_fit();
+    }
+
+    // Points every element at its leader, without the borrow juggling of compress_all_with.
+    pub(super) fn flatten(&mut self) {
+        let ps = self.parents.get_mut();
+        for i in 0..ps.len() {
+            Self::find_index_in(ps, i);
+        }
+    }
+
+    /// Releases the spare capacity of the map and of the opt-in recordings, such as pending
+    /// [UnionFind::take_updates] batches.
+    pub fn shrink_to_fit(&mut self) {
+        self.parents.get_mut().shrink_to_fit();
+        for pending in self.deposed.iter_mut().chain(&mut self.dirty) {
+            pending.shrink_to_fit();
+        }
+        if let Some(weights) = &mut self.weights {
+            weights.shrink_to_fit();
+        }
+        if let Some(updates) = &mut self.updates {
+            updates.shrink_to_fit();
+        }
+        if let Some(merges) = &mut self.merges {
+            merges.shrink_to_fit();
+        }
+        if let Some(index) = &mut self.member_index {
+            index.shrink_to_fit();
+        }
+    }
+}
+
+impl<T: Hash + Eq + Clone + Debug> UnionFind<T> {
+    /// Builds the connected components of an edge list, inserting the endpoints as they
+    /// appear. See [UnionFind::from_edges_par] for a parallel version.
+    pub fn from_edge_list<I: IntoIterator<Item = (T, T)>>(edges: I) -> Self {
+        match Self::from_edge_list_with(edges, |_| ControlFlow::Continue(())) {
+            ControlFlow::Continue(uf) | ControlFlow::Break(uf) => uf,
+        }
+    }
+
+    /// Like [UnionFind::from_edge_list], calling `progress` with the number of edges done
+    /// after every edge. Once it breaks, the components of the edges done so far are returned
+    /// in [ControlFlow::Break].
+    pub fn from_edge_list_with<I, F>(edges: I, mut progress: F) -> ControlFlow<Self, Self>
+    where
+        I: IntoIterator<Item = (T, T)>,
+        F: FnMut(usize) -> ControlFlow<()>,
+    {
+        let mut uf = Self::new();
+        for (done, (x, y)) in edges.into_iter().enumerate() {
+            uf.union_insert(x, y);
+            if progress(done + 1).is_break() {
+                return ControlFlow::Break(uf);
+            }
+        }
+        ControlFlow::Continue(uf)
+    }
+}
+
+/// Inserts every element as a singleton, as [UnionFind::insert_many] does.
+impl<T: Hash + Eq + Clone + Debug, B: Backend<Key = T>> Extend<T> for UnionFind<T, B> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        self.insert_many(iter);
+    }
+}
+
+/// Collects the connected components of an edge list, as [UnionFind::from_edge_list] does.
+///
+/// # Examples
+///
+/// ```
+/// use hash_unionfind::UnionFind;
+///
+/// let mut uf: UnionFind<_> = [("a", "b"), ("c", "d"), ("b", "d")].into_iter().collect();
+/// uf.extend(["e"]);
+/// assert_eq!((5, 2), (uf.size(), uf.num_classes()));
+/// ```
+impl<T: Hash + Eq + Clone + Debug> FromIterator<(T, T)> for UnionFind<T> {
+    fn from_iter<I: IntoIterator<Item = (T, T)>>(edges: I) -> Self {
+        Self::from_edge_list(edges)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn stop_partway() {
+        let edges: Vec<_> = (0..10).map(|i| (i, i + 1)).collect();
+        let ControlFlow::Break(partial) = UnionFind::from_edge_list_with(edges.clone(), |done| {
+            if done == 4 { ControlFlow::Break(()) } else { ControlFlow::Continue(()) }
+        }) else {
+            panic!("not stopped");
+        };
+        assert_eq!((5, 1), (partial.size(), partial.num_classes()));
+
+        let mut uf: UnionFind<_> = edges.into_iter().collect();
+        assert_eq!((11, 1), (uf.size(), uf.num_classes()));
+        uf.extend(20..30);
+        assert_eq!(5, uf.union_pairs([(20, 21), (22, 23), (21, 23), (20, 23), (24, 99), (25, 26), (27, 28)]));
+
+        let mut seen = 0;
+        assert_eq!(ControlFlow::Break(()), uf.compress_all_with(|done| {
+            seen = done;
+            assert!(uf.find(&0).is_some());
+            if done == 3 { ControlFlow::Break(()) } else { ControlFlow::Continue(()) }
+        }));
+        assert_eq!(3, seen);
+        uf.compress_all();
+        // Every element but the leaders is one step away.
+        assert_eq!((21 - 6) as f64 / 21.0, uf.stats().average_find_depth);
+
+        let mut normalized = UnionFind::with_capacity(1000);
+        normalized.extend(0..21);
+        normalized.union_pairs([(0, 1), (2, 3), (1, 3), (4, 5), (3, 5)]);
+        normalized.normalize();
+        assert!(normalized.capacity() < 64);
+        assert_eq!(5.0 / 21.0, normalized.stats().average_find_depth);
+    }
+}
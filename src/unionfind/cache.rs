@@ -0,0 +1,194 @@
+use std::{fmt::Debug, hash::Hash, rc::Rc};
+use indexmap::{map::Entry, IndexMap};
+
+use crate::Backend;
+
+use super::UnionFind;
+
+/// Values memoized per class of a [UnionFind], which never serves a value computed for a
+/// class that has since merged.
+///
+/// Every value is stored with the [class version](UnionFind::class_version) it was computed
+/// at, so a union made directly on the union-find invalidates the values of both classes.
+/// Unions made through [ClassCache::union_with] instead combine the two values into one for
+/// the merged class.
+///
+/// # Examples
+///
+/// ```
+/// use hash_unionfind::{ClassCache, UnionFind};
+///
+/// let mut uf = UnionFind::new();
+/// uf.insert_many(0..4);
+/// let mut sums = ClassCache::new();
+/// for i in 0..4 {
+///     sums.insert(&uf, &i, i);
+/// }
+///
+/// sums.union_with(&mut uf, &0, &1, |a, b| a + b);
+/// assert_eq!(Some(&1), sums.get(&uf, &1));
+///
+/// // A direct union invalidates instead.
+/// uf.union(&2, &3);
+/// assert_eq!(None, sums.get(&uf, &2));
+/// assert_eq!(&5, sums.get_or_insert_with(&uf, &3, |_| 2 + 3).unwrap());
+/// ```
+#[derive(Debug, Clone)]
+pub struct ClassCache<T, V> {
+    // By leader, with the class version the value is valid for.
+    entries: IndexMap<Rc<T>, (u64, V)>,
+}
+
+impl<T: Hash + Eq, V> Default for ClassCache<T, V> {
+    fn default() -> Self {
+        Self { entries: IndexMap::new() }
+    }
+}
+
+impl<T: Hash + Eq + Clone + Debug, B: Backend<Key = T>> UnionFind<T, B> {
+    // The leader and version of the class of x, under a single borrow.
+    fn leader_version(&self, x: &T) -> Option<(Rc<T>, u64)> {
+        self.with_leader(x, |ps, leader| (Self::handle_in(ps, leader).clone(), ps[leader].version))
+    }
+}
+
+impl<T: Hash + Eq + Clone + Debug, V> ClassCache<T, V> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The value cached for the class of x, None if there is none, it is stale, or x is
+    /// missing from `uf`.
+    pub fn get<B: Backend<Key = T>>(&self, uf: &UnionFind<T, B>, x: &T) -> Option<&V> {
+        let (leader, version) = uf.leader_version(x)?;
+        match self.entries.get(&leader) {
+            Some((valid, value)) if *valid == version => Some(value),
+            _ => None,
+        }
+    }
+
+    /// Caches value for the current class of x, replacing any previous value. Returns false
+    /// and drops the value if x is missing from `uf`.
+    pub fn insert<B: Backend<Key = T>>(&mut self, uf: &UnionFind<T, B>, x: &T, value: V) -> bool {
+        let Some((leader, version)) = uf.leader_version(x) else {
+            return false;
+        };
+        self.entries.insert(leader, (version, value));
+        true
+    }
+
+    /// The value cached for the class of x, computing it from the leader when there is none
+    /// or it is stale. None if x is missing from `uf`.
+    pub fn get_or_insert_with<B, F>(&mut self, uf: &UnionFind<T, B>, x: &T, compute: F) -> Option<&V>
+    where
+        B: Backend<Key = T>,
+        F: FnOnce(&Rc<T>) -> V,
+    {
+        let (leader, version) = uf.leader_version(x)?;
+        let entry = match self.entries.entry(leader) {
+            Entry::Occupied(entry) if entry.get().0 == version => entry.into_mut(),
+            Entry::Occupied(mut entry) => {
+                let value = compute(entry.key());
+                entry.insert((version, value));
+                entry.into_mut()
+            }
+            Entry::Vacant(entry) => {
+                let value = compute(entry.key());
+                entry.insert((version, value))
+            }
+        };
+        Some(&entry.1)
+    }
+
+    /// Unions x and y in `uf`, caching `combine` of both values for the merged class when both
+    /// classes had a valid value. Returns the new leader, or None if one of them is missing.
+    pub fn union_with<B, F>(&mut self, uf: &mut UnionFind<T, B>, x: &T, y: &T, combine: F) -> Option<Rc<T>>
+    where
+        B: Backend<Key = T>,
+        F: FnOnce(V, V) -> V,
+    {
+        let (x_leader, x_version) = uf.leader_version(x)?;
+        let (y_leader, y_version) = uf.leader_version(y)?;
+        if x_leader == y_leader {
+            return Some(x_leader);
+        }
+        let mut take = |leader: &Rc<T>, version| match self.entries.swap_remove(leader) {
+            Some((valid, value)) if valid == version => Some(value),
+            _ => None,
+        };
+        let values = take(&x_leader, x_version).zip(take(&y_leader, y_version));
+        let leader = uf.union(&x_leader, &y_leader).unwrap();
+        if let Some((x_value, y_value)) = values {
+            let version = uf.class_version(&leader).unwrap();
+            self.entries.insert(leader.clone(), (version, combine(x_value, y_value)));
+        }
+        Some(leader)
+    }
+
+    /// Drops every stale value, and values of classes missing from `uf`.
+    pub fn prune<B: Backend<Key = T>>(&mut self, uf: &UnionFind<T, B>) {
+        self.entries.retain(|leader, (valid, _)| uf.leader_version(leader) == Some((leader.clone(), *valid)));
+    }
+
+    /// The number of cached values, including stale ones not pruned yet.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn stale_after_direct_union() {
+        let mut uf = UnionFind::new();
+        uf.insert_many(0..6);
+        let mut cache = ClassCache::new();
+        assert!(cache.insert(&uf, &0, "zero"));
+        assert!(cache.insert(&uf, &2, "two"));
+        assert!(cache.insert(&uf, &4, "four"));
+        assert!(!cache.insert(&uf, &9, "nine"));
+
+        // The leader keeps its key but its class grew.
+        uf.union(&0, &1);
+        assert_eq!(None, cache.get(&uf, &0));
+        assert_eq!(None, cache.get(&uf, &1));
+        assert_eq!(Some(&"two"), cache.get(&uf, &2));
+
+        // Only one side valid, nothing is combined.
+        assert!(cache.union_with(&mut uf, &1, &2, |a, b| if a < b { a } else { b }).is_some());
+        assert_eq!(None, cache.get(&uf, &2));
+
+        uf.union(&5, &3);
+        cache.prune(&uf);
+        assert_eq!(1, cache.len());
+        assert_eq!(Some(&"four"), cache.get(&uf, &4));
+    }
+
+    #[test]
+    fn combine_through_cache() {
+        let mut uf = UnionFind::new();
+        uf.insert_many(0..4);
+        let mut mins = ClassCache::new();
+        let mut computed = 0;
+        for i in 0..4 {
+            mins.get_or_insert_with(&uf, &i, |leader| {
+                computed += 1;
+                **leader
+            });
+        }
+        mins.union_with(&mut uf, &3, &2, |a: i32, b| a.min(b));
+        mins.union_with(&mut uf, &1, &3, |a, b| a.min(b));
+        assert_eq!(Some(&1), mins.get(&uf, &2));
+        // Still valid, nothing to recompute.
+        assert_eq!(&1, mins.get_or_insert_with(&uf, &3, |_| unreachable!()).unwrap());
+        assert_eq!(4, computed);
+        assert_eq!(2, mins.len());
+        assert_eq!(Some(uf.find(&0).unwrap()), mins.union_with(&mut uf, &0, &0, |_, _| unreachable!()));
+    }
+}
@@ -0,0 +1,349 @@
+use std::{
+    fmt::{self, Debug, Display},
+    hash::Hash,
+};
+use indexmap::IndexSet;
+
+use crate::Backend;
+
+use super::{Index, Rank, UnionFind};
+
+/// A point to return to with [UnionFind::rollback], taken by [UnionFind::snapshot].
+///
+/// Checkpoints nest: rolling back to one also discards every checkpoint taken after it, and
+/// [UnionFind::commit] keeps the changes since it while folding them into the previous
+/// checkpoint.
+#[derive(Debug, PartialEq, Eq)]
+#[must_use = "the changes since a checkpoint are recorded until it is rolled back or committed"]
+pub struct Checkpoint {
+    // The number of checkpoints open before this one.
+    depth: usize,
+}
+
+/// Returned by operations that rollbacks cannot undo, such as [UnionFind::reorder_by_class],
+/// when called while a checkpoint is open. Commit or roll back the checkpoint first.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CheckpointOpen;
+
+impl Display for CheckpointOpen {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "a checkpoint is open, and this change could not be rolled back")
+    }
+}
+
+impl std::error::Error for CheckpointOpen {}
+
+// Changes since the oldest open checkpoint, undone in reverse.
+#[derive(Debug, Clone)]
+pub(super) struct Journal {
+    changes: Vec<Change>,
+    // The length of changes when each open checkpoint was taken.
+    marks: Vec<usize>,
+}
+
+#[derive(Debug, Clone)]
+enum Change {
+    Insert,
+    // A link of absorbed under kept, with the data of kept before it.
+    Link {
+        kept: Index,
+        absorbed: Index,
+        rank: Rank,
+        class_weight: f64,
+        // The index of the merge recorded in the history, if recording.
+        merge: Option<usize>,
+    },
+    // A new weight of element, with its old weight and the old total of its class.
+    Weight {
+        element: Index,
+        leader: Index,
+        own: f64,
+        class: f64,
+    },
+}
+
+impl<T: Hash + Eq + Clone + Debug, B: Backend<Key = T>> UnionFind<T, B> {
+    /// Takes a checkpoint, starting to record inserts, unions and weight changes so
+    /// [UnionFind::rollback] can undo them. Recording adds O(1) per change, and path
+    /// compression goes on as usual.
+    ///
+    /// The recordings of [UnionFind::record_history],
+    /// [UnionFind::track_updates], [UnionFind::track_merges] and [UnionFind::index_members]
+    /// are rolled back with the partition. [UnionFind::reorder_by_class] refuses to run while
+    /// checkpoints are open.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use hash_unionfind::UnionFind;
+    ///
+    /// let mut uf = UnionFind::new();
+    /// uf.insert_many(0..4);
+    /// uf.union(&0, &1);
+    ///
+    /// let decision = uf.snapshot();
+    /// uf.union(&1, &2);
+    /// uf.insert(4);
+    /// uf.union(&4, &3);
+    /// assert_eq!(uf.find(&0), uf.find(&2));
+    ///
+    /// uf.rollback(decision);
+    /// assert_ne!(uf.find(&0), uf.find(&2));
+    /// assert_eq!(None, uf.find(&4));
+    /// assert_eq!(3, uf.num_classes());
+    /// ```
+    pub fn snapshot(&mut self) -> Checkpoint {
+        let journal = self.journal.get_or_insert_with(|| Journal { changes: Vec::new(), marks: Vec::new() });
+        journal.marks.push(journal.changes.len());
+        Checkpoint { depth: journal.marks.len() - 1 }
+    }
+
+    /// Keeps the changes since checkpoint. They can still be undone by rolling back an older
+    /// checkpoint, and recording stops once no checkpoint is open.
+    ///
+    /// Panics if the checkpoint was already discarded by rolling back an older one.
+    pub fn commit(&mut self, checkpoint: Checkpoint) {
+        let journal = self.open_journal(&checkpoint);
+        journal.marks.truncate(checkpoint.depth);
+        if journal.marks.is_empty() {
+            self.journal = None;
+        }
+    }
+
+    /// Undoes every insert, union and weight change since checkpoint, and discards the checkpoints taken
+    /// after it. Takes time proportional to the undone changes and the classes they touched.
+    ///
+    /// Class versions of the restored classes increase, so caches keyed by
+    /// [UnionFind::class_version] never serve values computed after the checkpoint.
+    ///
+    /// Panics if the checkpoint was already discarded by rolling back an older one.
+    pub fn rollback(&mut self, checkpoint: Checkpoint) {
+        let journal = self.open_journal(&checkpoint);
+        let changes = journal.changes.split_off(journal.marks[checkpoint.depth]);
+        journal.marks.truncate(checkpoint.depth);
+        if journal.marks.is_empty() {
+            self.journal = None;
+        }
+
+        // Restored leaders whose members may be compressed past an undone link.
+        let mut touched = IndexSet::new();
+        let mut inserted = 0;
+        for change in changes.into_iter().rev() {
+            match change {
+                Change::Insert => inserted += 1,
+                Change::Link { kept, absorbed, rank, class_weight, merge } => {
+                    let (kept, absorbed) = (crate::wide(kept), crate::wide(absorbed));
+                    self.unlink(kept, absorbed, rank, class_weight, merge);
+                    touched.insert(kept);
+                    touched.insert(absorbed);
+                }
+                Change::Weight { element, leader, own, class } => {
+                    // set_weight makes the table right after journaling the change.
+                    let weights = self.weights.as_mut().unwrap();
+                    weights[crate::wide(element)].own = own;
+                    weights[crate::wide(leader)].class = class;
+                }
+            }
+        }
+
+        let ps = self.parents.get_mut();
+        let removed: Vec<T> = (0..inserted).map(|_| ps.pop().unwrap()).collect();
+        if let Some(updates) = &mut self.updates {
+            updates.extend(removed.iter().rev().map(|t| ((t.clone(), t.clone()), -1)));
+        }
+        self.classes -= inserted;
+        if let Some(weights) = &mut self.weights {
+            weights.truncate(ps.len());
+        }
+
+        touched.retain(|&leader| leader < ps.len());
+        for &leader in &touched {
+            for member in Self::member_indices_in(ps, leader) {
+                ps[member].parent = crate::small(leader);
+            }
+            self.clock += 1;
+            ps[leader].version = self.clock;
+        }
+        if let Some(dirty) = &mut self.dirty {
+            dirty.retain(|&d| crate::wide(d) < ps.len());
+            dirty.extend(touched.iter().map(|&leader| crate::small(leader)));
+        }
+        if let Some(deposed) = &mut self.deposed {
+            deposed.retain(|&d| ps.get_index(crate::wide(d)).is_some_and(|(_, node)| node.parent != d));
+        }
+        if self.member_index.take().is_some() {
+            self.index_members();
+        }
+    }
+
+    fn open_journal(&mut self, checkpoint: &Checkpoint) -> &mut Journal {
+        match &mut self.journal {
+            Some(journal) if checkpoint.depth < journal.marks.len() => journal,
+            _ => panic!("the checkpoint was already discarded by an older rollback"),
+        }
+    }
+
+    // Undoes the link of absorbed under kept, the last link not undone yet.
+    fn unlink(&mut self, kept: usize, absorbed: usize, rank: Rank, class_weight: f64, merge: Option<usize>) {
+        let ps = self.parents.get_mut();
+        let absorbed_size = ps[absorbed].size;
+        // Swapping the successors again splits the member cycles spliced by the link.
+        let kept_next = ps[kept].next;
+        let absorbed_node = &mut ps[absorbed];
+        absorbed_node.parent = crate::small(absorbed);
+        let absorbed_next = std::mem::replace(&mut absorbed_node.next, kept_next);
+        let kept_node = &mut ps[kept];
+        kept_node.next = absorbed_next;
+        kept_node.rank = rank;
+        kept_node.size -= absorbed_size;
+        if let Some(weights) = &mut self.weights {
+            weights[kept].class = class_weight;
+        }
+        self.classes += 1;
+
+        if let Some(updates) = &mut self.updates {
+            let (kept_key, absorbed_key) = (ps.get_index(kept).unwrap().0, ps.get_index(absorbed).unwrap().0);
+            for member in Self::member_indices_in(ps, absorbed) {
+                let member = ps.get_index(member).unwrap().0;
+                updates.push(((member.clone(), kept_key.clone()), -1));
+                updates.push(((member.clone(), absorbed_key.clone()), 1));
+            }
+        }
+        if let (Some(history), Some(merge)) = (&mut self.history, merge) {
+            history.unrecord(merge, Self::handle_in(ps, kept), Self::handle_in(ps, absorbed));
+        }
+        self.unlog_merge(kept, absorbed);
+    }
+
+    // Called by set_weight before it changes anything, leader being the leader of element.
+    pub(super) fn journal_weight(&mut self, element: usize, leader: usize) {
+        if let Some(journal) = &mut self.journal {
+            let (own, class) = match &self.weights {
+                Some(weights) => (weights[element].own, weights[leader].class),
+                None => (0.0, 0.0),
+            };
+            let (element, leader) = (crate::small(element), crate::small(leader));
+            journal.changes.push(Change::Weight { element, leader, own, class });
+        }
+    }
+
+    pub(super) fn journal_insert(&mut self) {
+        if let Some(journal) = &mut self.journal {
+            journal.changes.push(Change::Insert);
+        }
+    }
+
+    // Called by link before it changes anything.
+    pub(super) fn journal_link(&mut self, kept: usize, absorbed: usize) {
+        if let Some(journal) = &mut self.journal {
+            let node = &self.parents.get_mut()[kept];
+            journal.changes.push(Change::Link {
+                kept: crate::small(kept),
+                absorbed: crate::small(absorbed),
+                rank: node.rank,
+                class_weight: self.weights.as_ref().map_or(0.0, |weights| weights[kept].class),
+                merge: self.history.as_ref().map(|history| history.merges().len()),
+            });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::rc::Rc;
+    use crate::ClassState;
+
+    #[test]
+    fn nested_rollbacks_restore_the_partition() {
+        let mut uf = UnionFind::new();
+        uf.insert_many(0..6);
+        uf.union(&0, &1);
+        uf.index_members();
+        let before = uf.to_canonical_vec();
+        let version = uf.class_version(&0).unwrap();
+
+        let outer = uf.snapshot();
+        uf.union(&2, &3);
+        let inner = uf.snapshot();
+        uf.insert_many(6..9);
+        uf.union(&7, &0);
+        uf.union(&3, &7);
+        uf.union(&8, &5);
+        // Compress across the links that are going to be undone.
+        uf.compress_all();
+        uf.rollback(inner);
+        assert_eq!(vec![vec![0, 1], vec![2, 3], vec![4], vec![5]], uf.to_canonical_vec());
+        assert_eq!(Some(2), uf.class_size(&1));
+        assert_eq!(4, uf.num_classes());
+
+        let kept = uf.snapshot();
+        uf.union(&4, &5);
+        uf.commit(kept);
+        uf.rollback(outer);
+        assert_eq!(before, uf.to_canonical_vec());
+        assert_eq!(5, uf.num_classes());
+        assert_eq!(vec![&0, &1], uf.members(&1).collect::<Vec<_>>());
+        assert!(uf.class_version(&0).unwrap() > version);
+        assert!(uf.journal.is_none());
+        assert_eq!(Ok(()), uf.verify_partition(before));
+    }
+
+    #[test]
+    fn recordings_are_rolled_back() {
+        let mut uf = UnionFind::new();
+        uf.insert_many(0..3);
+        uf.record_history();
+        uf.union(&0, &1);
+        uf.track_updates();
+        uf.take_updates();
+        let merges = uf.history().unwrap().merges().to_vec();
+
+        let checkpoint = uf.snapshot();
+        uf.insert(3);
+        uf.union(&3, &2);
+        uf.union(&1, &2);
+        uf.rollback(checkpoint);
+        assert_eq!(merges, uf.history().unwrap().merges());
+        assert_eq!(ClassState::Merge(0), uf.history().unwrap().state_of(&Rc::new(0)));
+        assert_eq!(ClassState::Initial(Rc::new(2)), uf.history().unwrap().state_of(&Rc::new(2)));
+
+        // Replaying the updates leaves nothing changed.
+        let mut net = std::collections::HashMap::new();
+        for (pair, diff) in uf.take_updates().unwrap() {
+            *net.entry(pair).or_insert(0) += diff;
+        }
+        assert!(net.values().all(|diff| *diff == 0));
+    }
+
+    #[test]
+    fn weights_are_rolled_back() {
+        let mut uf = UnionFind::new();
+        uf.insert_weighted(0, 1.0);
+        uf.insert_many(1..3);
+        uf.union(&1, &2);
+
+        let checkpoint = uf.snapshot();
+        uf.union(&0, &1);
+        uf.set_weight(&0, 5.0);
+        uf.set_weight(&1, 7.0);
+        uf.insert_weighted(3, 2.0);
+        uf.union(&3, &2);
+        uf.rollback(checkpoint);
+        assert_eq!((Some(1.0), Some(1.0)), (uf.weight(&0), uf.class_weight(&0)));
+        assert_eq!((Some(0.0), Some(0.0)), (uf.weight(&1), uf.class_weight(&2)));
+        assert_eq!(None, uf.weight(&3));
+        uf.insert(3);
+        assert_eq!(Some(0.0), uf.class_weight(&3));
+    }
+
+    #[test]
+    #[should_panic(expected = "already discarded")]
+    fn stale_checkpoints_panic() {
+        let mut uf: UnionFind<u8> = UnionFind::new();
+        let outer = uf.snapshot();
+        let inner = uf.snapshot();
+        uf.rollback(outer);
+        uf.rollback(inner);
+    }
+}
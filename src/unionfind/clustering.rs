@@ -0,0 +1,202 @@
+use std::{borrow::Borrow, cmp::Ordering, fmt::Debug, hash::Hash, rc::Rc};
+use indexmap::IndexMap;
+
+use crate::{Backend, HashBackend};
+
+use super::UnionFind;
+
+impl<T: Hash + Eq + Clone + Debug, B: Backend<Key = T>> UnionFind<T, B> {
+    /// Applies the unions in `pairs` in order until at most `k` classes remain, ignoring the
+    /// rest of the pairs. Pairs with missing elements are skipped. Returns the number of
+    /// classes at the end, which is more than `k` if the pairs ran out first.
+    pub fn union_until<I: IntoIterator<Item = (T, T)>>(&mut self, k: usize, pairs: I) -> usize {
+        for (x, y) in pairs {
+            if self.num_classes() <= k {
+                break;
+            }
+            self.union(&x, &y);
+        }
+        self.num_classes()
+    }
+}
+
+impl<T: Hash + Eq + Clone + Debug> UnionFind<T> {
+    /// Clusters `elements` into `k` groups by their cheapest links (single linkage, as in
+    /// Kruskal's algorithm): edges are applied in ascending score order until `k` classes
+    /// remain. Incomparable scores (NaN) are ordered arbitrarily.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use hash_unionfind::UnionFind;
+    ///
+    /// let edges = vec![(0.5, 'a', 'b'), (9.0, 'b', 'c'), (0.1, 'c', 'd'), (3.0, 'a', 'd')];
+    /// let clusters = UnionFind::k_clusters("abcd".chars(), edges, 2);
+    /// assert_eq!(2, clusters.num_classes());
+    /// assert_eq!(clusters.find(&'a'), clusters.find(&'b'));
+    /// assert_eq!(clusters.find(&'c'), clusters.find(&'d'));
+    /// ```
+    pub fn k_clusters<S, I, E>(elements: I, edges: E, k: usize) -> Self
+    where
+        S: PartialOrd,
+        I: IntoIterator<Item = T>,
+        E: IntoIterator<Item = (S, T, T)>,
+    {
+        let mut uf = Self::new();
+        uf.insert_many(elements);
+        let mut edges: Vec<_> = edges.into_iter().collect();
+        edges.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap_or(Ordering::Equal));
+        uf.union_until(k, edges.into_iter().map(|(_, x, y)| (x, y)));
+        uf
+    }
+}
+
+/// A driver for agglomerative clustering policies on top of [UnionFind].
+///
+/// Every class carries an aggregate `A` (e.g. a bounding box), built from its elements with
+/// `init` and merged with `combine`. Candidate merges `(score, a, b)` are applied only when a
+/// predicate over the score and the two current class aggregates accepts them.
+///
+/// # Examples
+///
+/// ```
+/// use hash_unionfind::Agglomerative;
+///
+/// // Cluster points on a line, refusing merges whose diameter would exceed 2.
+/// let points = [0, 1, 2, 3, 10, 11];
+/// let mut clustering = Agglomerative::new(points, |p| (*p, *p), |a, b| (a.0.min(b.0), a.1.max(b.1)));
+/// let candidates = vec![(1, 0, 1), (1, 1, 2), (1, 2, 3), (7, 3, 10), (1, 10, 11)];
+/// clustering.merge_if(candidates, |_, a, b| a.1.max(b.1) - a.0.min(b.0) <= 2);
+///
+/// assert_eq!(Some(&(0, 2)), clustering.aggregate(&1));
+/// assert_eq!(Some(&(3, 3)), clustering.aggregate(&3));
+/// assert_eq!(Some(&(10, 11)), clustering.aggregate(&10));
+/// ```
+#[derive(Debug, Clone)]
+pub struct Agglomerative<T: Hash + Eq + Clone + Debug, A, C, B = HashBackend<T>> {
+    uf: UnionFind<T, B>,
+    // The aggregate of every class, by leader.
+    aggregates: IndexMap<Rc<T>, A>,
+    combine: C,
+}
+
+impl<T, A, C> Agglomerative<T, A, C>
+where
+    T: Hash + Eq + Clone + Debug,
+    C: FnMut(A, A) -> A,
+{
+    /// Starts with every element in its own class with aggregate `init(element)`.
+    pub fn new<I, F>(elements: I, init: F, combine: C) -> Self
+    where
+        I: IntoIterator<Item = T>,
+        F: FnMut(&T) -> A,
+    {
+        Self::with_backend(HashBackend::default(), elements, init, combine)
+    }
+}
+
+impl<T, A, C, B> Agglomerative<T, A, C, B>
+where
+    T: Hash + Eq + Clone + Debug,
+    C: FnMut(A, A) -> A,
+    B: Backend<Key = T>,
+{
+    /// Like [Agglomerative::new], keeping the elements in backend. Keys already in backend
+    /// start in their own classes as well.
+    pub fn with_backend<I, F>(backend: B, elements: I, mut init: F, combine: C) -> Self
+    where
+        I: IntoIterator<Item = T>,
+        F: FnMut(&T) -> A,
+    {
+        let mut uf = UnionFind::with_backend(backend);
+        uf.insert_many(elements);
+        let aggregates = uf.elements().iter().map(|t| (uf.find(t).unwrap(), init(t))).collect();
+        Self { uf, aggregates, combine }
+    }
+
+    /// Merges the classes of a and b if they are distinct and `accept(score, a, b)` holds
+    /// for their aggregates. Returns whether a merge happened.
+    pub fn merge_one<S, P>(&mut self, score: &S, a: &T, b: &T, mut accept: P) -> bool
+    where
+        P: FnMut(&S, &A, &A) -> bool,
+    {
+        let (Some(a), Some(b)) = (self.uf.find(a), self.uf.find(b)) else {
+            return false;
+        };
+        if a == b || !accept(score, &self.aggregates[&a], &self.aggregates[&b]) {
+            return false;
+        }
+        let a_aggregate = self.aggregates.swap_remove(&a).unwrap();
+        let b_aggregate = self.aggregates.swap_remove(&b).unwrap();
+        let leader = self.uf.union(&a, &b).unwrap();
+        self.aggregates.insert(leader, (self.combine)(a_aggregate, b_aggregate));
+        true
+    }
+
+    /// Considers the candidates in order, see [Agglomerative::merge_one]. Candidates are not
+    /// sorted, pass them sorted by score for greedy policies. Returns the number of merges.
+    pub fn merge_if<S, I, P>(&mut self, candidates: I, mut accept: P) -> usize
+    where
+        I: IntoIterator<Item = (S, T, T)>,
+        P: FnMut(&S, &A, &A) -> bool,
+    {
+        candidates
+            .into_iter()
+            .filter(|(score, a, b)| self.merge_one(score, a, b, &mut accept))
+            .count()
+    }
+
+    /// The aggregate of the class of x, or None if x is missing.
+    pub fn aggregate<Q: Hash + Eq + ?Sized>(&self, x: &Q) -> Option<&A>
+    where
+        T: Borrow<Q>,
+    {
+        self.aggregates.get(&self.uf.find(x)?)
+    }
+
+    pub fn union_find(&self) -> &UnionFind<T, B> {
+        &self.uf
+    }
+
+    /// The clustering and the aggregate of every class by leader.
+    pub fn into_parts(self) -> (UnionFind<T, B>, IndexMap<Rc<T>, A>) {
+        (self.uf, self.aggregates)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn union_until() {
+        let mut uf = UnionFind::new();
+        uf.insert_many(0..6);
+        assert_eq!(3, uf.union_until(3, vec![(0, 1), (9, 1), (1, 0), (2, 3), (4, 5), (0, 5)]));
+        assert_eq!(uf.find(&2), uf.find(&3));
+        assert_ne!(uf.find(&0), uf.find(&5));
+
+        assert_eq!(2, uf.union_until(1, vec![(0, 2)]));
+        assert_eq!(2, uf.union_until(5, vec![(0, 4)]));
+    }
+
+    #[test]
+    fn agglomerative_by_class_size() {
+        // Merge while the merged class stays at most 3 elements.
+        let mut clustering = Agglomerative::new(0..7, |_| 1usize, |a, b| a + b);
+        let candidates: Vec<_> = (0..6).map(|i| ((), i, i + 1)).collect();
+        assert_eq!(4, clustering.merge_if(candidates, |_, a, b| a + b <= 3));
+        let (uf, aggregates) = clustering.into_parts();
+        assert_eq!(3, uf.num_classes());
+        let mut sizes: Vec<usize> = aggregates.values().copied().collect();
+        sizes.sort();
+        assert_eq!(vec![1, 3, 3], sizes);
+        assert_eq!(uf.find(&3), uf.find(&5));
+
+        // The same policy over dense keys.
+        let mut dense = Agglomerative::with_backend(crate::DenseBackend::default(), 0u32..7, |_| 1usize, |a, b| a + b);
+        let candidates: Vec<_> = (0..6).map(|i| ((), i, i + 1)).collect();
+        assert_eq!(4, dense.merge_if(candidates, |_, a, b| a + b <= 3));
+        assert_eq!(Some(&3), dense.aggregate(&5));
+    }
+}
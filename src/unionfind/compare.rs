@@ -0,0 +1,194 @@
+use std::{cmp::Ordering, collections::hash_map::DefaultHasher, fmt::Debug, hash::{Hash, Hasher}, rc::Rc};
+use indexmap::{map::Entry, IndexMap};
+
+use crate::Backend;
+
+use super::UnionFind;
+
+// Partitions are compared semantically: by their elements and the classes they induce,
+// never by the shape of the forest, which depends on union order and path compression.
+impl<T: Hash + Eq + Clone + Debug, B: Backend<Key = T>> UnionFind<T, B> {
+    /// Whether every element of self is in other and every class of self is inside a class
+    /// of other, in other words self is below other in the refinement order, as `<=` checks.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use hash_unionfind::uf;
+    ///
+    /// let optimized = uf! { {1, 2}, {3}, {4} };
+    /// let reference = uf! { {4}, {3, 2, 1} };
+    /// assert!(optimized.is_refinement_of(&reference));
+    /// assert!(!reference.is_refinement_of(&optimized));
+    /// assert!(!optimized.same_partition(&reference));
+    /// ```
+    pub fn is_refinement_of<R: Backend<Key = T>>(&self, other: &UnionFind<T, R>) -> bool {
+        let ps = self.parents.borrow();
+        let other_ps = other.parents.borrow();
+        let mut class_in_other = IndexMap::new();
+        for t in ps.keys() {
+            let Some(other_leader) = UnionFind::<T, R>::root_in(&other_ps, t) else {
+                return false;
+            };
+            let leader = Self::root_in(&ps, t).unwrap();
+            if class_in_other.entry(leader).or_insert(other_leader) != &other_leader {
+                return false;
+            }
+        }
+        true
+    }
+
+    /// Whether self and other have the same elements partitioned into the same classes,
+    /// whatever their leaders and forests, as `==` checks.
+    pub fn same_partition<R: Backend<Key = T>>(&self, other: &UnionFind<T, R>) -> bool {
+        self.size() == other.size() && self.num_classes() == other.num_classes() && self.is_refinement_of(other)
+    }
+
+    /// The common refinement of self and other: the shared elements, two of them in the same
+    /// class when they are in both. Elements keep the insertion order of self.
+    ///
+    /// With ascent's `Lattice` in scope, which has a `meet` by value, call it as
+    /// `UnionFind::meet(&a, &b)`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use hash_unionfind::uf;
+    ///
+    /// let by_type = uf! { {"x", "y", "z"}, {"s"} };
+    /// let by_region = uf! { {"x", "y"}, {"z", "s"}, {"t"} };
+    /// let meet = by_type.meet(&by_region);
+    /// assert_eq!(vec![vec!["s"], vec!["x", "y"], vec!["z"]], meet.to_canonical_vec());
+    /// ```
+    pub fn meet<R: Backend<Key = T>>(&self, other: &UnionFind<T, R>) -> Self
+    where
+        B: Default,
+    {
+        let ps = self.parents.borrow();
+        let other_ps = other.parents.borrow();
+        // The first element of every pair of leaders, which the others are unioned with.
+        let mut firsts: IndexMap<(&Rc<T>, &Rc<T>), &T> = IndexMap::new();
+        let mut meet = Self::with_backend(B::default());
+        for t in ps.keys() {
+            let Some(other_leader) = UnionFind::<T, R>::root_in(&other_ps, t) else {
+                continue;
+            };
+            meet.insert(t.clone());
+            match firsts.entry((Self::root_in(&ps, t).unwrap(), other_leader)) {
+                Entry::Occupied(first) => {
+                    meet.union(*first.get(), t);
+                }
+                Entry::Vacant(entry) => {
+                    entry.insert(t);
+                }
+            }
+        }
+        meet
+    }
+}
+
+impl<T: Hash + Eq + Clone + Debug, B: Backend<Key = T>> PartialEq for UnionFind<T, B> {
+    /// Two union-finds are equal when they contain the same elements partitioned into the
+    /// same classes.
+    fn eq(&self, other: &Self) -> bool {
+        self.same_partition(other)
+    }
+}
+
+impl<T: Hash + Eq + Clone + Debug, B: Backend<Key = T>> Eq for UnionFind<T, B> {}
+
+impl<T: Hash + Eq + Clone + Debug, B: Backend<Key = T>> PartialOrd for UnionFind<T, B> {
+    /// The refinement order: `a <= b` when every element of `a` is in `b` and every class of
+    /// `a` is contained in a class of `b`.
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        if self == other {
+            Some(Ordering::Equal)
+        } else if self.is_refinement_of(other) {
+            Some(Ordering::Less)
+        } else if other.is_refinement_of(self) {
+            Some(Ordering::Greater)
+        } else {
+            None
+        }
+    }
+}
+
+impl<T: Hash + Eq + Clone + Debug, B: Backend<Key = T>> Hash for UnionFind<T, B> {
+    /// Consistent with the semantic equality, independent of insertion order and leaders.
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        let ps = self.parents.borrow();
+        let mut class_sums: IndexMap<&T, u64> = IndexMap::new();
+        for t in ps.keys() {
+            let mut hasher = DefaultHasher::new();
+            t.hash(&mut hasher);
+            let sum = class_sums.entry(Self::root_in(&ps, t).unwrap()).or_default();
+            *sum = sum.wrapping_add(hasher.finish());
+        }
+        let total = class_sums.values().fold(0u64, |total, sum| {
+            let mut hasher = DefaultHasher::new();
+            sum.hash(&mut hasher);
+            total.wrapping_add(hasher.finish())
+        });
+        state.write_usize(ps.len());
+        state.write_u64(total);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hash_of(uf: &UnionFind<u32>) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        uf.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    #[test]
+    fn semantic_equality() {
+        let mut a = UnionFind::new();
+        a.insert_many(0..5);
+        a.union(&0, &1);
+        a.union(&1, &2);
+
+        // Same partition, different insertion and union order so different leaders.
+        let mut b = UnionFind::new();
+        b.insert_many((0..5).rev());
+        b.union(&2, &1);
+        b.union(&0, &2);
+        assert_ne!(a.find(&0), b.find(&0));
+
+        assert_eq!(a, b);
+        assert_eq!(hash_of(&a), hash_of(&b));
+        assert_eq!(Some(Ordering::Equal), a.partial_cmp(&b));
+        let mut c = UnionFind::with_hasher(std::hash::BuildHasherDefault::<DefaultHasher>::default());
+        c.absorb(b.clone());
+        assert!(c.same_partition(&a) && a.is_refinement_of(&c));
+
+        b.union(&3, &4);
+        assert!(a < b);
+        assert!(b > a);
+        assert_ne!(a, b);
+
+        a.union(&0, &4);
+        assert_eq!(None, a.partial_cmp(&b));
+
+        let meet = a.meet(&b);
+        assert!(meet <= a && meet <= b);
+        assert_eq!(vec![vec![0, 1, 2], vec![3], vec![4]], meet.to_canonical_vec());
+        assert_eq!(meet, b.meet(&a));
+    }
+
+    #[test]
+    fn order_includes_elements() {
+        let mut a = UnionFind::new();
+        a.insert_many(0..3);
+        let mut b = a.clone();
+        b.insert(3);
+        assert!(a < b);
+        a.union(&0, &1);
+        assert_eq!(None, a.partial_cmp(&b));
+        b.union(&1, &0);
+        assert!(a < b);
+    }
+}
@@ -0,0 +1,122 @@
+//! Connected components of undirected graphs given as edge lists, the most common use of a
+//! union-find, without the glue around [UnionFind].
+//!
+//! # Examples
+//!
+//! ```
+//! use hash_unionfind::connectivity::{connected_components, DynamicConnectivity};
+//!
+//! let components = connected_components(["a", "b", "c", "d"], [("a", "c"), ("d", "c")]);
+//! assert_eq!(vec![vec!["a", "c", "d"], vec!["b"]], components);
+//!
+//! let mut graph = DynamicConnectivity::new();
+//! graph.add_edge(1, 2);
+//! graph.add_node(3);
+//! assert!(graph.connected(&2, &1));
+//! assert!(!graph.connected(&1, &3));
+//! assert_eq!(2, graph.num_components());
+//! ```
+
+use std::{borrow::Borrow, fmt::Debug, hash::Hash};
+
+use super::UnionFind;
+
+/// The connected components of the graph with the given nodes and edges, the endpoints of the
+/// edges being nodes too. Components are ordered by their first node, nodes listed before the
+/// edges, and nodes within a component keep that order.
+pub fn connected_components<T, N, E>(nodes: N, edges: E) -> Vec<Vec<T>>
+where
+    T: Hash + Eq + Clone + Debug,
+    N: IntoIterator<Item = T>,
+    E: IntoIterator<Item = (T, T)>,
+{
+    let mut graph = DynamicConnectivity::new();
+    graph.uf.insert_many(nodes);
+    for (x, y) in edges {
+        graph.add_edge(x, y);
+    }
+    graph.into_components()
+}
+
+/// An undirected graph growing one edge at a time, answering whether two nodes are connected.
+#[derive(Debug, Clone)]
+pub struct DynamicConnectivity<T: Hash + Eq + Clone + Debug> {
+    uf: UnionFind<T>,
+}
+
+impl<T: Hash + Eq + Clone + Debug> Default for DynamicConnectivity<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Hash + Eq + Clone + Debug> DynamicConnectivity<T> {
+    pub fn new() -> Self {
+        Self { uf: UnionFind::new() }
+    }
+
+    /// Adds an isolated node, doing nothing if it is already in the graph.
+    pub fn add_node(&mut self, x: T) {
+        self.uf.insert(x);
+    }
+
+    /// Adds an edge between x and y, adding them as nodes if missing. Returns whether it
+    /// joined two components, new nodes counting as components of their own.
+    pub fn add_edge(&mut self, x: T, y: T) -> bool {
+        let (x, y) = (self.uf.insert(x), self.uf.insert(y));
+        let components = self.uf.num_classes();
+        self.uf.union_ids(x, y);
+        self.uf.num_classes() < components
+    }
+
+    /// Whether there is a path between x and y. A node that is not in the graph is only
+    /// connected to itself.
+    pub fn connected<Q: Hash + Eq + ?Sized>(&self, x: &Q, y: &Q) -> bool
+    where
+        T: Borrow<Q>,
+    {
+        self.uf.same_class(x, y)
+    }
+
+    /// The number of nodes.
+    pub fn num_nodes(&self) -> usize {
+        self.uf.size()
+    }
+
+    pub fn num_components(&self) -> usize {
+        self.uf.num_classes()
+    }
+
+    /// Consumes the graph into its components, in the order of [connected_components].
+    pub fn into_components(self) -> Vec<Vec<T>> {
+        self.uf.into_groups().into_values().collect()
+    }
+
+    pub fn union_find(&self) -> &UnionFind<T> {
+        &self.uf
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn components_of_a_stream() {
+        let mut graph = DynamicConnectivity::new();
+        let joined: Vec<bool> = [(0, 1), (2, 3), (1, 0), (3, 0), (4, 4)]
+            .into_iter()
+            .map(|(x, y)| graph.add_edge(x, y))
+            .collect();
+        assert_eq!(vec![true, true, false, true, false], joined);
+        assert_eq!((5, 2), (graph.num_nodes(), graph.num_components()));
+        assert!(graph.connected(&2, &1));
+        assert!(!graph.connected(&4, &1));
+        assert!(graph.connected(&9, &9) && !graph.connected(&9, &4));
+        assert_eq!(vec![vec![0, 1, 2, 3], vec![4]], graph.into_components());
+
+        let edges = (0..10).map(|i| (i, (i + 3) % 10));
+        assert_eq!(vec![(0..10).collect::<Vec<_>>(), vec![10]], connected_components(0..11, edges));
+        assert!(connected_components(Vec::<u8>::new(), []).is_empty());
+    }
+}
@@ -0,0 +1,76 @@
+use std::{fmt::Debug, hash::Hash};
+
+use crate::{Backend, HashBackend};
+
+use super::UnionFind;
+
+/// Iterator returned by [UnionFind::component_counts].
+///
+/// Applies one edge per step and yields the number of classes after it.
+#[derive(Debug)]
+pub struct ComponentCounts<'a, T: Hash + Eq + Clone + Debug, I, B = HashBackend<T>> {
+    uf: &'a mut UnionFind<T, B>,
+    edges: I,
+}
+
+impl<'a, T, I, B> Iterator for ComponentCounts<'a, T, I, B>
+where
+    T: Hash + Eq + Clone + Debug,
+    I: Iterator<Item = (T, T)>,
+    B: Backend<Key = T>,
+{
+    type Item = usize;
+
+    fn next(&mut self) -> Option<usize> {
+        let (x, y) = self.edges.next()?;
+        self.uf.union_insert(x, y);
+        Some(self.uf.num_classes())
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.edges.size_hint()
+    }
+}
+
+impl<T: Hash + Eq + Clone + Debug, B: Backend<Key = T>> UnionFind<T, B> {
+    /// Consumes a stream of edges lazily, inserting unknown endpoints and unioning them,
+    /// and yields the number of classes after each edge.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use hash_unionfind::UnionFind;
+    ///
+    /// let mut uf = UnionFind::new();
+    /// let counts: Vec<usize> = uf.component_counts(vec![(1, 2), (3, 4), (2, 3), (1, 4)]).collect();
+    /// assert_eq!(counts, vec![1, 2, 1, 1]);
+    /// ```
+    pub fn component_counts<I>(&mut self, edges: I) -> ComponentCounts<'_, T, I::IntoIter, B>
+    where
+        I: IntoIterator<Item = (T, T)>,
+    {
+        ComponentCounts {
+            uf: self,
+            edges: edges.into_iter(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn counts_with_existing_elements() {
+        let mut uf = UnionFind::new();
+        for i in 0..5 {
+            uf.insert(i);
+        }
+        assert_eq!(5, uf.num_classes());
+
+        let counts: Vec<usize> = uf.component_counts(vec![(0, 1), (1, 0), (2, 3), (5, 6)]).collect();
+        assert_eq!(counts, vec![4, 4, 3, 4]);
+        assert_eq!(4, uf.num_classes());
+        assert_eq!(7, uf.size());
+    }
+}
@@ -0,0 +1,135 @@
+use std::{borrow::Borrow, fmt::Debug, hash::Hash, rc::Rc};
+use indexmap::IndexMap;
+
+use super::{QuotientMap, UnionFind};
+
+/// A [UnionFind] where every class carries a datum, merged into the kept class's datum by
+/// `merge(&mut kept, absorbed)` whenever two classes join, like the analyses of an e-graph.
+///
+/// It is a [QuotientMap] where every class has a value, since every element is inserted
+/// with its datum, and unions merge in place through [QuotientMap::union_with].
+///
+/// # Examples
+///
+/// ```
+/// use hash_unionfind::DataUnionFind;
+///
+/// // The interval of values a class of variables is known to lie in.
+/// let mut bounds = DataUnionFind::new(|kept: &mut (i32, i32), absorbed: (i32, i32)| {
+///     *kept = (kept.0.max(absorbed.0), kept.1.min(absorbed.1));
+/// });
+/// bounds.insert("x", (0, 10));
+/// bounds.insert("y", (5, 20));
+/// bounds.insert("z", (-5, 7));
+/// bounds.union(&"x", &"y");
+/// bounds.union(&"z", &"y");
+/// assert_eq!(Some(&(5, 7)), bounds.data(&"x"));
+/// ```
+#[derive(Debug, Clone)]
+pub struct DataUnionFind<T: Hash + Eq + Clone + Debug, D, M> {
+    // The combine of the map is unused, unions go through union_with and merge.
+    classes: QuotientMap<T, D, ()>,
+    merge: M,
+}
+
+impl<T, D, M> DataUnionFind<T, D, M>
+where
+    T: Hash + Eq + Clone + Debug,
+    M: FnMut(&mut D, D),
+{
+    /// An empty union-find, merging the data of unioned classes with merge(kept, absorbed).
+    pub fn new(merge: M) -> Self {
+        Self { classes: QuotientMap::from_union_find(UnionFind::new(), ()), merge }
+    }
+
+    pub fn size(&self) -> usize {
+        self.classes.union_find().size()
+    }
+
+    pub fn num_classes(&self) -> usize {
+        self.classes.union_find().num_classes()
+    }
+
+    /// Inserts t as a singleton class with datum, returning whether t was new. Datum is
+    /// dropped if t exists, use [DataUnionFind::data_mut] to change the datum of its class.
+    pub fn insert(&mut self, t: T, datum: D) -> bool {
+        if self.classes.union_find().id(&t).is_some() {
+            return false;
+        }
+        self.classes.insert(t, datum);
+        true
+    }
+
+    /// The leader of the class of x, or None if x is missing.
+    pub fn find<Q: Hash + Eq + ?Sized>(&self, x: &Q) -> Option<Rc<T>>
+    where
+        T: Borrow<Q>,
+    {
+        self.classes.union_find().find(x)
+    }
+
+    /// The datum of the class of x, or None if x is missing.
+    pub fn data<Q: Hash + Eq + ?Sized>(&self, x: &Q) -> Option<&D>
+    where
+        T: Borrow<Q>,
+    {
+        self.classes.get(x)
+    }
+
+    pub fn data_mut<Q: Hash + Eq + ?Sized>(&mut self, x: &Q) -> Option<&mut D>
+    where
+        T: Borrow<Q>,
+    {
+        self.classes.get_mut(x)
+    }
+
+    /// Unions the classes of x and y, merging the datum of the absorbed class into the kept
+    /// one. Returns the new leader, or None if one of them is missing.
+    pub fn union<Q: Hash + Eq + ?Sized>(&mut self, x: &Q, y: &Q) -> Option<Rc<T>>
+    where
+        T: Borrow<Q>,
+    {
+        self.classes.union_with(x, y, &mut self.merge)
+    }
+
+    /// Every class by leader with its datum.
+    pub fn iter(&self) -> impl Iterator<Item = (&Rc<T>, &D)> {
+        self.classes.iter()
+    }
+
+    pub fn union_find(&self) -> &UnionFind<T> {
+        self.classes.union_find()
+    }
+
+    /// The partition and the datum of every class by leader.
+    pub fn into_parts(self) -> (UnionFind<T>, IndexMap<Rc<T>, D>) {
+        self.classes.into_parts()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn data_follow_leaders() {
+        // The smallest member of every class, which is a canonical representative no matter
+        // which member leads.
+        let mut uf = DataUnionFind::new(|kept: &mut u32, absorbed| *kept = (*kept).min(absorbed));
+        for i in 0..16 {
+            assert!(uf.insert(i, i));
+        }
+        assert!(!uf.insert(3, 100));
+        for i in 0..12 {
+            uf.union(&(i * 5 % 16), &(i * 3 % 16 + 1));
+        }
+        let mut partition = uf.union_find().clone();
+        for i in 0..16 {
+            assert_eq!(partition.members(&i).min(), uf.data(&i));
+        }
+        assert_eq!(None, uf.data(&16));
+        assert_eq!(uf.num_classes(), uf.iter().count());
+        let (partition, data) = uf.into_parts();
+        assert!(data.keys().all(|leader| partition.find(leader.as_ref()).as_ref() == Some(leader)));
+    }
+}
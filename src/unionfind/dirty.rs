@@ -0,0 +1,99 @@
+use std::{fmt::Debug, hash::Hash, rc::Rc};
+use indexmap::IndexSet;
+
+use crate::Backend;
+
+use super::UnionFind;
+
+impl<T: Hash + Eq + Clone + Debug, B: Backend<Key = T>> UnionFind<T, B> {
+    /// Starts tracking the classes that are inserted, merged or split, for
+    /// [UnionFind::take_dirty]. Does nothing if already tracking.
+    ///
+    /// Tracking costs a push per insert and effective union until the next take, so a leader
+    /// that keeps absorbing classes is pushed once per union. Take between passes to bound it.
+    pub fn track_dirty(&mut self) {
+        if self.dirty.is_none() {
+            self.dirty = Some(Vec::new());
+        }
+    }
+
+    /// The current leaders of the classes that were inserted, merged or split since the last
+    /// call, each once, in the order they first changed, or None if not tracking. Rebuild
+    /// loops can reprocess only these classes instead of all of them.
+    ///
+    /// Classes restored by [UnionFind::rollback] count as changed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use hash_unionfind::UnionFind;
+    ///
+    /// let mut uf = UnionFind::new();
+    /// uf.insert_many(0..4);
+    /// assert_eq!(None, uf.take_dirty());
+    /// uf.track_dirty();
+    /// uf.union(&1, &2);
+    /// uf.union(&1, &1);
+    /// uf.union(&3, &1);
+    /// uf.insert(4);
+    /// assert_eq!(vec![1, 4], uf.take_dirty().unwrap().iter().map(|l| **l).collect::<Vec<_>>());
+    /// assert_eq!(Some(vec![]), uf.take_dirty());
+    /// ```
+    pub fn take_dirty(&mut self) -> Option<Vec<Rc<T>>> {
+        let dirty = std::mem::take(self.dirty.as_mut()?);
+        let ps = self.parents.get_mut();
+        let leaders: IndexSet<usize> = dirty.into_iter().map(|d| Self::find_index_in(ps, crate::wide(d))).collect();
+        Some(leaders.into_iter().map(|leader| Self::handle_in(ps, leader).clone()).collect())
+    }
+
+    /// Stops tracking and drops the pending classes.
+    pub fn stop_dirty(&mut self) {
+        self.dirty = None;
+    }
+
+    pub(super) fn mark_dirty(&mut self, leader: usize) {
+        if let Some(dirty) = &mut self.dirty {
+            dirty.push(crate::small(leader));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn values(leaders: Option<Vec<Rc<u32>>>) -> Vec<u32> {
+        leaders.unwrap().into_iter().map(|l| *l).collect()
+    }
+
+    #[test]
+    fn dirty_classes_follow_changes() {
+        let mut uf = UnionFind::new();
+        uf.track_dirty();
+        uf.insert_many(0..8);
+        assert_eq!((0..8).collect::<Vec<_>>(), values(uf.take_dirty()));
+
+        uf.union(&4, &5);
+        uf.union(&6, &4);
+        assert!(!uf.make_singleton(&7) && uf.make_singleton(&5));
+        assert_eq!(vec![4, 5], values(uf.take_dirty()));
+
+        // Reordering and removing move the pending classes along.
+        uf.union(&2, &3);
+        uf.reorder_by_class().unwrap();
+        uf.remove(&0);
+        assert_eq!(vec![2], values(uf.take_dirty()));
+
+        uf.union(&1, &2);
+        uf.take_dirty();
+        let checkpoint = uf.snapshot();
+        uf.insert(9);
+        uf.union(&9, &1);
+        uf.rollback(checkpoint);
+        assert_eq!(vec![2], values(uf.take_dirty()));
+
+        uf.stop_dirty();
+        uf.union(&4, &1);
+        assert_eq!(None, uf.take_dirty());
+    }
+}
@@ -0,0 +1,71 @@
+use std::{fmt::Debug, hash::Hash, io};
+
+use crate::Backend;
+
+use super::UnionFind;
+
+impl<T: Hash + Eq + Clone + Debug, B: Backend<Key = T>> UnionFind<T, B> {
+    /// Writes the parent forest in Graphviz DOT: a node per element labeled with its key
+    /// and rank, an edge from every element to its parent, leaders drawn bold as double
+    /// circles. The forest is read as it is, without compressing, so the trees show the
+    /// paths a find would walk.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use hash_unionfind::UnionFind;
+    ///
+    /// let uf = UnionFind::from_edge_list([("a", "b")]);
+    /// let mut dot = Vec::new();
+    /// uf.render_forest(&mut dot).unwrap();
+    /// assert!(String::from_utf8(dot).unwrap().contains("n1 -> n0;"));
+    /// ```
+    pub fn render_forest<W: io::Write + ?Sized>(&self, out: &mut W) -> io::Result<()> {
+        let ps = self.parents.borrow();
+        writeln!(out, "digraph forest {{")?;
+        for (i, (key, node)) in ps.iter().enumerate() {
+            let label = format!("{:?}", key).replace('\\', "\\\\").replace('"', "\\\"");
+            if crate::wide(node.parent) == i {
+                let style = "shape=doublecircle, style=bold";
+                writeln!(out, "    n{} [label=\"{}\\nrank {}\", {}];", i, label, node.rank, style)?;
+            } else {
+                writeln!(out, "    n{} [label=\"{}\\nrank {}\"];", i, label, node.rank)?;
+            }
+        }
+        for (i, node) in ps.values().enumerate() {
+            if crate::wide(node.parent) != i {
+                writeln!(out, "    n{} -> n{};", i, node.parent)?;
+            }
+        }
+        writeln!(out, "}}")
+    }
+
+    /// The parent forest in Graphviz DOT, see [UnionFind::render_forest].
+    pub fn to_dot(&self) -> String {
+        let mut out = Vec::new();
+        self.render_forest(&mut out).unwrap();
+        String::from_utf8(out).unwrap()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn dot_shows_the_forest() {
+        let mut uf = UnionFind::new();
+        uf.insert_many(["x", "y", "z", "say \"hi\""]);
+        uf.union(&"x", &"y");
+        uf.union(&"z", &"x");
+        let expected = "digraph forest {\n    \
+            n0 [label=\"\\\"x\\\"\\nrank 1\", shape=doublecircle, style=bold];\n    \
+            n1 [label=\"\\\"y\\\"\\nrank 0\"];\n    \
+            n2 [label=\"\\\"z\\\"\\nrank 0\"];\n    \
+            n3 [label=\"\\\"say \\\\\\\"hi\\\\\\\"\\\"\\nrank 0\", shape=doublecircle, style=bold];\n    \
+            n1 -> n0;\n    \
+            n2 -> n0;\n\
+            }\n";
+        assert_eq!(expected, uf.to_dot());
+    }
+}
@@ -0,0 +1,109 @@
+use std::{fmt::Debug, hash::Hash, rc::Rc};
+
+use crate::Backend;
+
+use super::UnionFind;
+
+/// An effective union, between two classes named by their leaders just before it.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct MergeEvent<T> {
+    /// The leader that kept leading, the leader of the merged class.
+    pub kept: Rc<T>,
+    /// The leader that was attached under it.
+    pub absorbed: Rc<T>,
+}
+
+impl<T: Hash + Eq + Clone + Debug, B: Backend<Key = T>> UnionFind<T, B> {
+    /// Starts logging a [MergeEvent] for every effective union, whichever method made it.
+    /// Does nothing if already logging.
+    ///
+    /// Unlike [UnionFind::take_leader_remap], which composes chains of merges, the log keeps
+    /// each merge in order. Unlike [UnionFind::record_history] it can be drained between
+    /// passes, so it only grows with the merges of a pass.
+    pub fn track_merges(&mut self) {
+        if self.merges.is_none() {
+            self.merges = Some(Vec::new());
+        }
+    }
+
+    /// Returns the merges since the last call, in order, or None if not logging.
+    ///
+    /// A [UnionFind::rollback] drops the events of the merges it undoes, as long as they were
+    /// not taken yet; events taken before are not retracted.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use hash_unionfind::{MergeEvent, UnionFind};
+    ///
+    /// let mut uf = UnionFind::new();
+    /// uf.insert_many(0..4);
+    /// uf.track_merges();
+    /// uf.union(&0, &1);
+    /// uf.union(&1, &0);
+    /// uf.union(&2, &1);
+    /// let merges = uf.take_merges().unwrap();
+    /// assert_eq!(vec![(0, 1), (0, 2)], merges.iter().map(|m| (*m.kept, *m.absorbed)).collect::<Vec<_>>());
+    /// assert_eq!(Some(vec![]), uf.take_merges());
+    /// ```
+    pub fn take_merges(&mut self) -> Option<Vec<MergeEvent<T>>> {
+        self.merges.as_mut().map(std::mem::take)
+    }
+
+    /// Stops logging and drops pending events.
+    pub fn stop_merges(&mut self) {
+        self.merges = None;
+    }
+
+    pub(super) fn log_merge(&mut self, kept: usize, absorbed: usize) {
+        if let Some(merges) = &mut self.merges {
+            let ps = self.parents.get_mut();
+            merges.push(MergeEvent {
+                kept: Self::handle_in(ps, kept).clone(),
+                absorbed: Self::copy_in(ps, absorbed),
+            });
+        }
+    }
+
+    // Drops the event of the link of absorbed under kept being undone, if still pending.
+    pub(super) fn unlog_merge(&mut self, kept: usize, absorbed: usize) {
+        if let Some(merges) = &mut self.merges {
+            let ps = self.parents.get_mut();
+            let (kept, absorbed) = (ps.get_index(kept).unwrap().0, ps.get_index(absorbed).unwrap().0);
+            if merges.last().is_some_and(|m| *m.kept == *kept && *m.absorbed == *absorbed) {
+                merges.pop();
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn merges_are_logged_per_pass() {
+        let mut uf = UnionFind::new();
+        uf.insert_many(0..6);
+        uf.union(&4, &5);
+        assert_eq!(None, uf.take_merges());
+        uf.track_merges();
+
+        uf.union(&0, &1);
+        uf.union_with(&2, &0, crate::KeepFirst);
+        let checkpoint = uf.snapshot();
+        uf.union(&3, &4);
+        uf.rollback(checkpoint);
+        uf.union_insert(6, 5);
+        let merges = uf.take_merges().unwrap();
+        let pairs: Vec<_> = merges.iter().map(|m| (*m.kept, *m.absorbed)).collect();
+        assert_eq!(vec![(0, 1), (2, 0), (4, 6)], pairs);
+        for merge in &merges {
+            assert_eq!(uf.find(&merge.kept), uf.find(&merge.absorbed));
+        }
+
+        uf.stop_merges();
+        uf.union(&3, &4);
+        assert_eq!(None, uf.take_merges());
+    }
+}
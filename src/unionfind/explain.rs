@@ -0,0 +1,199 @@
+use std::{borrow::Borrow, fmt::Debug, hash::Hash, rc::Rc};
+use indexmap::{IndexMap, IndexSet};
+
+use super::UnionFind;
+
+/// One step of an explanation: a union of `from` and `to`, given in the order the chain
+/// walks it, with the reason it was given.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Justification<'a, T, J> {
+    pub from: &'a T,
+    pub to: &'a T,
+    pub reason: Option<&'a J>,
+}
+
+/// A [UnionFind] remembering why elements are equivalent, like the explanations of egg.
+///
+/// Every effective union adds an edge between the two elements it was called with to a
+/// proof forest kept beside the rank forest, and [ProofUnionFind::explain] returns the
+/// unions on the path between two elements. Redundant unions of elements already equivalent
+/// are not recorded, so explanations only go through unions that were needed.
+///
+/// A union reroots the proof tree of the smaller class, which takes O(n log n) time overall.
+///
+/// # Examples
+///
+/// ```
+/// use hash_unionfind::ProofUnionFind;
+///
+/// let mut uf = ProofUnionFind::new();
+/// for t in ["a", "b", "c", "d"] {
+///     uf.insert(t);
+/// }
+/// uf.union(&"a", &"b", Some("a = b"));
+/// uf.union(&"c", &"d", Some("c = d"));
+/// uf.union(&"b", &"c", None);
+/// uf.union(&"a", &"d", Some("redundant"));
+///
+/// let steps = uf.explain(&"a", &"d").unwrap();
+/// let reasons: Vec<_> = steps.iter().map(|step| step.reason.copied()).collect();
+/// assert_eq!(vec![Some("a = b"), None, Some("c = d")], reasons);
+/// assert_eq!((&"a", &"d"), (steps[0].from, steps[2].to));
+/// assert_eq!(None, uf.explain(&"a", &"e"));
+/// ```
+#[derive(Debug, Clone)]
+pub struct ProofUnionFind<T: Hash + Eq + Clone + Debug, J> {
+    uf: UnionFind<T>,
+    // The edge from every element to its parent in the proof forest, by the index of the
+    // parent, in the insertion order of uf.
+    proofs: IndexMap<T, Option<(usize, Option<J>)>>,
+}
+
+impl<T: Hash + Eq + Clone + Debug, J> Default for ProofUnionFind<T, J> {
+    fn default() -> Self {
+        Self { uf: UnionFind::new(), proofs: IndexMap::new() }
+    }
+}
+
+impl<T: Hash + Eq + Clone + Debug, J> ProofUnionFind<T, J> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn size(&self) -> usize {
+        self.uf.size()
+    }
+
+    pub fn num_classes(&self) -> usize {
+        self.uf.num_classes()
+    }
+
+    /// The underlying union-find, for everything not forwarded here.
+    pub fn union_find(&self) -> &UnionFind<T> {
+        &self.uf
+    }
+
+    /// Inserts t as a singleton, returning whether it was new.
+    pub fn insert(&mut self, t: T) -> bool {
+        if self.proofs.contains_key(&t) {
+            return false;
+        }
+        self.uf.insert(t.clone());
+        self.proofs.insert(t, None);
+        true
+    }
+
+    /// The leader of the class of x, or None if x is missing.
+    pub fn find<Q: Hash + Eq + ?Sized>(&self, x: &Q) -> Option<Rc<T>>
+    where
+        T: Borrow<Q>,
+    {
+        self.uf.find(x)
+    }
+
+    /// Unions the classes of x and y for `reason`, returning the new leader, or None if one
+    /// of them is missing. The reason is only kept if the union is effective.
+    pub fn union<Q: Hash + Eq + ?Sized>(&mut self, x: &Q, y: &Q, reason: Option<J>) -> Option<Rc<T>>
+    where
+        T: Borrow<Q>,
+    {
+        let (mut x, mut y) = (self.proofs.get_index_of(x)?, self.proofs.get_index_of(y)?);
+        let leader = self.uf.find::<T>(self.key(x))?;
+        if Some(&leader) == self.uf.find::<T>(self.key(y)).as_ref() {
+            return Some(leader);
+        }
+        if self.uf.class_size::<T>(self.key(x)) > self.uf.class_size::<T>(self.key(y)) {
+            std::mem::swap(&mut x, &mut y);
+        }
+        self.reroot(x);
+        self.proofs[x] = Some((y, reason));
+        let (x, y) = (self.key(x).clone(), self.key(y).clone());
+        self.uf.union::<T>(&x, &y)
+    }
+
+    fn key(&self, i: usize) -> &T {
+        self.proofs.get_index(i).unwrap().0
+    }
+
+    // Reverses the edges on the path from i to its proof root, making i the root.
+    fn reroot(&mut self, i: usize) {
+        let mut current = i;
+        let mut carried = self.proofs[i].take();
+        while let Some((parent, reason)) = carried {
+            carried = self.proofs[parent].replace((current, reason));
+            current = parent;
+        }
+    }
+
+    /// The unions witnessing that x and y are equivalent, as a chain from x to y. Empty if x
+    /// and y are the same element, None if one of them is missing or they are not equivalent.
+    pub fn explain<Q: Hash + Eq + ?Sized>(&self, x: &Q, y: &Q) -> Option<Vec<Justification<'_, T, J>>>
+    where
+        T: Borrow<Q>,
+    {
+        let (x, y) = (self.proofs.get_index_of(x)?, self.proofs.get_index_of(y)?);
+        // The proof ancestors of x, starting at x itself.
+        let mut ancestors = IndexSet::from([x]);
+        while let Some((parent, _)) = self.proofs[*ancestors.last().unwrap()] {
+            ancestors.insert(parent);
+        }
+        let mut down = Vec::new();
+        let mut current = y;
+        let meet = loop {
+            if let Some(meet) = ancestors.get_index_of(&current) {
+                break meet;
+            }
+            let (parent, reason) = self.proofs[current].as_ref()?;
+            down.push(self.step(*parent, current, reason));
+            current = *parent;
+        };
+
+        let mut steps: Vec<_> = ancestors.iter().take(meet).map(|&child| {
+            let (parent, reason) = self.proofs[child].as_ref().unwrap();
+            self.step(child, *parent, reason)
+        }).collect();
+        steps.extend(down.into_iter().rev());
+        Some(steps)
+    }
+
+    fn step<'a>(&'a self, from: usize, to: usize, reason: &'a Option<J>) -> Justification<'a, T, J> {
+        Justification { from: self.key(from), to: self.key(to), reason: reason.as_ref() }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn explanations_chain_the_needed_unions() {
+        let mut uf = ProofUnionFind::new();
+        for i in 0..32 {
+            uf.insert(i);
+        }
+        for i in 0..40 {
+            let (x, y) = (i * 7 % 32, i * 11 % 32);
+            uf.union(&x, &y, Some(i));
+        }
+        for x in 0..32 {
+            for y in 0..32 {
+                let Some(steps) = uf.explain(&x, &y) else {
+                    assert_ne!(uf.find(&x), uf.find(&y));
+                    continue;
+                };
+                // The steps connect x to y, each through a union made with its reason.
+                let mut at = &x;
+                for step in &steps {
+                    assert_eq!(at, step.from);
+                    let i = *step.reason.unwrap();
+                    let union = (i * 7 % 32, i * 11 % 32);
+                    assert!(union == (*step.from, *step.to) || union == (*step.to, *step.from));
+                    at = step.to;
+                }
+                assert_eq!(&y, at);
+                assert_eq!(x == y, steps.is_empty());
+            }
+        }
+        assert_eq!(uf.union_find().num_classes(), uf.num_classes());
+    }
+}
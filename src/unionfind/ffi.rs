@@ -0,0 +1,215 @@
+//! A C ABI over [UnionFind], for hosts that are not written in Rust.
+//!
+//! Union-finds are opaque handles created by `uf_new` over `uint64_t` keys, or `uf_str_new`
+//! over NUL terminated strings, and freed by the matching `uf_free` or `uf_str_free`. The
+//! functions are `extern "C"` and unmangled so cbindgen can generate the header, and the
+//! crate can be linked as a `staticlib` or `cdylib`. A handle is not thread safe, a host
+//! sharing one between threads must lock around every call.
+//!
+//! # Examples
+//!
+//! ```
+//! use hash_unionfind::ffi::*;
+//!
+//! unsafe {
+//!     let uf = uf_new();
+//!     for key in 0..4 {
+//!         uf_insert(uf, key);
+//!     }
+//!     assert!(uf_union(uf, 1, 3));
+//!     let mut leader = 0;
+//!     assert!(uf_find(uf, 3, &mut leader));
+//!     assert_eq!((1, 3), (leader, uf_num_classes(uf)));
+//!     uf_free(uf);
+//! }
+//! ```
+
+use std::{
+    ffi::{c_char, CStr, CString},
+    ptr,
+};
+
+use super::UnionFind;
+
+/// An opaque union-find over `uint64_t` keys.
+#[derive(Debug, Default)]
+pub struct UfU64(UnionFind<u64>);
+
+/// An opaque union-find over string keys, which it copies.
+#[derive(Debug, Default)]
+pub struct UfStr(UnionFind<CString>);
+
+/// A new empty union-find over `uint64_t` keys, to be freed with [uf_free].
+#[no_mangle]
+pub extern "C" fn uf_new() -> *mut UfU64 {
+    Box::into_raw(Box::default())
+}
+
+/// Frees a union-find from [uf_new], doing nothing on NULL.
+///
+/// # Safety
+///
+/// uf must be NULL or a pointer returned by [uf_new] that was not freed yet.
+#[no_mangle]
+pub unsafe extern "C" fn uf_free(uf: *mut UfU64) {
+    if !uf.is_null() {
+        drop(Box::from_raw(uf));
+    }
+}
+
+/// Inserts key as a singleton, returning whether it was new.
+///
+/// # Safety
+///
+/// uf must be a live pointer returned by [uf_new].
+#[no_mangle]
+pub unsafe extern "C" fn uf_insert(uf: *mut UfU64, key: u64) -> bool {
+    let uf = &mut (*uf).0;
+    let size = uf.size();
+    uf.insert(key);
+    uf.size() > size
+}
+
+/// Unions the classes of x and y, returning false if one of them is missing.
+///
+/// # Safety
+///
+/// uf must be a live pointer returned by [uf_new].
+#[no_mangle]
+pub unsafe extern "C" fn uf_union(uf: *mut UfU64, x: u64, y: u64) -> bool {
+    (*uf).0.union(&x, &y).is_some()
+}
+
+/// Writes the leader of the class of key to leader, returning false and leaving it as is
+/// if key is missing.
+///
+/// # Safety
+///
+/// uf must be a live pointer returned by [uf_new], and leader must be valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn uf_find(uf: *const UfU64, key: u64, leader: *mut u64) -> bool {
+    match (*uf).0.find(&key) {
+        Some(found) => {
+            *leader = *found;
+            true
+        }
+        None => false,
+    }
+}
+
+/// The number of elements.
+///
+/// # Safety
+///
+/// uf must be a live pointer returned by [uf_new].
+#[no_mangle]
+pub unsafe extern "C" fn uf_size(uf: *const UfU64) -> usize {
+    (*uf).0.size()
+}
+
+/// The number of classes.
+///
+/// # Safety
+///
+/// uf must be a live pointer returned by [uf_new].
+#[no_mangle]
+pub unsafe extern "C" fn uf_num_classes(uf: *const UfU64) -> usize {
+    (*uf).0.num_classes()
+}
+
+/// A new empty union-find over string keys, to be freed with [uf_str_free].
+#[no_mangle]
+pub extern "C" fn uf_str_new() -> *mut UfStr {
+    Box::into_raw(Box::default())
+}
+
+/// Frees a union-find from [uf_str_new], doing nothing on NULL. The strings returned by
+/// [uf_str_find] are freed with it.
+///
+/// # Safety
+///
+/// uf must be NULL or a pointer returned by [uf_str_new] that was not freed yet.
+#[no_mangle]
+pub unsafe extern "C" fn uf_str_free(uf: *mut UfStr) {
+    if !uf.is_null() {
+        drop(Box::from_raw(uf));
+    }
+}
+
+/// Inserts a copy of key as a singleton, returning whether it was new.
+///
+/// # Safety
+///
+/// uf must be a live pointer returned by [uf_str_new], and key a NUL terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn uf_str_insert(uf: *mut UfStr, key: *const c_char) -> bool {
+    let uf = &mut (*uf).0;
+    let size = uf.size();
+    uf.insert(CStr::from_ptr(key).to_owned());
+    uf.size() > size
+}
+
+/// Unions the classes of x and y, returning false if one of them is missing.
+///
+/// # Safety
+///
+/// uf must be a live pointer returned by [uf_str_new], x and y NUL terminated strings.
+#[no_mangle]
+pub unsafe extern "C" fn uf_str_union(uf: *mut UfStr, x: *const c_char, y: *const c_char) -> bool {
+    (*uf).0.union(CStr::from_ptr(x), CStr::from_ptr(y)).is_some()
+}
+
+/// The leader of the class of key, or NULL if key is missing. The string belongs to the
+/// union-find and stays valid until [uf_str_free], even once it no longer leads.
+///
+/// # Safety
+///
+/// uf must be a live pointer returned by [uf_str_new], and key a NUL terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn uf_str_find(uf: *const UfStr, key: *const c_char) -> *const c_char {
+    let uf = &(*uf).0;
+    let Some(leader) = uf.id(CStr::from_ptr(key)).and_then(|id| uf.find_id(id)) else {
+        return ptr::null();
+    };
+    // The stored key, whose buffer does not move with the map, unlike the leader clones.
+    uf.parents.borrow().get_index(leader.index()).unwrap().0.as_ptr()
+}
+
+/// The number of classes.
+///
+/// # Safety
+///
+/// uf must be a live pointer returned by [uf_str_new].
+#[no_mangle]
+pub unsafe extern "C" fn uf_str_num_classes(uf: *const UfStr) -> usize {
+    (*uf).0.num_classes()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn string_keys() {
+        let keys: Vec<CString> = ["a", "b", "c"].into_iter().map(|k| CString::new(k).unwrap()).collect();
+        unsafe {
+            let uf = uf_str_new();
+            for key in &keys {
+                assert!(uf_str_insert(uf, key.as_ptr()));
+            }
+            assert!(!uf_str_insert(uf, keys[0].as_ptr()));
+            assert!(uf_str_union(uf, keys[1].as_ptr(), keys[2].as_ptr()));
+            let leader = uf_str_find(uf, keys[2].as_ptr());
+            // Later inserts grow the map without moving the returned string.
+            for i in 0..100 {
+                uf_str_insert(uf, CString::new(format!("k{}", i)).unwrap().as_ptr());
+            }
+            assert_eq!(c"b", CStr::from_ptr(leader));
+            assert!(uf_str_find(uf, c"d".as_ptr()).is_null());
+            assert!(!uf_str_union(uf, keys[0].as_ptr(), c"d".as_ptr()));
+            assert_eq!(102, uf_str_num_classes(uf));
+            uf_str_free(uf);
+            uf_str_free(ptr::null_mut());
+        }
+    }
+}
@@ -0,0 +1,250 @@
+use std::{
+    collections::hash_map::DefaultHasher,
+    fmt::{self, Debug, Display},
+    hash::{BuildHasher, BuildHasherDefault, Hash, Hasher},
+    rc::Rc,
+};
+use indexmap::{map::Entry, IndexMap};
+
+use super::UnionFind;
+
+/// A fixed size hash standing in for a key in a [FingerprintUnionFind].
+pub trait Fingerprint: Hash + Eq + Copy + Debug {
+    fn of<K: Hash + ?Sized, S: BuildHasher>(key: &K, build: &S) -> Self;
+}
+
+impl Fingerprint for u64 {
+    fn of<K: Hash + ?Sized, S: BuildHasher>(key: &K, build: &S) -> Self {
+        salted(key, build, 0)
+    }
+}
+
+impl Fingerprint for u128 {
+    fn of<K: Hash + ?Sized, S: BuildHasher>(key: &K, build: &S) -> Self {
+        (salted(key, build, 0) as u128) << 64 | salted(key, build, 1) as u128
+    }
+}
+
+// Hashes key after a salt byte, giving independent hashes out of a single hasher.
+fn salted<K: Hash + ?Sized, S: BuildHasher>(key: &K, build: &S, salt: u8) -> u64 {
+    let mut hasher = build.build_hasher();
+    hasher.write_u8(salt);
+    key.hash(&mut hasher);
+    hasher.finish()
+}
+
+// The salt of the check hashes kept when detecting collisions.
+const CHECK: u8 = 2;
+
+/// An inserted key whose fingerprint is already taken by a different key, as told by the
+/// check hashes of [FingerprintUnionFind::detect_collisions].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FingerprintCollision<F> {
+    pub fingerprint: F,
+}
+
+impl<F: Debug> Display for FingerprintCollision<F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "fingerprint {:?} is shared by two different keys", self.fingerprint)
+    }
+}
+
+impl<F: Debug> std::error::Error for FingerprintCollision<F> {}
+
+/// A [UnionFind] over the fingerprints of keys instead of the keys, for key sets too large
+/// to keep, as long as the keys can be recovered elsewhere.
+///
+/// Keys are hashed into a [Fingerprint], 64 bits by default or 128 bits with `u128`, and
+/// only the fingerprint is stored. Two keys with the same fingerprint are silently the same
+/// element. With 64 bits that is likely once there are billions of keys, by the birthday
+/// bound about n² / 2⁶⁵ collisions for n keys, so that many keys call for 128 bits.
+///
+/// [FingerprintUnionFind::detect_collisions] additionally keeps a second, independent 64 bit
+/// hash per fingerprint, which catches all but about one in 2⁶⁴ of the collisions at the
+/// cost of the extra memory.
+///
+/// The default hasher is keyed with zeros, so fingerprints are the same across runs. Its
+/// algorithm may still change between Rust releases, use [FingerprintUnionFind::with_hasher]
+/// with a fixed algorithm to keep fingerprints stored elsewhere valid.
+///
+/// # Examples
+///
+/// ```
+/// use hash_unionfind::FingerprintUnionFind;
+///
+/// let mut uf = FingerprintUnionFind::<u128>::new().detect_collisions();
+/// for url in ["https://a.example/", "https://a.example/index.html", "https://b.example/"] {
+///     uf.insert(url).unwrap();
+/// }
+/// uf.union("https://a.example/", "https://a.example/index.html");
+/// assert!(uf.equiv("https://a.example/index.html", "https://a.example/"));
+/// assert!(!uf.equiv("https://a.example/", "https://b.example/"));
+/// assert_eq!(None, uf.find("https://c.example/"));
+/// ```
+#[derive(Debug, Clone)]
+pub struct FingerprintUnionFind<F: Fingerprint = u64, S = BuildHasherDefault<DefaultHasher>> {
+    uf: UnionFind<F>,
+    build: S,
+    // The check hash of the key behind every fingerprint, only kept once detect_collisions
+    // was called.
+    checks: Option<IndexMap<F, u64>>,
+}
+
+impl<F: Fingerprint, S: Default> Default for FingerprintUnionFind<F, S> {
+    fn default() -> Self {
+        Self::with_hasher(S::default())
+    }
+}
+
+impl<F: Fingerprint> FingerprintUnionFind<F> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl<F: Fingerprint, S> FingerprintUnionFind<F, S> {
+    /// An empty union-find fingerprinting keys with `build`.
+    pub fn with_hasher(build: S) -> Self {
+        Self { uf: UnionFind::new(), build, checks: None }
+    }
+
+    pub fn size(&self) -> usize {
+        self.uf.size()
+    }
+
+    pub fn num_classes(&self) -> usize {
+        self.uf.num_classes()
+    }
+
+    /// The union-find over fingerprints, for everything not forwarded here.
+    pub fn union_find(&self) -> &UnionFind<F> {
+        &self.uf
+    }
+}
+
+impl<F: Fingerprint, S: BuildHasher> FingerprintUnionFind<F, S> {
+    /// Starts keeping a check hash for every fingerprint, so [FingerprintUnionFind::insert]
+    /// can report collisions and lookups of colliding keys fail. Fingerprints inserted
+    /// before cannot be checked and are trusted.
+    pub fn detect_collisions(mut self) -> Self {
+        self.checks.get_or_insert_with(IndexMap::new);
+        self
+    }
+
+    pub fn fingerprint<K: Hash + ?Sized>(&self, key: &K) -> F {
+        F::of(key, &self.build)
+    }
+
+    // The fingerprint of key, None if collisions are detected and the fingerprint is known to
+    // belong to a different key.
+    fn checked<K: Hash + ?Sized>(&self, key: &K) -> Option<F> {
+        let fingerprint = self.fingerprint(key);
+        match self.checks.as_ref().and_then(|checks| checks.get(&fingerprint)) {
+            Some(check) if *check != salted(key, &self.build, CHECK) => None,
+            _ => Some(fingerprint),
+        }
+    }
+
+    /// Inserts key as a singleton, returning whether its fingerprint was new. Fails if
+    /// collisions are detected and the fingerprint belongs to a different key.
+    pub fn insert<K: Hash + ?Sized>(&mut self, key: &K) -> Result<bool, FingerprintCollision<F>> {
+        let fingerprint = self.fingerprint(key);
+        if let Some(checks) = &mut self.checks {
+            let check = salted(key, &self.build, CHECK);
+            match checks.entry(fingerprint) {
+                Entry::Occupied(entry) if *entry.get() != check => {
+                    return Err(FingerprintCollision { fingerprint });
+                }
+                Entry::Occupied(_) => {}
+                Entry::Vacant(entry) => {
+                    entry.insert(check);
+                }
+            }
+        }
+        let before = self.uf.size();
+        self.uf.insert(fingerprint);
+        Ok(self.uf.size() > before)
+    }
+
+    /// The fingerprint leading the class of key, or None if key is missing.
+    pub fn find<K: Hash + ?Sized>(&self, key: &K) -> Option<Rc<F>> {
+        self.uf.find(&self.checked(key)?)
+    }
+
+    /// Unions the classes of x and y, returning the new leading fingerprint, or None if one
+    /// of them is missing.
+    pub fn union<K: Hash + ?Sized>(&mut self, x: &K, y: &K) -> Option<Rc<F>> {
+        let (x, y) = (self.checked(x)?, self.checked(y)?);
+        self.uf.union(&x, &y)
+    }
+
+    /// Whether x and y are both present and in the same class.
+    pub fn equiv<K: Hash + ?Sized>(&self, x: &K, y: &K) -> bool {
+        match (self.find(x), self.find(y)) {
+            (Some(x), Some(y)) => x == y,
+            _ => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Fingerprints only the first byte of every write, so keys starting alike collide, but
+    // hashes everything for the check hashes.
+    #[derive(Default)]
+    struct Weak {
+        hasher: DefaultHasher,
+        // Whether the salt was the fingerprint's, set by the first write.
+        weak: Option<bool>,
+    }
+
+    impl Hasher for Weak {
+        fn finish(&self) -> u64 {
+            self.hasher.finish()
+        }
+
+        fn write(&mut self, bytes: &[u8]) {
+            match self.weak {
+                Some(true) => self.hasher.write(&bytes[..bytes.len().min(1)]),
+                Some(false) => self.hasher.write(bytes),
+                None => {
+                    self.weak = Some(bytes != [CHECK]);
+                    self.hasher.write(bytes);
+                }
+            }
+        }
+    }
+
+    type WeakUnionFind = FingerprintUnionFind<u64, BuildHasherDefault<Weak>>;
+
+    #[test]
+    fn collisions() {
+        let mut trusting = WeakUnionFind::default();
+        assert_eq!(Ok(true), trusting.insert("apple"));
+        assert_eq!(Ok(false), trusting.insert("avocado"));
+        assert!(trusting.equiv("apple", "avocado"));
+
+        let mut checked = WeakUnionFind::default().detect_collisions();
+        assert_eq!(Ok(true), checked.insert(&"apple".to_string()));
+        assert_eq!(Ok(false), checked.insert(&"apple".to_string()));
+        assert_eq!(Ok(true), checked.insert(&"banana".to_string()));
+        let fingerprint = checked.fingerprint("apple");
+        assert_eq!(Err(FingerprintCollision { fingerprint }), checked.insert("avocado"));
+        assert_eq!(None, checked.find("avocado"));
+        assert_eq!(None, checked.union("apple", "avocado"));
+        assert_eq!(Some(Rc::new(fingerprint)), checked.union("apple", "banana"));
+        assert!(checked.equiv("banana", "apple"));
+        assert_eq!((2, 1), (checked.size(), checked.num_classes()));
+    }
+
+    #[test]
+    fn wide_fingerprints_are_stable() {
+        let uf = FingerprintUnionFind::<u128>::new();
+        let fingerprint = uf.fingerprint(&42u32);
+        assert_eq!(fingerprint, FingerprintUnionFind::<u128>::new().fingerprint(&42u32));
+        assert_ne!(fingerprint >> 64, fingerprint & u64::MAX as u128);
+        assert_ne!(fingerprint, uf.fingerprint(&43u32));
+    }
+}
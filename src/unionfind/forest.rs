@@ -0,0 +1,149 @@
+use std::{
+    borrow::Borrow,
+    hash::Hash,
+    ops::{Index, IndexMut},
+};
+
+use crate::Backend;
+
+use super::Node;
+
+// The keys of a union-find in their backend and the node of every key at the same position,
+// so the forest links nodes by position whatever the layout of the keys.
+#[derive(Debug, Clone)]
+pub(super) struct Forest<T, B> {
+    backend: B,
+    nodes: Vec<Node<T>>,
+}
+
+impl<T, B: Backend<Key = T>> Forest<T, B> {
+    // A forest with every key already in backend as a singleton.
+    pub(super) fn new(backend: B) -> Self {
+        let mut nodes = Vec::with_capacity(backend.capacity());
+        nodes.extend((0..backend.len()).map(Node::new));
+        Self { backend, nodes }
+    }
+
+    // A forest over the keys of backend where the entry at every position i hangs straight
+    // below roots[i], the root of its tree, with the sizes and member cycles of those trees.
+    pub(super) fn from_roots(backend: B, roots: &[usize]) -> Self {
+        let mut forest = Self::new(backend);
+        for (i, &root) in roots.iter().enumerate().filter(|&(i, &root)| i != root) {
+            let leader = &mut forest.nodes[root];
+            leader.rank = 1;
+            leader.size += 1;
+            let next = std::mem::replace(&mut leader.next, crate::small(i));
+            let node = &mut forest.nodes[i];
+            node.parent = crate::small(root);
+            node.next = next;
+        }
+        forest
+    }
+
+    pub(super) fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    pub(super) fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+
+    pub(super) fn capacity(&self) -> usize {
+        self.backend.capacity().min(self.nodes.capacity())
+    }
+
+    pub(super) fn reserve(&mut self, additional: usize) {
+        self.backend.reserve(additional);
+        self.nodes.reserve(additional);
+    }
+
+    pub(super) fn shrink_to_fit(&mut self) {
+        self.backend.shrink_to_fit();
+        self.nodes.shrink_to_fit();
+    }
+
+    pub(super) fn get_index_of<Q: Hash + Eq + ?Sized>(&self, key: &Q) -> Option<usize>
+    where
+        T: Borrow<Q>,
+    {
+        self.backend.position(key)
+    }
+
+    pub(super) fn contains_key<Q: Hash + Eq + ?Sized>(&self, key: &Q) -> bool
+    where
+        T: Borrow<Q>,
+    {
+        self.get_index_of(key).is_some()
+    }
+
+    pub(super) fn get_index(&self, index: usize) -> Option<(&T, &Node<T>)> {
+        Some((self.backend.key(index)?, self.nodes.get(index)?))
+    }
+
+    pub(super) fn keys(&self) -> impl ExactSizeIterator<Item = &T> + '_ {
+        (0..self.len()).map(|i| self.backend.key(i).unwrap())
+    }
+
+    pub(super) fn values(&self) -> std::slice::Iter<'_, Node<T>> {
+        self.nodes.iter()
+    }
+
+    pub(super) fn values_mut(&mut self) -> std::slice::IterMut<'_, Node<T>> {
+        self.nodes.iter_mut()
+    }
+
+    pub(super) fn iter(&self) -> impl ExactSizeIterator<Item = (&T, &Node<T>)> + '_ {
+        self.keys().zip(&self.nodes)
+    }
+
+    // Adds key as a singleton if it is new, returning its index and whether it was new.
+    pub(super) fn insert_full(&mut self, key: T) -> (usize, bool) {
+        let (index, new) = self.backend.insert(key);
+        if new {
+            self.nodes.push(Node::new(index));
+        }
+        (index, new)
+    }
+
+    pub(super) fn pop(&mut self) -> Option<T> {
+        self.nodes.pop()?;
+        self.backend.pop()
+    }
+
+    // Removes key and its node, the later entries move down one index, whose links the
+    // caller fixes.
+    pub(super) fn shift_remove_full<Q: Hash + Eq + ?Sized>(&mut self, key: &Q) -> Option<(usize, T)>
+    where
+        T: Borrow<Q>,
+    {
+        let (index, key) = self.backend.shift_remove(key)?;
+        self.nodes.remove(index);
+        Some((index, key))
+    }
+
+    // Moves the entry at order[i] to index i, the caller fixes the links.
+    pub(super) fn permute(&mut self, order: &[usize]) {
+        self.backend.permute(order);
+        let mut nodes: Vec<Option<Node<T>>> = self.nodes.drain(..).map(Some).collect();
+        self.nodes.extend(order.iter().map(|&old| nodes[old].take().unwrap()));
+    }
+
+    // The keys and nodes by index.
+    pub(super) fn into_parts(self) -> (Vec<T>, Vec<Node<T>>) {
+        (self.backend.into_keys(), self.nodes)
+    }
+}
+
+impl<T, B> Index<usize> for Forest<T, B> {
+    type Output = Node<T>;
+
+    fn index(&self, index: usize) -> &Node<T> {
+        &self.nodes[index]
+    }
+}
+
+impl<T, B> IndexMut<usize> for Forest<T, B> {
+    fn index_mut(&mut self, index: usize) -> &mut Node<T> {
+        &mut self.nodes[index]
+    }
+}
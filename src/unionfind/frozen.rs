@@ -0,0 +1,191 @@
+use std::{borrow::Borrow, collections::HashMap, fmt::Debug, hash::Hash};
+use indexmap::IndexMap;
+
+use crate::Backend;
+
+use super::UnionFind;
+
+/// An immutable, fully compressed copy of a partition.
+///
+/// Every element maps straight to the index of its leader, so [FrozenPartition::find] is a
+/// single lookup with no interior mutability. It is `Send + Sync` whenever `T` is, and
+/// can be shared between threads freely.
+#[derive(Debug, Clone)]
+pub struct FrozenPartition<T> {
+    leaders: IndexMap<T, usize>,
+    classes: usize,
+}
+
+impl<T: Hash + Eq> FrozenPartition<T> {
+    /// The leader of the class of x, or None if x is missing.
+    pub fn find<Q: Hash + Eq + ?Sized>(&self, x: &Q) -> Option<&T>
+    where
+        T: Borrow<Q>,
+    {
+        let leader = *self.leaders.get(x)?;
+        self.leaders.get_index(leader).map(|(t, _)| t)
+    }
+
+    /// Whether x and y are in the same class, None if one of them is missing.
+    pub fn equiv<Q: Hash + Eq + ?Sized>(&self, x: &Q, y: &Q) -> Option<bool>
+    where
+        T: Borrow<Q>,
+    {
+        Some(self.leaders.get(x)? == self.leaders.get(y)?)
+    }
+
+    pub fn size(&self) -> usize {
+        self.leaders.len()
+    }
+
+    pub fn num_classes(&self) -> usize {
+        self.classes
+    }
+}
+
+impl<T: Hash + Eq + Clone + Debug, B: Backend<Key = T>> UnionFind<T, B> {
+    /// Takes an immutable, compressed copy of the current partition, see [FrozenPartition].
+    /// Compresses every path as a side effect.
+    pub fn freeze(&self) -> FrozenPartition<T> {
+        let mut ps = self.access();
+        let leaders = (0..ps.len())
+            .map(|i| {
+                let leader = ps.leader(i);
+                (ps.get_index(i).unwrap().0.clone(), leader)
+            })
+            .collect();
+        FrozenPartition {
+            leaders,
+            classes: self.classes,
+        }
+    }
+
+    /// The partition as a substitution from every element to the leader of its class. With
+    /// `with_identity` false, leaders mapping to themselves are left out, so the map holds
+    /// only elements that actually get rewritten. Compresses every path as a side effect.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::collections::HashMap;
+    /// use hash_unionfind::UnionFind;
+    ///
+    /// let mut uf = UnionFind::new();
+    /// uf.insert_many(["x", "y", "z"]);
+    /// uf.union(&"x", &"y");
+    /// assert_eq!(HashMap::from([("y", "x")]), uf.substitution(false));
+    /// assert_eq!(3, uf.substitution(true).len());
+    /// ```
+    pub fn substitution(&self, with_identity: bool) -> HashMap<T, T> {
+        let mut ps = self.access();
+        let mut substitution = HashMap::with_capacity(if with_identity { ps.len() } else { ps.len() - self.classes });
+        for i in 0..ps.len() {
+            let leader = ps.leader(i);
+            if !with_identity && leader == i {
+                continue;
+            }
+            substitution.insert(ps.get_index(i).unwrap().0.clone(), ps.get_index(leader).unwrap().0.clone());
+        }
+        substitution
+    }
+}
+
+/// Publishes [FrozenPartition]s of a union-find through an [arc_swap::ArcSwap].
+///
+/// The writer keeps mutating its private [UnionFind] and calls [SnapshotPublisher::publish]
+/// whenever readers should see the changes. Readers on any thread [load](SnapshotPublisher::load)
+/// the latest snapshot without locks.
+///
+/// # Examples
+///
+/// ```
+/// use std::sync::Arc;
+/// use hash_unionfind::{SnapshotPublisher, UnionFind};
+///
+/// let mut uf = UnionFind::new();
+/// uf.insert_many([1, 2]);
+/// let publisher = Arc::new(SnapshotPublisher::new(&uf));
+///
+/// let reader = publisher.clone();
+/// std::thread::spawn(move || assert_eq!(Some(false), reader.load().equiv(&1, &2))).join().unwrap();
+///
+/// uf.union(&1, &2);
+/// publisher.publish(&uf);
+/// let reader = publisher.clone();
+/// std::thread::spawn(move || assert_eq!(Some(true), reader.load().equiv(&1, &2))).join().unwrap();
+/// ```
+#[cfg(feature = "arc-swap")]
+#[derive(Debug)]
+pub struct SnapshotPublisher<T> {
+    current: arc_swap::ArcSwap<FrozenPartition<T>>,
+}
+
+#[cfg(feature = "arc-swap")]
+impl<T: Hash + Eq + Clone + Debug> SnapshotPublisher<T> {
+    /// Starts publishing with a snapshot of `uf`.
+    pub fn new<B: Backend<Key = T>>(uf: &UnionFind<T, B>) -> Self {
+        Self {
+            current: arc_swap::ArcSwap::from_pointee(uf.freeze()),
+        }
+    }
+
+    /// Replaces the published snapshot with one of `uf`. Readers holding older snapshots keep
+    /// them until they drop them.
+    pub fn publish<B: Backend<Key = T>>(&self, uf: &UnionFind<T, B>) {
+        self.current.store(std::sync::Arc::new(uf.freeze()));
+    }
+
+    /// The latest snapshot, cheap enough for every query.
+    pub fn load(&self) -> arc_swap::Guard<std::sync::Arc<FrozenPartition<T>>> {
+        self.current.load()
+    }
+
+    /// The latest snapshot as an owned [Arc](std::sync::Arc), for holding it long.
+    pub fn load_full(&self) -> std::sync::Arc<FrozenPartition<T>> {
+        self.current.load_full()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn freeze() {
+        let mut uf = UnionFind::new();
+        uf.insert_many(0..5);
+        uf.union(&0, &1);
+        uf.union(&2, &1);
+        let frozen = uf.freeze();
+        uf.union(&3, &4);
+
+        assert_eq!(5, frozen.size());
+        assert_eq!(3, frozen.num_classes());
+        assert_eq!(Some(&0), frozen.find(&2));
+        assert_eq!(Some(&3), frozen.find(&3));
+        assert_eq!(Some(false), frozen.equiv(&3, &4));
+        assert_eq!(Some(true), frozen.equiv(&1, &2));
+        assert_eq!(None, frozen.find(&5));
+
+        // Freezing leaves every element pointing at its leader.
+        uf.union(&0, &3);
+        uf.freeze();
+        assert_eq!(4.0 / 5.0, uf.stats().average_find_depth);
+    }
+
+    #[test]
+    fn substitution() {
+        let mut uf = UnionFind::new();
+        uf.insert_many(0..8);
+        for (x, y) in [(0, 1), (2, 3), (0, 2), (4, 5), (4, 6)] {
+            uf.union(&x, &y);
+        }
+        let expected = HashMap::from([(1, 0), (2, 0), (3, 0), (5, 4), (6, 4)]);
+        assert_eq!(expected, uf.substitution(false));
+        let full = uf.substitution(true);
+        assert_eq!(8, full.len());
+        assert_eq!(Some(&7), full.get(&7));
+        assert_eq!(Some(&0), full.get(&3));
+        assert!(UnionFind::<u8>::new().substitution(false).is_empty());
+    }
+}
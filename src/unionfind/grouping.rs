@@ -0,0 +1,246 @@
+use std::{collections::HashSet, fmt::Debug, hash::Hash, rc::Rc};
+use indexmap::IndexMap;
+
+use crate::{Backend, HashBackend};
+
+use super::UnionFind;
+
+impl<T: Hash + Eq + Clone + Debug, B: Backend<Key = T>> UnionFind<T, B> {
+    // The leader of key, treating keys that were never inserted as singleton classes.
+    pub(super) fn class_key(&self, key: &T) -> Rc<T> {
+        self.find(key).unwrap_or_else(|| Rc::new(key.clone()))
+    }
+
+    /// Buckets items by the class of the key extracted from each, yielding every class leader
+    /// with its items in order of first appearance.
+    ///
+    /// Items whose key was never inserted are treated as singleton classes of that key.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use hash_unionfind::UnionFind;
+    ///
+    /// let mut uf = UnionFind::new();
+    /// uf.insert_many(["a", "b", "c"]);
+    /// uf.union(&"a", &"c");
+    ///
+    /// let records = vec![("a", 1), ("b", 2), ("c", 3)];
+    /// let groups: Vec<_> = uf.group_by(records, |(k, _)| k).collect();
+    /// assert_eq!(groups[0].1, vec![("a", 1), ("c", 3)]);
+    /// assert_eq!(groups[1].1, vec![("b", 2)]);
+    /// ```
+    pub fn group_by<I, F>(&self, items: I, mut key: F) -> impl Iterator<Item = (Rc<T>, Vec<I::Item>)>
+    where
+        I: IntoIterator,
+        F: FnMut(&I::Item) -> &T,
+    {
+        let mut groups: IndexMap<Rc<T>, Vec<I::Item>> = IndexMap::new();
+        for item in items {
+            let leader = self.class_key(key(&item));
+            groups.entry(leader).or_default().push(item);
+        }
+        groups.into_iter()
+    }
+
+    /// Reorders the slice so exactly one element per class, the first occurrence, comes first,
+    /// keeping their order. Returns the representatives and the remaining duplicates, whose
+    /// order is unspecified. Elements that were never inserted are treated as singleton classes.
+    pub fn partition_dedup<'a>(&self, items: &'a mut [T]) -> (&'a mut [T], &'a mut [T]) {
+        let mut seen = HashSet::new();
+        let mut kept = 0;
+        for i in 0..items.len() {
+            if seen.insert(self.class_key(&items[i])) {
+                items.swap(kept, i);
+                kept += 1;
+            }
+        }
+        items.split_at_mut(kept)
+    }
+
+    /// Retains exactly one element per class in the vector, the first occurrence,
+    /// preserving order. See [UnionFind::partition_dedup].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use hash_unionfind::UnionFind;
+    ///
+    /// let mut uf = UnionFind::new();
+    /// uf.insert_many(["x", "y", "z"]);
+    /// uf.union(&"x", &"z");
+    ///
+    /// let mut terms = vec!["z", "y", "x", "w", "y"];
+    /// uf.dedup_vec(&mut terms);
+    /// assert_eq!(terms, vec!["z", "y", "w"]);
+    /// ```
+    pub fn dedup_vec(&self, items: &mut Vec<T>) {
+        let kept = self.partition_dedup(items).0.len();
+        items.truncate(kept);
+    }
+}
+
+impl<T: Hash + Eq + Clone + Debug + Ord, B: Backend<Key = T>> UnionFind<T, B> {
+    /// A canonical form of the partition: every class with its members sorted, and the classes
+    /// sorted by their minimal element. It depends only on the partition, not on insertion
+    /// order, union order or which elements lead, so it can be compared across runs.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use hash_unionfind::UnionFind;
+    ///
+    /// let mut uf = UnionFind::new();
+    /// uf.insert_many([5, 3, 4, 1, 2]);
+    /// uf.union(&5, &1);
+    /// uf.union(&4, &2);
+    /// assert_eq!(uf.to_canonical_vec(), vec![vec![1, 5], vec![2, 4], vec![3]]);
+    /// ```
+    pub fn to_canonical_vec(&self) -> Vec<Vec<T>> {
+        let mut classes: Vec<Vec<T>> = self.group_by(self.elements(), |t| t).map(|(_, members)| members).collect();
+        for class in &mut classes {
+            class.sort();
+        }
+        // Classes are disjoint and non empty, so their first elements are distinct.
+        classes.sort_unstable_by(|a, b| a[0].cmp(&b[0]));
+        classes
+    }
+}
+
+/// Iterator adapters that consult a [UnionFind].
+pub trait UnionFindIterExt: Iterator + Sized {
+    /// Keeps only the first item seen for every equivalence class of the key extracted
+    /// from each item. Keys that were never inserted are treated as singleton classes.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use hash_unionfind::{UnionFind, UnionFindIterExt};
+    ///
+    /// let mut uf = UnionFind::new();
+    /// uf.insert_many(0..4);
+    /// uf.union(&0, &3);
+    ///
+    /// let firsts: Vec<i32> = vec![3, 1, 0, 2, 1].into_iter().dedup_by_class(&uf, |i| i).collect();
+    /// assert_eq!(firsts, vec![3, 1, 2]);
+    /// ```
+    fn dedup_by_class<T, F, B>(self, uf: &UnionFind<T, B>, key: F) -> DedupByClass<'_, T, Self, F, B>
+    where
+        T: Hash + Eq + Clone + Debug,
+        B: Backend<Key = T>,
+        F: FnMut(&Self::Item) -> &T,
+    {
+        DedupByClass {
+            uf,
+            iter: self,
+            key,
+            seen: HashSet::new(),
+        }
+    }
+}
+
+impl<I: Iterator> UnionFindIterExt for I {}
+
+/// Iterator returned by [UnionFindIterExt::dedup_by_class].
+pub struct DedupByClass<'a, T: Hash + Eq + Clone + Debug, I, F, B = HashBackend<T>> {
+    uf: &'a UnionFind<T, B>,
+    iter: I,
+    key: F,
+    seen: HashSet<Rc<T>>,
+}
+
+impl<'a, T, I, F, B> Iterator for DedupByClass<'a, T, I, F, B>
+where
+    T: Hash + Eq + Clone + Debug,
+    B: Backend<Key = T>,
+    I: Iterator,
+    F: FnMut(&I::Item) -> &T,
+{
+    type Item = I::Item;
+
+    fn next(&mut self) -> Option<I::Item> {
+        loop {
+            let item = self.iter.next()?;
+            if self.seen.insert(self.uf.class_key((self.key)(&item))) {
+                return Some(item);
+            }
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, self.iter.size_hint().1)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn group_by_unknown_keys() {
+        let mut uf = UnionFind::new();
+        uf.insert_many(0..4);
+        uf.union(&0, &2);
+        uf.union(&1, &3);
+
+        let items = vec![3, 7, 0, 1, 7, 2, 8];
+        let groups: Vec<(Rc<i32>, Vec<i32>)> = uf.group_by(items, |i| i).collect();
+        assert_eq!(groups, vec![
+            (Rc::new(1), vec![3, 1]),
+            (Rc::new(7), vec![7, 7]),
+            (Rc::new(0), vec![0, 2]),
+            (Rc::new(8), vec![8]),
+        ]);
+    }
+
+    #[test]
+    fn dedup_by_class() {
+        let mut uf = UnionFind::new();
+        uf.insert_many(["a", "b", "c", "d"]);
+        uf.union(&"a", &"d");
+        uf.union(&"b", &"c");
+
+        let records = [("d", 1), ("c", 2), ("x", 3), ("a", 4), ("x", 5), ("y", 6), ("b", 7)];
+        let deduped: Vec<_> = records.iter().dedup_by_class(&uf, |(k, _)| k).map(|(_, v)| *v).collect();
+        assert_eq!(deduped, vec![1, 2, 3, 6]);
+    }
+
+    #[test]
+    fn partition_dedup() {
+        let mut uf = UnionFind::new();
+        uf.insert_many(0..6);
+        uf.union(&0, &1);
+        uf.union(&2, &3);
+        uf.union(&3, &4);
+
+        let mut items = vec![4, 1, 0, 9, 2, 5, 9, 3];
+        let (reps, dups) = uf.partition_dedup(&mut items);
+        assert_eq!(reps, &[4, 1, 9, 5]);
+        dups.sort();
+        assert_eq!(dups, &[0, 2, 3, 9]);
+
+        let mut empty: Vec<i32> = vec![];
+        uf.dedup_vec(&mut empty);
+        assert!(empty.is_empty());
+    }
+
+    #[test]
+    fn canonical_vec_ignores_order() {
+        let mut a = UnionFind::new();
+        a.insert_many(["d", "a", "c", "b", "e"]);
+        a.union(&"d", &"b");
+        a.union(&"e", &"a");
+        a.union(&"b", &"e");
+
+        let mut b = UnionFind::new();
+        b.insert_many(["e", "c", "b", "a", "d"]);
+        b.union(&"a", &"b");
+        b.union(&"d", &"e");
+        b.union(&"a", &"d");
+
+        let expected = vec![vec!["a", "b", "d", "e"], vec!["c"]];
+        assert_eq!(expected, a.to_canonical_vec());
+        assert_eq!(expected, b.to_canonical_vec());
+        assert!(UnionFind::<u8>::new().to_canonical_vec().is_empty());
+    }
+}
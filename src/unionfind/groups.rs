@@ -0,0 +1,267 @@
+//! Serializes a [UnionFind] as a list of its classes, each a list of full element values with
+//! the leader first, which also suits formats whose map keys must be strings (JSON, YAML, TOML).
+//!
+//! This is the format of the `Serialize` and `Deserialize` impls of [UnionFind]. The module
+//! names it for `#[serde(with = "hash_unionfind::serde_groups")]`, or with the `serde_with`
+//! feature as `#[serde_as(as = "hash_unionfind::serde_groups::Groups")]`, which also composes
+//! inside containers such as `Option` or `Vec`. Element weights and opt-in recordings are not
+//! part of this format.
+//!
+//! Classes come in insertion order and start with their leader, which depends on the order of
+//! the unions. For ordered keys, [canonical] sorts the members and the classes instead, so
+//! equal partitions serialize identically however they were built.
+//!
+//! # Examples
+//!
+//! ```
+//! use hash_unionfind::{serde_groups, uf, UnionFind};
+//! use serde::{Deserialize, Serialize};
+//!
+//! #[derive(Serialize, Deserialize)]
+//! struct Config {
+//!     #[serde(with = "serde_groups")]
+//!     aliases: UnionFind<(u8, u8)>,
+//! }
+//!
+//! let config = Config { aliases: uf! { {(0, 1), (2, 3)}, {(4, 5)} } };
+//! let json = serde_json::to_string(&config).unwrap();
+//! assert_eq!(json, r#"{"aliases":[[[0,1],[2,3]],[[4,5]]]}"#);
+//!
+//! let back: Config = serde_json::from_str(&json).unwrap();
+//! assert_eq!(back.aliases, config.aliases);
+//! ```
+
+use std::{fmt::Debug, hash::Hash};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::Backend;
+
+use super::UnionFind;
+
+impl<T: Hash + Eq + Clone + Debug + Serialize, B: Backend<Key = T>> Serialize for UnionFind<T, B> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serialize(self, serializer)
+    }
+}
+
+impl<'de, T, B> Deserialize<'de> for UnionFind<T, B>
+where
+    T: Hash + Eq + Clone + Debug + Deserialize<'de>,
+    B: Backend<Key = T> + Default,
+{
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserialize(deserializer)
+    }
+}
+
+/// Serializes every class of `uf` as a list of its members, leader first.
+pub fn serialize<T, B, S>(uf: &UnionFind<T, B>, serializer: S) -> Result<S::Ok, S::Error>
+where
+    T: Hash + Eq + Clone + Debug + Serialize,
+    B: Backend<Key = T>,
+    S: Serializer,
+{
+    let ps = uf.parents.borrow();
+    let groups = (0..ps.len())
+        .filter(|&i| crate::wide(ps[i].parent) == i)
+        .map(|leader| UnionFind::member_indices_in(&ps, leader).into_iter().map(|m| ps.get_index(m).unwrap().0));
+    serializer.collect_seq(groups.map(|members| members.collect::<Vec<_>>()))
+}
+
+/// Rebuilds a union-find from a list of classes, the first member of each leading it.
+/// Classes sharing an element are merged, and empty classes are ignored.
+pub fn deserialize<'de, T, B, D>(deserializer: D) -> Result<UnionFind<T, B>, D::Error>
+where
+    T: Hash + Eq + Clone + Debug + Deserialize<'de>,
+    B: Backend<Key = T> + Default,
+    D: Deserializer<'de>,
+{
+    let groups = Vec::<Vec<T>>::deserialize(deserializer)?;
+    let mut uf = UnionFind::with_backend(B::default());
+    for group in groups {
+        let mut members = group.into_iter();
+        let Some(first) = members.next() else {
+            continue;
+        };
+        uf.insert(first.clone());
+        for member in members {
+            uf.insert(member.clone());
+            uf.union(&first, &member);
+        }
+    }
+    Ok(uf)
+}
+
+/// The list of groups format as a `serde_with` adaptor.
+#[cfg(feature = "serde_with")]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Groups;
+
+#[cfg(feature = "serde_with")]
+impl<T, B> serde_with::SerializeAs<UnionFind<T, B>> for Groups
+where
+    T: Hash + Eq + Clone + Debug + Serialize,
+    B: Backend<Key = T>,
+{
+    fn serialize_as<S: Serializer>(source: &UnionFind<T, B>, serializer: S) -> Result<S::Ok, S::Error> {
+        serialize(source, serializer)
+    }
+}
+
+#[cfg(feature = "serde_with")]
+impl<'de, T, B> serde_with::DeserializeAs<'de, UnionFind<T, B>> for Groups
+where
+    T: Hash + Eq + Clone + Debug + Deserialize<'de>,
+    B: Backend<Key = T> + Default,
+{
+    fn deserialize_as<D: Deserializer<'de>>(deserializer: D) -> Result<UnionFind<T, B>, D::Error> {
+        deserialize(deserializer)
+    }
+}
+
+/// The list of groups format with sorted members and classes sorted by their smallest
+/// member, as returned by [UnionFind::to_canonical_vec]. Deserializing reads any list of
+/// groups, the smallest member of each class leading it.
+///
+/// # Examples
+///
+/// ```
+/// use hash_unionfind::{serde_groups, uf};
+///
+/// let mut json = vec![];
+/// for uf in [uf! { {3, 1}, {2} }, uf! { {2}, {1, 3} }] {
+///     let mut serializer = serde_json::Serializer::new(vec![]);
+///     serde_groups::canonical::serialize(&uf, &mut serializer).unwrap();
+///     json.push(String::from_utf8(serializer.into_inner()).unwrap());
+/// }
+/// assert_eq!(json[0], "[[1,3],[2]]");
+/// assert_eq!(json[0], json[1]);
+/// ```
+pub mod canonical {
+    use super::*;
+
+    pub fn serialize<T, B, S>(uf: &UnionFind<T, B>, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        T: Hash + Eq + Clone + Debug + Ord + Serialize,
+        B: Backend<Key = T>,
+        S: Serializer,
+    {
+        serializer.collect_seq(uf.to_canonical_vec())
+    }
+
+    pub fn deserialize<'de, T, B, D>(deserializer: D) -> Result<UnionFind<T, B>, D::Error>
+    where
+        T: Hash + Eq + Clone + Debug + Ord + Deserialize<'de>,
+        B: Backend<Key = T> + Default,
+        D: Deserializer<'de>,
+    {
+        let mut groups = Vec::<Vec<T>>::deserialize(deserializer)?;
+        for group in &mut groups {
+            group.sort();
+        }
+        let mut uf = UnionFind::with_backend(B::default());
+        for group in groups.into_iter().filter(|group| !group.is_empty()) {
+            uf.insert_many(group.iter().cloned());
+            for member in &group[1..] {
+                uf.union(&group[0], member);
+            }
+        }
+        Ok(uf)
+    }
+
+    /// The canonical format as a `serde_with` adaptor.
+    #[cfg(feature = "serde_with")]
+    #[derive(Debug, Clone, Copy, Default)]
+    pub struct CanonicalGroups;
+
+    #[cfg(feature = "serde_with")]
+    impl<T, B> serde_with::SerializeAs<UnionFind<T, B>> for CanonicalGroups
+    where
+        T: Hash + Eq + Clone + Debug + Ord + Serialize,
+        B: Backend<Key = T>,
+    {
+        fn serialize_as<S: Serializer>(source: &UnionFind<T, B>, serializer: S) -> Result<S::Ok, S::Error> {
+            serialize(source, serializer)
+        }
+    }
+
+    #[cfg(feature = "serde_with")]
+    impl<'de, T, B> serde_with::DeserializeAs<'de, UnionFind<T, B>> for CanonicalGroups
+    where
+        T: Hash + Eq + Clone + Debug + Ord + Deserialize<'de>,
+        B: Backend<Key = T> + Default,
+    {
+        fn deserialize_as<D: Deserializer<'de>>(deserializer: D) -> Result<UnionFind<T, B>, D::Error> {
+            deserialize(deserializer)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::rc::Rc;
+
+    #[derive(Serialize, Deserialize)]
+    struct Wrapper(#[serde(with = "super")] UnionFind<(String, u32)>);
+
+    #[test]
+    fn groups_keep_leaders() {
+        let mut uf = UnionFind::new();
+        let key = |s: &str, i| (s.to_string(), i);
+        uf.insert_many([key("a", 1), key("b", 2), key("c", 3), key("d", 4)]);
+        uf.union(&key("c", 3), &key("a", 1));
+        uf.union(&key("c", 3), &key("d", 4));
+
+        let json = serde_json::to_string(&Wrapper(uf)).unwrap();
+        let Wrapper(back) = serde_json::from_str(&json).unwrap();
+        assert_eq!(2, back.num_classes());
+        assert_eq!(Some(Rc::new(key("c", 3))), back.find(&key("d", 4)));
+        assert_eq!(Some(1), back.class_size(&key("b", 2)));
+
+        let Wrapper(merged) = serde_json::from_str(r#"[[["x",1],["y",2]],[],[["z",3],["x",1]]]"#).unwrap();
+        assert_eq!(1, merged.num_classes());
+        assert_eq!(3, merged.size());
+    }
+
+    #[test]
+    fn impls_write_groups() {
+        // Keys that are not strings need not become JSON map keys.
+        let mut uf = UnionFind::new();
+        uf.insert_many([(1, 'a'), (2, 'b'), (3, 'c')]);
+        uf.union(&(3, 'c'), &(1, 'a'));
+        let json = serde_json::to_string(&uf).unwrap();
+        assert_eq!(r#"[[[2,"b"]],[[3,"c"],[1,"a"]]]"#, json);
+        let back: UnionFind<(u8, char)> = serde_json::from_str(&json).unwrap();
+        assert_eq!(back, uf);
+        assert_eq!(Some(Rc::new((3, 'c'))), back.find(&(1, 'a')));
+        // Any backend reads and writes the same format.
+        let dense: UnionFind<u32, crate::DenseBackend<u32>> = serde_json::from_str("[[4,2],[7]]").unwrap();
+        assert_eq!(Some(Rc::new(4)), dense.find(&2));
+        assert_eq!("[[4,2],[7]]", serde_json::to_string(&dense).unwrap());
+    }
+
+    #[cfg(feature = "serde_with")]
+    #[test]
+    fn serde_with_adaptor() {
+        #[serde_with::serde_as]
+        #[derive(Serialize, Deserialize)]
+        struct Snapshot {
+            #[serde_as(as = "Option<Groups>")]
+            partition: Option<UnionFind<(u8, u8)>>,
+            #[serde_as(as = "Vec<canonical::CanonicalGroups>")]
+            history: Vec<UnionFind<u8>>,
+        }
+
+        let snapshot = Snapshot {
+            partition: Some(crate::uf! { {(1, 1), (2, 2)} }),
+            history: vec![crate::uf! { {2, 1}, {0} }],
+        };
+        let json = serde_json::to_string(&snapshot).unwrap();
+        assert_eq!(json, r#"{"partition":[[[1,1],[2,2]]],"history":[[[0],[1,2]]]}"#);
+        let back: Snapshot = serde_json::from_str(&json).unwrap();
+        assert_eq!(back.partition, snapshot.partition);
+        assert_eq!(back.history[0].to_canonical_vec(), snapshot.history[0].to_canonical_vec());
+        assert_eq!(Some(Rc::new(1)), back.history[0].find(&2));
+    }
+}
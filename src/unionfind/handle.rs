@@ -0,0 +1,108 @@
+use std::{borrow::Borrow, fmt::Debug, hash::Hash, rc::Rc};
+
+use crate::Backend;
+
+use super::UnionFind;
+
+/// A leader that keeps resolving to the current leader of its class across later unions.
+///
+/// A plain `Rc<T>` returned by [UnionFind::find] is only the leader until its class is
+/// merged under another one. The handle instead follows the forwarding from the leader it
+/// was taken at, which stays in the class across unions, so unions never make it stale.
+///
+/// Splits are not followed: once [UnionFind::make_singleton] detaches the leader the handle
+/// was taken at, the handle resolves to that element's new singleton class rather than the
+/// rest of its old class, and once [UnionFind::remove] drops it the handle resolves to None.
+///
+/// # Examples
+///
+/// ```
+/// use hash_unionfind::UnionFind;
+///
+/// let mut uf = UnionFind::new();
+/// uf.insert_many(0..4);
+/// uf.union(&2, &3);
+/// let mut handle = uf.find_handle(&0).unwrap();
+///
+/// uf.union(&3, &0);
+/// assert_eq!(0, **handle.last_seen());
+/// assert_eq!(2, *handle.leader(&uf).unwrap());
+/// assert!(handle.refresh(&uf));
+/// assert_eq!(2, **handle.last_seen());
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct LeaderHandle<T> {
+    // A leader of the class when last resolved.
+    leader: Rc<T>,
+}
+
+impl<T: Hash + Eq + Clone + Debug, B: Backend<Key = T>> UnionFind<T, B> {
+    /// Like [UnionFind::find], but returns a [LeaderHandle] for the class of x.
+    pub fn find_handle<Q: Hash + Eq + ?Sized>(&self, x: &Q) -> Option<LeaderHandle<T>>
+    where
+        T: Borrow<Q>,
+    {
+        self.find(x).map(|leader| LeaderHandle { leader })
+    }
+}
+
+impl<T: Hash + Eq + Clone + Debug> LeaderHandle<T> {
+    /// The current leader of the class. None if uf is not the union-find the handle was taken
+    /// from, or if the leader it was taken at has been removed since.
+    pub fn leader<B: Backend<Key = T>>(&self, uf: &UnionFind<T, B>) -> Option<Rc<T>> {
+        uf.find(&self.leader)
+    }
+
+    /// Whether the leader seen last is still the leader.
+    pub fn is_current<B: Backend<Key = T>>(&self, uf: &UnionFind<T, B>) -> bool {
+        self.leader(uf).is_some_and(|leader| leader == self.leader)
+    }
+
+    /// Resolves the current leader and remembers it, so later resolutions are shorter.
+    /// Returns whether the leader changed.
+    pub fn refresh<B: Backend<Key = T>>(&mut self, uf: &UnionFind<T, B>) -> bool {
+        match self.leader(uf) {
+            Some(leader) if leader != self.leader => {
+                self.leader = leader;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// The leader when the handle was taken or last refreshed, which may be stale.
+    pub fn last_seen(&self) -> &Rc<T> {
+        &self.leader
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn follows_merges() {
+        let mut uf = UnionFind::new();
+        uf.insert_many(0..8);
+        let handle = uf.find_handle(&5).unwrap();
+        assert!(uf.find_handle(&9).is_none());
+        for (x, y) in [(4, 5), (6, 7), (6, 4), (0, 1), (0, 6)] {
+            uf.union(&x, &y);
+            assert_eq!(uf.find(&5), handle.leader(&uf));
+        }
+        assert!(!handle.is_current(&uf));
+
+        let mut refreshed = handle.clone();
+        assert!(refreshed.refresh(&uf));
+        assert!(!refreshed.refresh(&uf));
+        assert!(refreshed.is_current(&uf));
+        assert_eq!(Rc::new(6), *refreshed.last_seen());
+        assert_eq!(None, handle.leader(&UnionFind::new()));
+
+        // Splitting the leader out leaves the handle with the split element.
+        uf.make_singleton(&5);
+        assert_eq!(Some(Rc::new(5)), handle.leader(&uf));
+        uf.remove(&5);
+        assert_eq!(None, handle.leader(&uf));
+    }
+}
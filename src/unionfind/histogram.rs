@@ -0,0 +1,181 @@
+use std::{borrow::Borrow, fmt::Debug, hash::Hash, rc::Rc};
+use indexmap::IndexMap;
+
+use super::{QuotientMap, UnionFind};
+
+/// Counts of categories within one class, in order of first appearance. Searching is linear,
+/// meant for a handful of categories.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Histogram<K> {
+    counts: Vec<(K, usize)>,
+}
+
+impl<K: PartialEq> Histogram<K> {
+    fn single(category: K) -> Self {
+        Self { counts: vec![(category, 1)] }
+    }
+
+    fn add(&mut self, category: K, count: usize) {
+        match self.counts.iter_mut().find(|(k, _)| *k == category) {
+            Some((_, c)) => *c += count,
+            None => self.counts.push((category, count)),
+        }
+    }
+
+    fn remove_one(&mut self, category: &K) {
+        let i = self.counts.iter().position(|(k, _)| k == category).unwrap();
+        self.counts[i].1 -= 1;
+        if self.counts[i].1 == 0 {
+            self.counts.remove(i);
+        }
+    }
+
+    fn merge(mut self, other: Self) -> Self {
+        for (category, count) in other.counts {
+            self.add(category, count);
+        }
+        self
+    }
+
+    /// The number of members tagged with category.
+    pub fn count(&self, category: &K) -> usize {
+        self.counts.iter().find(|(k, _)| k == category).map_or(0, |(_, c)| *c)
+    }
+
+    /// The categories present with their counts.
+    pub fn iter(&self) -> impl Iterator<Item = (&K, usize)> {
+        self.counts.iter().map(|(k, c)| (k, *c))
+    }
+
+    /// The number of members counted, the class size.
+    pub fn total(&self) -> usize {
+        self.counts.iter().map(|(_, c)| c).sum()
+    }
+}
+
+type Merge<K> = fn(Histogram<K>, Histogram<K>) -> Histogram<K>;
+
+/// A partition of tagged elements, keeping a [Histogram] of the categories in every class
+/// up to date on union, a [QuotientMap] over histograms.
+///
+/// # Examples
+///
+/// ```
+/// use hash_unionfind::CategoryCounts;
+///
+/// let mut docs = CategoryCounts::new();
+/// for (id, kind) in [(1, "invoice"), (2, "invoice"), (3, "refund"), (4, "invoice"), (5, "credit")] {
+///     docs.insert(id, kind);
+/// }
+/// docs.union(&1, &3);
+/// docs.union(&3, &5);
+/// docs.union(&5, &2);
+///
+/// let cluster = docs.histogram(&1).unwrap();
+/// assert_eq!(2, cluster.count(&"invoice"));
+/// assert_eq!(1, cluster.count(&"refund"));
+/// assert_eq!(4, cluster.total());
+/// ```
+#[derive(Debug, Clone)]
+pub struct CategoryCounts<T: Hash + Eq + Clone + Debug, K> {
+    classes: QuotientMap<T, Histogram<K>, Merge<K>>,
+    categories: IndexMap<T, K>,
+}
+
+impl<T: Hash + Eq + Clone + Debug, K: Clone + PartialEq> Default for CategoryCounts<T, K> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Hash + Eq + Clone + Debug, K: Clone + PartialEq> CategoryCounts<T, K> {
+    pub fn new() -> Self {
+        Self {
+            classes: QuotientMap::new(Histogram::merge as Merge<K>),
+            categories: IndexMap::new(),
+        }
+    }
+
+    /// Inserts t as a singleton tagged with category. Returns false and does nothing if t
+    /// already exists, use [CategoryCounts::set_category] to retag.
+    pub fn insert(&mut self, t: T, category: K) -> bool {
+        if self.categories.contains_key(&t) {
+            return false;
+        }
+        self.categories.insert(t.clone(), category.clone());
+        self.classes.insert(t, Histogram::single(category));
+        true
+    }
+
+    /// Retags t, updating the histogram of its class. Returns the previous category, or None
+    /// if t is missing.
+    pub fn set_category<Q: Hash + Eq + ?Sized>(&mut self, t: &Q, category: K) -> Option<K>
+    where
+        T: Borrow<Q>,
+    {
+        let old = std::mem::replace(self.categories.get_mut(t)?, category.clone());
+        let histogram = self.classes.get_mut(t).unwrap();
+        histogram.remove_one(&old);
+        histogram.add(category, 1);
+        Some(old)
+    }
+
+    pub fn category<Q: Hash + Eq + ?Sized>(&self, t: &Q) -> Option<&K>
+    where
+        T: Borrow<Q>,
+    {
+        self.categories.get(t)
+    }
+
+    /// Unions the classes of x and y, adding up their histograms. Returns the new leader, or
+    /// None if one of them is missing.
+    pub fn union<Q: Hash + Eq + ?Sized>(&mut self, x: &Q, y: &Q) -> Option<Rc<T>>
+    where
+        T: Borrow<Q>,
+    {
+        self.classes.union(x, y)
+    }
+
+    /// The histogram of the class of x, or None if x is missing.
+    pub fn histogram<Q: Hash + Eq + ?Sized>(&self, x: &Q) -> Option<&Histogram<K>>
+    where
+        T: Borrow<Q>,
+    {
+        self.classes.get(x)
+    }
+
+    pub fn union_find(&self) -> &UnionFind<T> {
+        self.classes.union_find()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn counts_follow_unions_and_retags() {
+        let mut counts = CategoryCounts::new();
+        for i in 0..6 {
+            assert!(counts.insert(i, i % 3));
+        }
+        assert!(!counts.insert(0, 2));
+        counts.union(&0, &3);
+        counts.union(&1, &4);
+        counts.union(&0, &1);
+        let histogram = counts.histogram(&4).unwrap();
+        assert_eq!(vec![(&0, 2), (&1, 2)], histogram.iter().collect::<Vec<_>>());
+        assert_eq!(0, histogram.count(&2));
+
+        assert_eq!(Some(1), counts.set_category(&4, 2));
+        assert_eq!(Some(0), counts.set_category(&0, 2));
+        assert_eq!(Some(0), counts.set_category(&3, 1));
+        let histogram = counts.histogram(&0).unwrap();
+        assert_eq!(vec![(&1, 2), (&2, 2)], histogram.iter().collect::<Vec<_>>());
+        assert_eq!(4, histogram.total());
+        assert_eq!(Some(&2), counts.category(&0));
+        assert_eq!(None, counts.set_category(&9, 0));
+        assert_eq!(None, counts.histogram(&9));
+        assert_eq!(Some(1), counts.histogram(&5).map(Histogram::total));
+    }
+}
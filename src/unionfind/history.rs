@@ -0,0 +1,182 @@
+use std::{fmt::{Debug, Write}, hash::Hash, rc::Rc};
+use indexmap::IndexMap;
+
+use crate::Backend;
+
+use super::UnionFind;
+
+/// The state of a class at the time it took part in a merge.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ClassState<T> {
+    /// A class that has not been merged since recording started, named by its leader.
+    /// Elements inserted after recording started begin as such singletons.
+    Initial(Rc<T>),
+    /// The class produced by the merge at this index of [MergeHistory::merges].
+    Merge(usize),
+}
+
+/// One effective union: the two prior class states and the leader of the result.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MergeNode<T> {
+    /// The state of the class that kept its leader.
+    pub kept: ClassState<T>,
+    /// The state of the class that was attached under it.
+    pub absorbed: ClassState<T>,
+    pub leader: Rc<T>,
+}
+
+/// The lineage of class merges, a DAG where every merge references the two class states it
+/// combined. Unlike the parent forest it is not affected by path compression or by which
+/// element happened to lead.
+#[derive(Debug, Clone)]
+pub struct MergeHistory<T: Hash + Eq> {
+    merges: Vec<MergeNode<T>>,
+    // The last merge of every class that has one, by current leader.
+    latest: IndexMap<Rc<T>, usize>,
+}
+
+impl<T: Hash + Eq + Debug> MergeHistory<T> {
+    fn new() -> Self {
+        Self {
+            merges: Vec::new(),
+            latest: IndexMap::new(),
+        }
+    }
+
+    /// All merges in the order they happened.
+    pub fn merges(&self) -> &[MergeNode<T>] {
+        &self.merges
+    }
+
+    /// The current state of the class led by `leader`.
+    pub fn state_of(&self, leader: &Rc<T>) -> ClassState<T> {
+        match self.latest.get(leader) {
+            Some(i) => ClassState::Merge(*i),
+            None => ClassState::Initial(leader.clone()),
+        }
+    }
+
+    fn record(&mut self, kept: Rc<T>, absorbed: Rc<T>) {
+        let node = MergeNode {
+            kept: self.state_of(&kept),
+            absorbed: self.state_of(&absorbed),
+            leader: kept.clone(),
+        };
+        self.latest.swap_remove(&absorbed);
+        self.latest.insert(kept, self.merges.len());
+        self.merges.push(node);
+    }
+
+    // Drops the record of the merge at index merge of absorbed under kept, when it is the
+    // last one. A history restarted since has no such record and is left as is.
+    pub(super) fn unrecord(&mut self, merge: usize, kept: &Rc<T>, absorbed: &Rc<T>) {
+        if self.merges.len() != merge + 1 || self.merges[merge].leader != *kept {
+            return;
+        }
+        let node = self.merges.pop().unwrap();
+        self.latest.swap_remove(kept);
+        for (leader, state) in [(kept, node.kept), (absorbed, node.absorbed)] {
+            if let ClassState::Merge(i) = state {
+                self.latest.insert(leader.clone(), i);
+            }
+        }
+    }
+
+    /// Renders the history in Graphviz DOT format, with edges from every merge to the two
+    /// states it combined.
+    pub fn to_dot(&self) -> String {
+        let mut out = String::from("digraph history {\n");
+        let mut initial = IndexMap::new();
+        let mut name = |state: &ClassState<T>, out: &mut String| match state {
+            ClassState::Merge(i) => format!("m{}", i),
+            ClassState::Initial(t) => {
+                let next = initial.len();
+                let i = *initial.entry(t.clone()).or_insert_with(|| {
+                    writeln!(out, "  i{} [label=\"{}\", shape=box];", next, escape(t.as_ref())).unwrap();
+                    next
+                });
+                format!("i{}", i)
+            }
+        };
+        for (i, merge) in self.merges.iter().enumerate() {
+            writeln!(out, "  m{} [label=\"{}\"];", i, escape(merge.leader.as_ref())).unwrap();
+            let kept = name(&merge.kept, &mut out);
+            let absorbed = name(&merge.absorbed, &mut out);
+            writeln!(out, "  m{} -> {};", i, kept).unwrap();
+            writeln!(out, "  m{} -> {} [style=dashed];", i, absorbed).unwrap();
+        }
+        out.push_str("}\n");
+        out
+    }
+}
+
+fn escape<T: Debug>(t: &T) -> String {
+    format!("{:?}", t).replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+impl<T: Hash + Eq + Clone + Debug, B: Backend<Key = T>> UnionFind<T, B> {
+    /// Starts recording the merge history, see [MergeHistory]. Classes that exist at this
+    /// point appear as [ClassState::Initial] states. Does nothing if already recording.
+    pub fn record_history(&mut self) {
+        if self.history.is_none() {
+            self.history = Some(MergeHistory::new());
+        }
+    }
+
+    /// The recorded merge history, if recording was started.
+    pub fn history(&self) -> Option<&MergeHistory<T>> {
+        self.history.as_ref()
+    }
+
+    /// Stops recording and returns the history recorded so far.
+    pub fn take_history(&mut self) -> Option<MergeHistory<T>> {
+        self.history.take()
+    }
+
+    pub(super) fn record_merge(&mut self, kept: usize, absorbed: usize) {
+        if let Some(history) = &mut self.history {
+            let ps = self.parents.get_mut();
+            history.record(Self::handle_in(ps, kept).clone(), Self::copy_in(ps, absorbed));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn history_dag() {
+        let mut uf = UnionFind::new();
+        uf.insert_many(0..5);
+        uf.union(&0, &1);
+        uf.record_history();
+        uf.union(&2, &3);
+        uf.union(&0, &2);
+        uf.union(&1, &3);
+        uf.union(&4, &0);
+
+        let history = uf.history().unwrap();
+        let leader = |i| Rc::new(i);
+        assert_eq!(history.merges(), &[
+            MergeNode {
+                kept: ClassState::Initial(leader(2)),
+                absorbed: ClassState::Initial(leader(3)),
+                leader: leader(2),
+            },
+            MergeNode { kept: ClassState::Initial(leader(0)), absorbed: ClassState::Merge(0), leader: leader(0) },
+            MergeNode { kept: ClassState::Merge(1), absorbed: ClassState::Initial(leader(4)), leader: leader(0) },
+        ]);
+        assert_eq!(ClassState::Merge(2), history.state_of(&leader(0)));
+        assert_eq!(ClassState::Initial(leader(4)), history.state_of(&leader(4)));
+
+        let dot = history.to_dot();
+        assert!(dot.starts_with("digraph history {\n"));
+        assert!(dot.contains("  m1 -> m0 [style=dashed];\n"));
+        assert!(dot.contains("  m2 -> m1;\n"));
+
+        assert!(uf.take_history().is_some());
+        uf.union(&0, &0);
+        assert!(uf.history().is_none());
+    }
+}
@@ -0,0 +1,110 @@
+use std::{borrow::Borrow, fmt::Debug, hash::Hash, num::NonZeroU32};
+
+use crate::Backend;
+
+use super::{validate, UnionFind};
+
+/// A handle to an element of a [UnionFind], returned by [UnionFind::insert], so hot loops
+/// can find and union without hashing the key again.
+///
+/// It is the position of the element in insertion order. [UnionFind::reorder_by_class] and
+/// [UnionFind::remove] move elements and invalidate the handles taken before, and so does a
+/// [UnionFind::rollback] undoing the insert. The handle is a [NonZeroU32] so an
+/// `Option<Id>` takes no more space, and inserting panics past `u32::MAX - 1` elements.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Id(NonZeroU32);
+
+impl Id {
+    pub(super) fn new(index: usize) -> Self {
+        u32::try_from(index + 1).ok().and_then(NonZeroU32::new).map(Id).expect("too many elements for an Id")
+    }
+
+    /// The position of the element in insertion order.
+    pub fn index(self) -> usize {
+        self.0.get() as usize - 1
+    }
+}
+
+impl<T: Hash + Eq + Clone + Debug, B: Backend<Key = T>> UnionFind<T, B> {
+    /// The handle of x, or None if x is missing.
+    pub fn id<Q: Hash + Eq + ?Sized>(&self, x: &Q) -> Option<Id>
+    where
+        T: Borrow<Q>,
+    {
+        self.parents.borrow().get_index_of(x).map(Id::new)
+    }
+
+    /// The handle of the leader of the class of id, or None if id is not a handle of this
+    /// union-find. The forest links elements by position, so no key is hashed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use hash_unionfind::UnionFind;
+    ///
+    /// let mut uf = UnionFind::new();
+    /// let ids: Vec<_> = (0..4).map(|i| uf.insert(format!("element {}", i))).collect();
+    /// for pair in ids.windows(2) {
+    ///     uf.union_ids(pair[0], pair[1]);
+    /// }
+    /// assert_eq!(uf.find_id(ids[3]), uf.find_id(ids[0]));
+    /// assert_eq!(Some(ids[0]), uf.id("element 0"));
+    /// ```
+    pub fn find_id(&self, id: Id) -> Option<Id> {
+        if let Ok(mut ps) = self.parents.try_borrow_mut() {
+            if id.index() >= ps.len() {
+                return None;
+            }
+            return Some(Id::new(Self::find_index_in(&mut ps, id.index())));
+        }
+        let ps = self.parents.try_borrow().unwrap_or_else(|_| validate::reentered());
+        if id.index() >= ps.len() {
+            return None;
+        }
+        Some(Id::new(Self::root_index_in(&ps, id.index())))
+    }
+
+    /// Unions the classes of x and y as [UnionFind::union] does, returning the handle of the
+    /// new leader, or None if one of them is not a handle of this union-find.
+    pub fn union_ids(&mut self, x: Id, y: Id) -> Option<Id> {
+        let (mut x, mut y) = (self.find_id(x)?, self.find_id(y)?);
+        if x == y {
+            return Some(x);
+        }
+        let ps = self.parents.get_mut();
+        if ps[y.index()].rank > ps[x.index()].rank {
+            std::mem::swap(&mut x, &mut y);
+        }
+        self.link(x.index(), y.index());
+        Some(x)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ids_match_keys() {
+        let mut by_key = UnionFind::new();
+        let mut uf = UnionFind::new();
+        let ids: Vec<Id> = (0..32).map(|i| uf.insert(i)).collect();
+        by_key.insert_many(0..32);
+        assert_eq!(ids[5], uf.insert(5));
+        assert_eq!(std::mem::size_of::<Id>(), std::mem::size_of::<Option<Id>>());
+        for i in 0..31 {
+            let (x, y) = (i * 7 % 32, i * 13 % 32);
+            let leader = uf.union_ids(ids[x], ids[y]).unwrap();
+            assert_eq!(by_key.union(&x, &y).map(|l| *l), Some(leader.index()));
+        }
+        for (i, id) in ids.iter().enumerate() {
+            assert_eq!(uf.id(&*by_key.find(&i).unwrap()), uf.find_id(*id));
+        }
+        assert_eq!(None, uf.find_id(Id::new(32)));
+
+        // Reads under a live borrow walk without compressing.
+        let ps = uf.parents.borrow();
+        assert_eq!(uf.find_id(ids[0]), uf.id(&*by_key.find(&0).unwrap()));
+        drop(ps);
+    }
+}
@@ -0,0 +1,87 @@
+use std::{fmt::Debug, hash::Hash, iter::Zip, rc::Rc, vec};
+
+use crate::Backend;
+
+use super::UnionFind;
+
+/// Iterator returned by the [IntoIterator] impl of [UnionFind], yielding every element with
+/// its leader in insertion order.
+#[derive(Debug)]
+pub struct IntoPairs<T> {
+    pairs: Zip<vec::IntoIter<T>, vec::IntoIter<Rc<T>>>,
+}
+
+impl<T> Iterator for IntoPairs<T> {
+    type Item = (T, Rc<T>);
+
+    fn next(&mut self) -> Option<(T, Rc<T>)> {
+        self.pairs.next()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.pairs.size_hint()
+    }
+}
+
+impl<T> ExactSizeIterator for IntoPairs<T> {}
+
+impl<T: Hash + Eq + Clone + Debug, B: Backend<Key = T>> UnionFind<T, B> {
+    /// Every element with its leader, in insertion order.
+    ///
+    /// Takes `&mut self` to compress every path first, as [UnionFind::normalize] does
+    /// without shrinking, and then lend the keys straight out of the map. [UnionFind::freeze]
+    /// gives the same mapping from a shared reference.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::collections::HashMap;
+    /// use hash_unionfind::UnionFind;
+    ///
+    /// let mut uf = UnionFind::from_edge_list([("a", "b"), ("c", "b")]);
+    /// let table: HashMap<_, _> = uf.iter().map(|(t, leader)| (*t, *leader)).collect();
+    /// assert_eq!(HashMap::from([("a", "a"), ("b", "a"), ("c", "a")]), table);
+    ///
+    /// let pairs: Vec<_> = uf.into_iter().map(|(t, leader)| (t, *leader)).collect();
+    /// assert_eq!(vec![("a", "a"), ("b", "a"), ("c", "a")], pairs);
+    /// ```
+    pub fn iter(&mut self) -> impl ExactSizeIterator<Item = (&T, Rc<T>)> {
+        self.flatten();
+        let ps = &*self.parents.get_mut();
+        ps.iter().map(move |(t, node)| (t, Self::handle_in(ps, crate::wide(node.parent)).clone()))
+    }
+}
+
+impl<T: Hash + Eq + Clone + Debug, B: Backend<Key = T>> IntoIterator for UnionFind<T, B> {
+    type Item = (T, Rc<T>);
+    type IntoIter = IntoPairs<T>;
+
+    fn into_iter(mut self) -> IntoPairs<T> {
+        self.flatten();
+        let ps = self.parents.into_inner();
+        let leaders: Vec<Rc<T>> = ps.values()
+            .map(|node| Self::handle_in(&ps, crate::wide(node.parent)).clone())
+            .collect();
+        IntoPairs { pairs: ps.into_parts().0.into_iter().zip(leaders) }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pairs_match_finds() {
+        let mut uf = UnionFind::new();
+        uf.insert_many(0..20);
+        for i in 0..12 {
+            uf.union(&(i * 7 % 20), &(i * 3 % 20));
+        }
+        let expected: Vec<_> = (0..20).map(|i| (i, uf.find(&i).unwrap())).collect();
+        let pairs = uf.iter();
+        assert_eq!(20, pairs.len());
+        assert_eq!(expected, pairs.map(|(t, leader)| (*t, leader)).collect::<Vec<_>>());
+        assert_eq!(uf.num_classes() as f64 / 20.0, 1.0 - uf.stats().average_find_depth);
+        assert_eq!(expected, uf.into_iter().collect::<Vec<_>>());
+    }
+}
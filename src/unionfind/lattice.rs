@@ -0,0 +1,67 @@
+use std::{fmt::Debug, hash::Hash};
+use ascent_base::Lattice;
+
+use crate::Backend;
+
+use super::UnionFind;
+
+/// Partitions form a lattice under the refinement order (see the [PartialOrd] impl), so a
+/// union-find can be a lattice column in Datalog engines such as ascent, where a relation like
+/// `lattice alias(UnionFind<Var>)` accumulates equivalences.
+///
+/// Join merges the partitions over the union of their elements, meet keeps the common
+/// refinement over the shared elements.
+impl<T: Hash + Eq + Clone + Debug, B: Backend<Key = T> + Default> Lattice for UnionFind<T, B> {
+    fn meet_mut(&mut self, other: Self) -> bool {
+        let meet = UnionFind::meet(self, &other);
+        // The meet refines self, so it is only different with fewer elements or more classes.
+        if (meet.size(), meet.num_classes()) == (self.size(), self.num_classes()) {
+            return false;
+        }
+        *self = meet;
+        true
+    }
+
+    fn join_mut(&mut self, other: Self) -> bool {
+        let before = (self.size(), self.num_classes());
+        self.absorb(other);
+        before != (self.size(), self.num_classes())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn from_groups(groups: &[&[u32]]) -> UnionFind<u32> {
+        let mut uf = UnionFind::new();
+        for group in groups {
+            uf.insert_many(group.iter().copied());
+            for t in &group[1..] {
+                uf.union(&group[0], t);
+            }
+        }
+        uf
+    }
+
+    #[test]
+    fn join_and_meet() {
+        let a = from_groups(&[&[0, 1], &[2], &[3, 4]]);
+        let b = from_groups(&[&[1, 2], &[3], &[4], &[5]]);
+
+        let join = a.clone().join(b.clone());
+        assert_eq!(join, from_groups(&[&[0, 1, 2], &[3, 4], &[5]]));
+        assert!(a <= join && b <= join);
+
+        let meet = a.clone().meet(b.clone());
+        assert_eq!(meet, from_groups(&[&[1], &[2], &[3], &[4]]));
+        assert!(meet <= a && meet <= b);
+
+        let mut c = a.clone();
+        assert!(!c.join_mut(meet.clone()));
+        assert!(!c.meet_mut(join.clone()));
+        assert_eq!(a, c);
+        assert!(c.meet_mut(b));
+        assert_eq!(meet, c);
+    }
+}
@@ -0,0 +1,258 @@
+use std::{borrow::Borrow, fmt::Debug, hash::Hash, rc::Rc};
+use indexmap::IndexMap;
+
+use crate::Backend;
+
+use super::{Index, UnionFind};
+
+impl<T: Hash + Eq + Clone + Debug, B: Backend<Key = T>> UnionFind<T, B> {
+    /// Starts keeping an ordered list of members for every class, so [UnionFind::kth_member]
+    /// and [UnionFind::member_page] are as cheap as a find. Existing members are listed in
+    /// insertion order, and a union appends the members of the absorbed class after those
+    /// of the kept one, so pages only change when their class merges. Does nothing if
+    /// already indexing.
+    ///
+    /// The lists hold indices into the map, not keys. Unions cost time proportional to the
+    /// absorbed class while indexing.
+    pub fn index_members(&mut self) {
+        if self.member_index.is_some() {
+            return;
+        }
+        let ps = self.parents.get_mut();
+        let index: IndexMap<Index, Vec<Index>> = (0..ps.len())
+            .filter(|&i| crate::wide(ps[i].parent) == i)
+            .map(|leader| {
+                let mut members = Self::member_indices_in(ps, leader);
+                members.sort();
+                (crate::small(leader), members.into_iter().map(crate::small).collect())
+            })
+            .collect();
+        self.member_index = Some(index);
+    }
+
+    /// Stops indexing members and frees the index.
+    pub fn stop_indexing_members(&mut self) {
+        self.member_index = None;
+    }
+
+    /// The k-th member of the class of x. With [UnionFind::index_members] this is O(1) after
+    /// the find and the order is the index order, otherwise it walks the member cycle in
+    /// O(k) in an unspecified order that is stable between unions. None if x is missing or
+    /// the class has at most k members.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use hash_unionfind::UnionFind;
+    ///
+    /// let mut uf = UnionFind::new();
+    /// uf.insert_many(0..6);
+    /// uf.index_members();
+    /// uf.union(&4, &0);
+    /// uf.union(&4, &2);
+    /// assert_eq!(Some(&2), uf.kth_member(&0, 2));
+    /// assert_eq!(None, uf.kth_member(&0, 3));
+    /// assert_eq!(vec![&0, &2], uf.member_page(&2, 1, 10));
+    /// ```
+    pub fn kth_member<Q: Hash + Eq + ?Sized>(&mut self, x: &Q, k: usize) -> Option<&T>
+    where
+        T: Borrow<Q>,
+    {
+        self.member_page(x, k, 1).pop()
+    }
+
+    /// Up to `len` members of the class of x starting at position `offset`, in the order of
+    /// [UnionFind::kth_member], lent out of the map. Empty if x is missing or the class is
+    /// shorter than `offset`.
+    pub fn member_page<Q: Hash + Eq + ?Sized>(&mut self, x: &Q, offset: usize, len: usize) -> Vec<&T>
+    where
+        T: Borrow<Q>,
+    {
+        let ps = self.parents.get_mut();
+        let Some(x) = ps.get_index_of(x) else {
+            return Vec::new();
+        };
+        let leader = Self::find_index_in(ps, x);
+        let ps = &*ps;
+        let key = |i: usize| ps.get_index(i).unwrap().0;
+        if let Some(index) = &self.member_index {
+            let members = &index[&crate::small(leader)];
+            let start = offset.min(members.len());
+            let page = &members[start..members.len().min(start.saturating_add(len))];
+            return page.iter().map(|&m| key(crate::wide(m))).collect();
+        }
+        let size = crate::wide(ps[leader].size);
+        let mut current = leader;
+        let mut page = Vec::new();
+        for i in 0..size.min(offset.saturating_add(len)) {
+            if i >= offset {
+                page.push(key(current));
+            }
+            current = crate::wide(ps[current].next);
+        }
+        page
+    }
+
+    /// The members of the class of x, in the order of [UnionFind::kth_member]. Empty if x is
+    /// missing.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use hash_unionfind::UnionFind;
+    ///
+    /// let mut uf = UnionFind::new();
+    /// uf.insert_many(["f(a)", "f(b)", "a", "b"]);
+    /// uf.union(&"a", &"b");
+    /// uf.union(&"f(a)", &"f(b)");
+    /// let mut equal: Vec<_> = uf.members(&"f(b)").copied().collect();
+    /// equal.sort();
+    /// assert_eq!(vec!["f(a)", "f(b)"], equal);
+    /// ```
+    pub fn members<Q: Hash + Eq + ?Sized>(&mut self, x: &Q) -> impl Iterator<Item = &T>
+    where
+        T: Borrow<Q>,
+    {
+        self.member_page(x, 0, usize::MAX).into_iter()
+    }
+
+    /// Every class with its leader and members, in a deterministic order that only depends
+    /// on the sequence of inserts and unions: classes by the insertion order of their leader,
+    /// and members in insertion order, lent out of the map. Each class is gathered from its
+    /// member cycle only once the iterator reaches it, and nothing is compressed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use hash_unionfind::UnionFind;
+    ///
+    /// let mut uf = UnionFind::new();
+    /// uf.insert_many(["a", "b", "c", "d"]);
+    /// uf.union(&"d", &"b");
+    /// let classes: Vec<_> = uf.classes()
+    ///     .map(|(leader, members)| (*leader, members.into_iter().copied().collect::<Vec<_>>()))
+    ///     .collect();
+    /// assert_eq!(vec![("a", vec!["a"]), ("c", vec!["c"]), ("d", vec!["b", "d"])], classes);
+    /// ```
+    pub fn classes(&mut self) -> impl Iterator<Item = (Rc<T>, Vec<&T>)> {
+        let ps = &*self.parents.get_mut();
+        (0..ps.len()).filter(move |&i| crate::wide(ps[i].parent) == i).map(move |leader| {
+            let mut members = Self::member_indices_in(ps, leader);
+            members.sort_unstable();
+            let members = members.into_iter().map(|m| ps.get_index(m).unwrap().0).collect();
+            (Self::handle_in(ps, leader).clone(), members)
+        })
+    }
+
+    /// Every class by leader with its members lent out of the map, in the order of
+    /// [UnionFind::into_groups]. Takes `&mut self` to point every element at its leader
+    /// first, as [UnionFind::iter] does, so the classes are gathered in one pass.
+    pub fn groups(&mut self) -> IndexMap<Rc<T>, Vec<&T>> {
+        self.flatten();
+        let ps = &*self.parents.get_mut();
+        let mut by_leader: IndexMap<usize, Vec<&T>> = IndexMap::with_capacity(self.classes);
+        for (t, node) in ps.iter() {
+            by_leader.entry(crate::wide(node.parent)).or_default().push(t);
+        }
+        by_leader.into_iter().map(|(leader, members)| (Self::handle_in(ps, leader).clone(), members)).collect()
+    }
+
+    /// Consumes the union-find into its classes by leader, classes by their earliest inserted
+    /// member and members in insertion order. The keys are moved out, not cloned.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use hash_unionfind::uf;
+    ///
+    /// let groups = uf! { {"a", "b"}, {"c"} }.into_groups();
+    /// assert_eq!(Some(&vec!["a", "b"]), groups.get(&"a"));
+    /// assert_eq!(2, groups.len());
+    /// ```
+    pub fn into_groups(self) -> IndexMap<Rc<T>, Vec<T>> {
+        let mut groups: IndexMap<Rc<T>, Vec<T>> = IndexMap::with_capacity(self.classes);
+        for (t, leader) in self {
+            groups.entry(leader).or_default().push(t);
+        }
+        groups
+    }
+
+    pub(super) fn record_members(&mut self, kept: usize, absorbed: usize) {
+        if let Some(index) = &mut self.member_index {
+            let moved = index.swap_remove(&crate::small(absorbed)).unwrap();
+            index[&crate::small(kept)].extend(moved);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn values(members: Vec<&u32>) -> Vec<u32> {
+        members.into_iter().copied().collect()
+    }
+
+    #[test]
+    fn indexed_pages() {
+        let mut uf = UnionFind::new();
+        uf.insert_many(0..8);
+        uf.union(&5, &1);
+        uf.union(&3, &6);
+        uf.index_members();
+        assert_eq!(vec![1, 5], values(uf.member_page(&5, 0, 5)));
+
+        uf.insert(8);
+        uf.union(&3, &1);
+        uf.union(&8, &3);
+        // 3 keeps its members first, then come the absorbed classes in merge order.
+        assert_eq!(vec![3, 6, 1, 5, 8], values(uf.member_page(&8, 0, usize::MAX)));
+        assert_eq!(vec![1, 5], values(uf.member_page(&6, 2, 2)));
+        assert_eq!(vec![8], values(uf.member_page(&6, 4, 2)));
+        assert!(uf.member_page(&6, 9, 2).is_empty());
+        assert!(uf.member_page(&9, 0, 2).is_empty());
+        assert_eq!(Some(&5), uf.kth_member(&1, 3));
+        assert_eq!(vec![3, 6, 1, 5, 8], values(uf.members(&5).collect()));
+        assert_eq!(0, uf.members(&9).count());
+    }
+
+    #[test]
+    fn unindexed_pages_cover_the_class() {
+        let mut uf = UnionFind::new();
+        uf.insert_many(0..10);
+        for i in (0..10).step_by(2) {
+            uf.union(&0, &i);
+        }
+        let mut paged: Vec<u32> = (0..3).flat_map(|page| values(uf.member_page(&4, page * 2, 2))).collect();
+        assert_eq!(Some(&paged[3]), uf.kth_member(&2, 3));
+        assert_eq!(None, uf.kth_member(&2, 5));
+        paged.sort();
+        assert_eq!(vec![0, 2, 4, 6, 8], paged);
+
+        uf.index_members();
+        assert_eq!(vec![0, 2, 4, 6, 8], values(uf.member_page(&2, 0, 10)));
+        uf.stop_indexing_members();
+        assert_eq!(5, uf.member_page(&2, 0, 10).len());
+    }
+
+    #[test]
+    fn classes_in_insertion_order() {
+        let mut uf = UnionFind::new();
+        uf.insert_many([4, 7, 1, 0, 9, 3]);
+        for (x, y) in [(9, 7), (0, 1), (9, 3), (0, 9)] {
+            uf.union(&x, &y);
+        }
+        let classes: Vec<_> = uf.classes().map(|(leader, members)| (*leader, values(members))).collect();
+        assert_eq!(vec![(4, vec![4]), (0, vec![7, 1, 0, 9, 3])], classes);
+
+        // Compression and finds do not change the order.
+        uf.compress_all();
+        assert_eq!(classes, uf.classes().map(|(leader, members)| (*leader, values(members))).collect::<Vec<_>>());
+        assert_eq!(0, UnionFind::<u32>::new().classes().count());
+
+        let groups: Vec<_> = uf.groups().into_iter().map(|(leader, members)| (*leader, values(members))).collect();
+        let owned: Vec<_> = uf.into_groups().into_iter().collect();
+        assert_eq!(classes, groups);
+        assert_eq!(classes, owned.into_iter().map(|(leader, members)| (*leader, members)).collect::<Vec<_>>());
+    }
+}
@@ -0,0 +1,134 @@
+use std::{borrow::Borrow, fmt::Debug, hash::Hash, rc::Rc};
+
+use super::{ByMin, Id, UnionFind};
+
+/// A [UnionFind] whose classes are always led by their minimum element, whatever the order
+/// of insertions and unions, so leaders can serve as canonical names.
+///
+/// Unions link by [ByMin] instead of rank, so the trees are only kept shallow by path
+/// compression and finds cost amortized O(log n) instead of nearly constant.
+///
+/// # Examples
+///
+/// ```
+/// use hash_unionfind::MinLeaderUnionFind;
+///
+/// let mut uf = MinLeaderUnionFind::new();
+/// for t in ["x2", "x0", "x1"] {
+///     uf.insert(t);
+/// }
+/// uf.union(&"x2", &"x1");
+/// assert_eq!(Some("x0".into()), uf.union(&"x1", &"x0"));
+/// assert_eq!(Some("x0".into()), uf.find(&"x2"));
+/// ```
+#[derive(Debug, Clone)]
+pub struct MinLeaderUnionFind<T: Hash + Eq + Clone + Debug + Ord> {
+    uf: UnionFind<T>,
+}
+
+impl<T: Hash + Eq + Clone + Debug + Ord> Default for MinLeaderUnionFind<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Hash + Eq + Clone + Debug + Ord> MinLeaderUnionFind<T> {
+    pub fn new() -> Self {
+        Self { uf: UnionFind::new() }
+    }
+
+    /// The number of elements.
+    pub fn size(&self) -> usize {
+        self.uf.size()
+    }
+
+    pub fn num_classes(&self) -> usize {
+        self.uf.num_classes()
+    }
+
+    /// Create a new set from the element t, see [UnionFind::insert].
+    pub fn insert(&mut self, t: T) -> Id {
+        self.uf.insert(t)
+    }
+
+    /// The minimum element of the class of t, or None if t is missing.
+    pub fn find<Q: Hash + Eq + ?Sized>(&self, t: &Q) -> Option<Rc<T>>
+    where
+        T: Borrow<Q>,
+    {
+        self.uf.find(t)
+    }
+
+    /// Unions the classes of x and y, returning the minimum of the merged class, or None if
+    /// one of them is missing.
+    pub fn union<Q: Hash + Eq + ?Sized>(&mut self, x: &Q, y: &Q) -> Option<Rc<T>>
+    where
+        T: Borrow<Q>,
+    {
+        self.uf.union_with(x, y, ByMin)
+    }
+
+    pub fn union_find(&self) -> &UnionFind<T> {
+        &self.uf
+    }
+
+    pub fn into_union_find(self) -> UnionFind<T> {
+        self.uf
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn leaders_are_minimums() {
+        let mut uf = MinLeaderUnionFind::new();
+        let mut reversed = MinLeaderUnionFind::new();
+        for i in 0..32 {
+            uf.insert(i);
+            reversed.insert(31 - i);
+        }
+        let pairs: Vec<_> = (0..24).map(|i| (i * 7 % 32, i * 11 % 32)).collect();
+        for (x, y) in &pairs {
+            uf.union(x, y);
+        }
+        for (x, y) in pairs.iter().rev() {
+            reversed.union(y, x);
+        }
+        for (leader, members) in uf.union_find().clone().classes() {
+            assert_eq!(members.into_iter().min(), Some(&*leader));
+        }
+        for i in 0..32 {
+            assert_eq!(uf.find(&i), reversed.find(&i));
+        }
+        assert_eq!(uf.num_classes(), reversed.num_classes());
+    }
+
+    #[test]
+    fn unions_copy_only_the_kept_leader() {
+        // Every clone of a key bumps the counter.
+        #[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+        struct Counted(u32);
+
+        thread_local!(static CLONES: std::cell::Cell<usize> = const { std::cell::Cell::new(0) });
+
+        impl Clone for Counted {
+            fn clone(&self) -> Self {
+                CLONES.with(|clones| clones.set(clones.get() + 1));
+                Counted(self.0)
+            }
+        }
+
+        let mut uf = MinLeaderUnionFind::new();
+        for i in 0..4 {
+            uf.insert(Counted(i));
+        }
+        let before = CLONES.with(|clones| clones.get());
+        uf.union(&Counted(3), &Counted(2));
+        uf.union(&Counted(2), &Counted(1));
+        assert_eq!(Some(Rc::new(Counted(1))), uf.find(&Counted(3)));
+        // One copy of the minimum per union, handed out as the new leader.
+        assert_eq!(2, CLONES.with(|clones| clones.get()) - before);
+    }
+}
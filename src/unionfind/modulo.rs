@@ -0,0 +1,290 @@
+use std::{borrow::Borrow, fmt::Debug, hash::{Hash, Hasher}, rc::Rc};
+
+use crate::Backend;
+
+use super::UnionFind;
+
+/// Structural equality of values embedding keys of a [UnionFind], where keys are equal when
+/// they are in the same class.
+///
+/// Implement it for the types holding keys, comparing keys with [UnionFind::same_class] and
+/// recursing into other fields. Standard containers forward to their contents. The
+/// comparison is generic over the [Backend], so one impl serves every layout of the keys.
+///
+/// # Examples
+///
+/// ```
+/// use hash_unionfind::{Backend, EqModulo, UnionFind};
+///
+/// enum Expr {
+///     Var(&'static str),
+///     Call(&'static str, Vec<Expr>),
+/// }
+///
+/// impl EqModulo<&'static str> for Expr {
+///     fn eq_modulo<B: Backend<Key = &'static str>>(&self, other: &Self, uf: &UnionFind<&'static str, B>) -> bool {
+///         match (self, other) {
+///             (Expr::Var(a), Expr::Var(b)) => uf.same_class(a, b),
+///             (Expr::Call(f, args), Expr::Call(g, other_args)) => f == g && args.eq_modulo(other_args, uf),
+///             _ => false,
+///         }
+///     }
+/// }
+///
+/// let mut uf = UnionFind::new();
+/// uf.insert_many(["x", "y", "z"]);
+/// uf.union(&"x", &"y");
+/// let f = |v| Expr::Call("f", vec![Expr::Var(v), Expr::Var("z")]);
+/// assert!(uf.eq_modulo(&f("x"), &f("y")));
+/// assert!(!uf.eq_modulo(&f("x"), &f("z")));
+/// ```
+pub trait EqModulo<T: Hash + Eq + Clone + Debug> {
+    fn eq_modulo<B: Backend<Key = T>>(&self, other: &Self, uf: &UnionFind<T, B>) -> bool;
+}
+
+/// Hashing of values embedding keys of a [UnionFind] consistent with [EqModulo]: keys hash as
+/// the leader of their class, so values equal modulo the partition hash the same.
+///
+/// Implement it for the types holding keys, hashing keys with [UnionFind::hash_key] and other
+/// fields as usual. Hashes are only valid until the next effective union, so tables keyed
+/// this way have to be rehashed after merging, as in congruence closure.
+///
+/// # Examples
+///
+/// ```
+/// use std::{collections::hash_map::DefaultHasher, hash::{Hash, Hasher}};
+/// use hash_unionfind::{Backend, HashModulo, UnionFind};
+///
+/// struct Call(&'static str, Vec<&'static str>);
+///
+/// impl HashModulo<&'static str> for Call {
+///     fn hash_modulo<B, H>(&self, uf: &UnionFind<&'static str, B>, state: &mut H)
+///     where
+///         B: Backend<Key = &'static str>,
+///         H: Hasher,
+///     {
+///         self.0.hash(state);
+///         self.1.len().hash(state);
+///         for arg in &self.1 {
+///             uf.hash_key(arg, state);
+///         }
+///     }
+/// }
+///
+/// let mut uf = UnionFind::new();
+/// uf.insert_many(["a", "b"]);
+/// uf.union(&"a", &"b");
+/// let hash = |call: &Call| {
+///     let mut hasher = DefaultHasher::new();
+///     uf.hash_modulo(call, &mut hasher);
+///     hasher.finish()
+/// };
+/// assert_eq!(hash(&Call("f", vec!["a", "b"])), hash(&Call("f", vec!["b", "a"])));
+/// ```
+pub trait HashModulo<T: Hash + Eq + Clone + Debug> {
+    fn hash_modulo<B: Backend<Key = T>, H: Hasher>(&self, uf: &UnionFind<T, B>, state: &mut H);
+}
+
+impl<T: Hash + Eq + Clone + Debug, B: Backend<Key = T>> UnionFind<T, B> {
+    /// Hashes the leader of the class of key, or key itself if it was never inserted.
+    pub fn hash_key<Q: Hash + Eq + ?Sized, H: Hasher>(&self, key: &Q, state: &mut H)
+    where
+        T: Borrow<Q>,
+    {
+        match self.find(key) {
+            Some(leader) => T::borrow(&leader).hash(state),
+            None => key.hash(state),
+        }
+    }
+
+    /// Hashes value with [HashModulo].
+    pub fn hash_modulo<V: HashModulo<T> + ?Sized, H: Hasher>(&self, value: &V, state: &mut H) {
+        value.hash_modulo(self, state)
+    }
+
+    /// Whether x and y are in the same class, where keys that were never inserted are
+    /// singleton classes, so only equal to themselves.
+    pub fn same_class<Q: Hash + Eq + ?Sized>(&self, x: &Q, y: &Q) -> bool
+    where
+        T: Borrow<Q>,
+    {
+        x == y || self.same_set(x, y) == Some(true)
+    }
+
+    /// Compares a and b with [EqModulo].
+    pub fn eq_modulo<V: EqModulo<T> + ?Sized>(&self, a: &V, b: &V) -> bool {
+        a.eq_modulo(b, self)
+    }
+}
+
+impl<T: Hash + Eq + Clone + Debug, V: EqModulo<T>> EqModulo<T> for [V] {
+    fn eq_modulo<B: Backend<Key = T>>(&self, other: &Self, uf: &UnionFind<T, B>) -> bool {
+        self.len() == other.len() && self.iter().zip(other).all(|(a, b)| a.eq_modulo(b, uf))
+    }
+}
+
+impl<T: Hash + Eq + Clone + Debug, V: EqModulo<T>> EqModulo<T> for Vec<V> {
+    fn eq_modulo<B: Backend<Key = T>>(&self, other: &Self, uf: &UnionFind<T, B>) -> bool {
+        self.as_slice().eq_modulo(other, uf)
+    }
+}
+
+impl<T: Hash + Eq + Clone + Debug, V: EqModulo<T>> EqModulo<T> for Option<V> {
+    fn eq_modulo<B: Backend<Key = T>>(&self, other: &Self, uf: &UnionFind<T, B>) -> bool {
+        match (self, other) {
+            (Some(a), Some(b)) => a.eq_modulo(b, uf),
+            (a, b) => a.is_none() && b.is_none(),
+        }
+    }
+}
+
+impl<T: Hash + Eq + Clone + Debug, V: EqModulo<T> + ?Sized> EqModulo<T> for Box<V> {
+    fn eq_modulo<B: Backend<Key = T>>(&self, other: &Self, uf: &UnionFind<T, B>) -> bool {
+        self.as_ref().eq_modulo(other, uf)
+    }
+}
+
+impl<T: Hash + Eq + Clone + Debug, V: EqModulo<T> + ?Sized> EqModulo<T> for Rc<V> {
+    fn eq_modulo<B: Backend<Key = T>>(&self, other: &Self, uf: &UnionFind<T, B>) -> bool {
+        self.as_ref().eq_modulo(other, uf)
+    }
+}
+
+impl<T: Hash + Eq + Clone + Debug, X: EqModulo<T>, Y: EqModulo<T>> EqModulo<T> for (X, Y) {
+    fn eq_modulo<B: Backend<Key = T>>(&self, other: &Self, uf: &UnionFind<T, B>) -> bool {
+        self.0.eq_modulo(&other.0, uf) && self.1.eq_modulo(&other.1, uf)
+    }
+}
+
+impl<T: Hash + Eq + Clone + Debug, X: EqModulo<T>, Y: EqModulo<T>, Z: EqModulo<T>> EqModulo<T> for (X, Y, Z) {
+    fn eq_modulo<B: Backend<Key = T>>(&self, other: &Self, uf: &UnionFind<T, B>) -> bool {
+        self.0.eq_modulo(&other.0, uf) && self.1.eq_modulo(&other.1, uf) && self.2.eq_modulo(&other.2, uf)
+    }
+}
+
+impl<T: Hash + Eq + Clone + Debug, V: HashModulo<T>> HashModulo<T> for [V] {
+    fn hash_modulo<B: Backend<Key = T>, H: Hasher>(&self, uf: &UnionFind<T, B>, state: &mut H) {
+        // The length keeps nested sequences from hashing the same when regrouped.
+        self.len().hash(state);
+        for v in self {
+            v.hash_modulo(uf, state);
+        }
+    }
+}
+
+impl<T: Hash + Eq + Clone + Debug, V: HashModulo<T>> HashModulo<T> for Vec<V> {
+    fn hash_modulo<B: Backend<Key = T>, H: Hasher>(&self, uf: &UnionFind<T, B>, state: &mut H) {
+        self.as_slice().hash_modulo(uf, state)
+    }
+}
+
+impl<T: Hash + Eq + Clone + Debug, V: HashModulo<T>> HashModulo<T> for Option<V> {
+    fn hash_modulo<B: Backend<Key = T>, H: Hasher>(&self, uf: &UnionFind<T, B>, state: &mut H) {
+        self.is_some().hash(state);
+        if let Some(v) = self {
+            v.hash_modulo(uf, state);
+        }
+    }
+}
+
+impl<T: Hash + Eq + Clone + Debug, V: HashModulo<T> + ?Sized> HashModulo<T> for Box<V> {
+    fn hash_modulo<B: Backend<Key = T>, H: Hasher>(&self, uf: &UnionFind<T, B>, state: &mut H) {
+        self.as_ref().hash_modulo(uf, state)
+    }
+}
+
+impl<T: Hash + Eq + Clone + Debug, V: HashModulo<T> + ?Sized> HashModulo<T> for Rc<V> {
+    fn hash_modulo<B: Backend<Key = T>, H: Hasher>(&self, uf: &UnionFind<T, B>, state: &mut H) {
+        self.as_ref().hash_modulo(uf, state)
+    }
+}
+
+impl<T: Hash + Eq + Clone + Debug, X: HashModulo<T>, Y: HashModulo<T>> HashModulo<T> for (X, Y) {
+    fn hash_modulo<B: Backend<Key = T>, H: Hasher>(&self, uf: &UnionFind<T, B>, state: &mut H) {
+        self.0.hash_modulo(uf, state);
+        self.1.hash_modulo(uf, state);
+    }
+}
+
+impl<T: Hash + Eq + Clone + Debug, X: HashModulo<T>, Y: HashModulo<T>, Z: HashModulo<T>> HashModulo<T> for (X, Y, Z) {
+    fn hash_modulo<B: Backend<Key = T>, H: Hasher>(&self, uf: &UnionFind<T, B>, state: &mut H) {
+        self.0.hash_modulo(uf, state);
+        self.1.hash_modulo(uf, state);
+        self.2.hash_modulo(uf, state);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // A key wrapper, the leaves of the compared structures.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+    struct Var(u8);
+
+    impl EqModulo<Var> for Var {
+        fn eq_modulo<B: Backend<Key = Var>>(&self, other: &Self, uf: &UnionFind<Var, B>) -> bool {
+            uf.same_class(self, other)
+        }
+    }
+
+    impl HashModulo<Var> for Var {
+        fn hash_modulo<B: Backend<Key = Var>, H: Hasher>(&self, uf: &UnionFind<Var, B>, state: &mut H) {
+            uf.hash_key(self, state)
+        }
+    }
+
+    fn hash<V: HashModulo<Var>>(uf: &UnionFind<Var>, v: &V) -> u64 {
+        let mut hasher = std::collections::hash_map::DefaultHasher::new();
+        uf.hash_modulo(v, &mut hasher);
+        hasher.finish()
+    }
+
+    #[test]
+    fn containers_forward() {
+        let mut uf = UnionFind::new();
+        uf.insert_many((0..4).map(Var));
+        uf.union(&Var(0), &Var(1));
+
+        let a = vec![(Var(0), Some(Box::new(Var(2)))), (Var(3), None)];
+        let b = vec![(Var(1), Some(Box::new(Var(2)))), (Var(3), None)];
+        assert!(uf.eq_modulo(&a, &b));
+        let c = vec![(Var(1), Some(Box::new(Var(3)))), (Var(3), None)];
+        assert!(!uf.eq_modulo(&a, &c));
+        assert!(!uf.eq_modulo(&a, &a[..1].to_vec()));
+        assert!(uf.eq_modulo(&(Var(9), Var(0), Var(9)), &(Var(9), Var(1), Var(9))));
+        assert!(!uf.same_class(&Var(9), &Var(8)));
+        assert!(!uf.eq_modulo(&Some(Var(0)), &None));
+    }
+
+    #[test]
+    fn hash_agrees_with_eq() {
+        let mut uf = UnionFind::new();
+        uf.insert_many((0..6).map(Var));
+        uf.union(&Var(0), &Var(1));
+        uf.union(&Var(2), &Var(3));
+
+        let values: Vec<Vec<(Var, Option<Var>)>> = vec![
+            vec![(Var(0), Some(Var(2)))],
+            vec![(Var(1), Some(Var(3)))],
+            vec![(Var(1), None)],
+            vec![(Var(4), Some(Var(3)))],
+            vec![(Var(1), Some(Var(3))), (Var(5), None)],
+        ];
+        for a in &values {
+            for b in &values {
+                if uf.eq_modulo(a, b) {
+                    assert_eq!(hash(&uf, a), hash(&uf, b));
+                }
+            }
+        }
+        assert_eq!(hash(&uf, &values[0]), hash(&uf, &values[1]));
+        assert_ne!(hash(&uf, &values[0]), hash(&uf, &values[3]));
+
+        // Merging changes the hash of values mentioning the absorbed class.
+        let before = hash(&uf, &values[3]);
+        uf.union(&Var(0), &Var(4));
+        assert_ne!(before, hash(&uf, &values[3]));
+        assert_eq!(hash(&uf, &values[0]), hash(&uf, &values[3]));
+    }
+}
@@ -0,0 +1,203 @@
+use std::{
+    borrow::Borrow,
+    cell::RefCell,
+    fmt::{self, Debug, Display},
+    hash::Hash,
+    ops::{Add, Sub},
+};
+use indexmap::IndexSet;
+
+use crate::{small, wide, Small};
+
+/// A union-find where the elements of a class have values known relative to each other, a
+/// potential or weighted union-find for systems of difference constraints.
+///
+/// [WeightedUnionFind::union_with] records `val(y) - val(x) = w` for any commutative group
+/// `W`, such as integers, given by [Add], [Sub] and [Default] as zero. Every element keeps
+/// its offset to its parent, and path compression composes the offsets on the way.
+///
+/// # Examples
+///
+/// ```
+/// use hash_unionfind::WeightedUnionFind;
+///
+/// let mut uf = WeightedUnionFind::new();
+/// uf.union_with("a", "b", 3).unwrap();
+/// uf.union_with("c", "b", -2).unwrap();
+/// assert_eq!(Some(5), uf.offset(&"a", &"c"));
+/// assert_eq!(None, uf.offset(&"a", &"d"));
+///
+/// let conflict = uf.union_with("c", "a", 4).unwrap_err();
+/// assert_eq!((-5, 4), (conflict.expected, conflict.given));
+/// ```
+#[derive(Debug, Clone)]
+pub struct WeightedUnionFind<T, W> {
+    keys: IndexSet<T>,
+    // The parent position of every position and val(element) - val(parent). RefCell allows
+    // path compression from offset(&self), like the other union-finds.
+    parents: RefCell<Vec<(Small, W)>>,
+    // Upper bound on the height of the tree, only meaningful on leaders.
+    ranks: Vec<Small>,
+    classes: usize,
+}
+
+/// An offset between two elements that contradicts the offsets recorded before, also used
+/// for the relations of [ParityUnionFind](crate::ParityUnionFind).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OffsetConflict<W> {
+    /// The offset implied by the earlier constraints.
+    pub expected: W,
+    pub given: W,
+}
+
+impl<W: Debug> Display for OffsetConflict<W> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:?} contradicts {:?}, implied by the earlier constraints", self.given, self.expected)
+    }
+}
+
+impl<W: Debug> std::error::Error for OffsetConflict<W> {}
+
+impl<T, W> Default for WeightedUnionFind<T, W> {
+    fn default() -> Self {
+        Self {
+            keys: IndexSet::new(),
+            parents: RefCell::new(Vec::new()),
+            ranks: Vec::new(),
+            classes: 0,
+        }
+    }
+}
+
+impl<T, W> WeightedUnionFind<T, W>
+where
+    T: Hash + Eq,
+    W: Clone + Default + PartialEq + Add<Output = W> + Sub<Output = W>,
+{
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The number of elements.
+    pub fn size(&self) -> usize {
+        self.keys.len()
+    }
+
+    pub fn num_classes(&self) -> usize {
+        self.classes
+    }
+
+    /// Create a new set from the element t, returning whether it was new.
+    pub fn insert(&mut self, t: T) -> bool {
+        self.insert_full(t).1
+    }
+
+    fn insert_full(&mut self, t: T) -> (usize, bool) {
+        let (position, new) = self.keys.insert_full(t);
+        if new {
+            self.parents.get_mut().push((small(position), W::default()));
+            self.ranks.push(0);
+            self.classes += 1;
+        }
+        (position, new)
+    }
+
+    // The leader of position and val(position) - val(leader), compressing the path when the
+    // parents are free and only walking when a read up the stack still holds them.
+    fn find_position(&self, position: usize) -> (usize, W) {
+        let Ok(mut parents) = self.parents.try_borrow_mut() else {
+            let parents = self.parents.try_borrow().unwrap_or_else(|_| super::validate::reentered());
+            let (mut root, mut offset) = (position, W::default());
+            while wide(parents[root].0) != root {
+                offset = offset + parents[root].1.clone();
+                root = wide(parents[root].0);
+            }
+            return (root, offset);
+        };
+        let mut path = vec![];
+        let mut root = position;
+        while wide(parents[root].0) != root {
+            path.push(root);
+            root = wide(parents[root].0);
+        }
+        // From the top down, so every parent already holds its offset to the leader.
+        let mut offset = W::default();
+        for i in path.into_iter().rev() {
+            offset = parents[i].1.clone() + offset;
+            parents[i] = (small(root), offset.clone());
+        }
+        (root, offset)
+    }
+
+    /// Find the leader of the set that t is in.
+    pub fn find<Q: Hash + Eq + ?Sized>(&self, t: &Q) -> Option<&T>
+    where
+        T: Borrow<Q>,
+    {
+        let (leader, _) = self.find_position(self.keys.get_index_of(t)?);
+        self.keys.get_index(leader)
+    }
+
+    /// `val(y) - val(x)`, or None if x and y are not in the same class.
+    pub fn offset<Q: Hash + Eq + ?Sized>(&self, x: &Q, y: &Q) -> Option<W>
+    where
+        T: Borrow<Q>,
+    {
+        let (x_leader, x_offset) = self.find_position(self.keys.get_index_of(x)?);
+        let (y_leader, y_offset) = self.find_position(self.keys.get_index_of(y)?);
+        (x_leader == y_leader).then(|| y_offset - x_offset)
+    }
+
+    /// Records `val(y) - val(x) = w`, inserting x and y if missing. Returns whether two
+    /// classes were merged, or the conflict if x and y were already related by another
+    /// offset, in which case nothing changes.
+    pub fn union_with(&mut self, x: T, y: T, w: W) -> Result<bool, OffsetConflict<W>> {
+        let (x, _) = self.insert_full(x);
+        let (y, _) = self.insert_full(y);
+        let (x_leader, x_offset) = self.find_position(x);
+        let (y_leader, y_offset) = self.find_position(y);
+        if x_leader == y_leader {
+            let expected = y_offset - x_offset;
+            return if expected == w { Ok(false) } else { Err(OffsetConflict { expected, given: w }) };
+        }
+        let parents = self.parents.get_mut();
+        if self.ranks[y_leader] <= self.ranks[x_leader] {
+            // val(y_leader) - val(x_leader) = (val(y) - y_offset) - (val(x) - x_offset).
+            parents[y_leader] = (small(x_leader), w + x_offset - y_offset);
+            if self.ranks[y_leader] == self.ranks[x_leader] {
+                self.ranks[x_leader] += 1;
+            }
+        } else {
+            parents[x_leader] = (small(y_leader), y_offset - x_offset - w);
+        }
+        self.classes -= 1;
+        Ok(true)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn offsets_compose() {
+        // val(i) = 3 * i, given as offsets between scattered pairs.
+        let mut uf = WeightedUnionFind::new();
+        for i in 0..40i64 {
+            let (x, y) = (i * 7 % 41, (i * 7 + 5) % 41);
+            assert_eq!(Ok(true), uf.union_with(x, y, 3 * (y - x)));
+        }
+        assert!(uf.insert(99));
+        assert_eq!((42, 2), (uf.size(), uf.num_classes()));
+        for x in 0..41 {
+            for y in 0..41 {
+                assert_eq!(Some(3 * (y - x)), uf.offset(&x, &y));
+            }
+        }
+        assert_eq!(None, uf.offset(&0, &99));
+        assert_eq!(Ok(false), uf.union_with(40, 2, -114));
+        assert_eq!(Err(OffsetConflict { expected: 3, given: 4 }), uf.union_with(1, 2, 4));
+        assert_eq!(Some(3), uf.offset(&1, &2));
+        assert_eq!(uf.find(&0), uf.find(&40));
+    }
+}
@@ -0,0 +1,288 @@
+use std::{borrow::Borrow, fmt::{self, Debug, Display}, hash::Hash, rc::Rc};
+use indexmap::IndexMap;
+
+use super::UnionFind;
+
+/// Equality and outlives constraints between regions, in the shape of lifetime inference.
+///
+/// Equalities are unions. Outlives edges `a: b` form a preorder between classes, so every
+/// cycle of them forces its regions to be equal, and [OutlivesSolver::solve] merges such cycles
+/// until none remain. A strict edge whose two regions end up equal is a contradiction.
+///
+/// # Examples
+///
+/// ```
+/// use hash_unionfind::OutlivesSolver;
+///
+/// let mut solver = OutlivesSolver::new();
+/// solver.insert_many(["'static", "'a", "'b", "'c"]);
+/// solver.add_outlives("'static", "'a");
+/// solver.add_outlives("'a", "'b");
+/// solver.add_outlives("'b", "'a");
+/// solver.add_strictly_outlives("'b", "'c");
+/// assert!(solver.solve().is_ok());
+///
+/// assert_eq!(Some(true), solver.equiv(&"'a", &"'b"));
+/// assert_eq!(Some(true), solver.outlives(&"'static", &"'c"));
+/// assert_eq!(Some(false), solver.outlives(&"'c", &"'a"));
+///
+/// solver.equate(&"'c", &"'static");
+/// let contradiction = solver.solve().unwrap_err();
+/// assert_eq!(("'b", "'c"), (contradiction.longer, contradiction.shorter));
+/// ```
+#[derive(Debug, Clone)]
+pub struct OutlivesSolver<T: Hash + Eq + Clone + Debug> {
+    uf: UnionFind<T>,
+    // Outlives edges as given, longer region first, with whether they are strict.
+    edges: Vec<(T, T, bool)>,
+}
+
+/// A strict outlives constraint whose regions were forced equal.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Contradiction<T> {
+    pub longer: T,
+    pub shorter: T,
+}
+
+impl<T: Debug> Display for Contradiction<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:?} must strictly outlive {:?}, but they are equal", self.longer, self.shorter)
+    }
+}
+
+impl<T: Debug> std::error::Error for Contradiction<T> {}
+
+impl<T: Hash + Eq + Clone + Debug> Default for OutlivesSolver<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Hash + Eq + Clone + Debug> OutlivesSolver<T> {
+    pub fn new() -> Self {
+        Self {
+            uf: UnionFind::new(),
+            edges: Vec::new(),
+        }
+    }
+
+    pub fn insert(&mut self, t: T) {
+        self.uf.insert(t);
+    }
+
+    /// Inserts every region, see [UnionFind::insert_many].
+    pub fn insert_many<I: IntoIterator<Item = T>>(&mut self, iter: I) -> usize {
+        self.uf.insert_many(iter)
+    }
+
+    /// Constrains a and b to be equal. Returns the leader of the merged class, or None if one
+    /// of them is missing.
+    pub fn equate<Q: Hash + Eq + ?Sized>(&mut self, a: &Q, b: &Q) -> Option<Rc<T>>
+    where
+        T: Borrow<Q>,
+    {
+        self.uf.union(a, b)
+    }
+
+    /// Constrains `longer` to outlive `shorter`, inserting both if missing.
+    pub fn add_outlives(&mut self, longer: T, shorter: T) {
+        self.add_edge(longer, shorter, false);
+    }
+
+    /// Constrains `longer` to outlive `shorter` without being equal to it, inserting both
+    /// if missing.
+    pub fn add_strictly_outlives(&mut self, longer: T, shorter: T) {
+        self.add_edge(longer, shorter, true);
+    }
+
+    fn add_edge(&mut self, longer: T, shorter: T, strict: bool) {
+        self.uf.insert(longer.clone());
+        self.uf.insert(shorter.clone());
+        self.edges.push((longer, shorter, strict));
+    }
+
+    // The outlives graph between current classes, leaders numbered in first appearance order.
+    fn class_graph(&self) -> (IndexMap<Rc<T>, usize>, Vec<Vec<usize>>) {
+        let mut ids = IndexMap::new();
+        let mut adjacency: Vec<Vec<usize>> = Vec::new();
+        let mut id = |t: &T, adjacency: &mut Vec<Vec<usize>>| {
+            let leader = self.uf.find(t).unwrap();
+            let next = ids.len();
+            let id = *ids.entry(leader).or_insert(next);
+            if id == adjacency.len() {
+                adjacency.push(Vec::new());
+            }
+            id
+        };
+        for (longer, shorter, _) in &self.edges {
+            let from = id(longer, &mut adjacency);
+            let to = id(shorter, &mut adjacency);
+            adjacency[from].push(to);
+        }
+        (ids, adjacency)
+    }
+
+    /// Merges every cycle of outlives edges into one class, then checks the strict edges.
+    /// Returns the first strict edge between equal regions as a contradiction. Solving again
+    /// after adding constraints continues from the current classes.
+    pub fn solve(&mut self) -> Result<(), Contradiction<T>> {
+        let (ids, adjacency) = self.class_graph();
+        let components = strongly_connected(&adjacency);
+        let mut representative: IndexMap<usize, &Rc<T>> = IndexMap::new();
+        for (leader, component) in ids.keys().zip(components) {
+            let first = *representative.entry(component).or_insert(leader);
+            self.uf.union(first, leader);
+        }
+        for (longer, shorter, strict) in &self.edges {
+            if *strict && self.uf.find(longer) == self.uf.find(shorter) {
+                return Err(Contradiction {
+                    longer: longer.clone(),
+                    shorter: shorter.clone(),
+                });
+            }
+        }
+        Ok(())
+    }
+
+    /// Whether a and b are in the same class, None if one of them is missing.
+    pub fn equiv<Q: Hash + Eq + ?Sized>(&self, a: &Q, b: &Q) -> Option<bool>
+    where
+        T: Borrow<Q>,
+    {
+        Some(self.uf.find(a)? == self.uf.find(b)?)
+    }
+
+    /// Whether the constraints so far imply that `longer` outlives `shorter`, which holds for
+    /// equal regions. None if one of them is missing.
+    pub fn outlives<Q: Hash + Eq + ?Sized>(&self, longer: &Q, shorter: &Q) -> Option<bool>
+    where
+        T: Borrow<Q>,
+    {
+        let from = self.uf.find(longer)?;
+        let to = self.uf.find(shorter)?;
+        if from == to {
+            return Some(true);
+        }
+        let (ids, adjacency) = self.class_graph();
+        let (Some(&from), Some(&to)) = (ids.get(&from), ids.get(&to)) else {
+            return Some(false);
+        };
+        let mut seen = vec![false; adjacency.len()];
+        let mut stack = vec![from];
+        while let Some(node) = stack.pop() {
+            if node == to {
+                return Some(true);
+            }
+            for &next in &adjacency[node] {
+                if !std::mem::replace(&mut seen[next], true) {
+                    stack.push(next);
+                }
+            }
+        }
+        Some(false)
+    }
+
+    pub fn union_find(&self) -> &UnionFind<T> {
+        &self.uf
+    }
+}
+
+// Kosaraju's algorithm with explicit stacks, returns a component id for every node.
+fn strongly_connected(adjacency: &[Vec<usize>]) -> Vec<usize> {
+    let n = adjacency.len();
+    let mut finished = Vec::with_capacity(n);
+    let mut visited = vec![false; n];
+    for root in 0..n {
+        if std::mem::replace(&mut visited[root], true) {
+            continue;
+        }
+        let mut stack = vec![(root, 0)];
+        while let Some((node, edge)) = stack.last_mut() {
+            match adjacency[*node].get(*edge) {
+                Some(&next) => {
+                    *edge += 1;
+                    if !std::mem::replace(&mut visited[next], true) {
+                        stack.push((next, 0));
+                    }
+                }
+                None => {
+                    finished.push(*node);
+                    stack.pop();
+                }
+            }
+        }
+    }
+
+    let mut reversed = vec![Vec::new(); n];
+    for (from, targets) in adjacency.iter().enumerate() {
+        for &to in targets {
+            reversed[to].push(from);
+        }
+    }
+    let mut component = vec![usize::MAX; n];
+    for (id, &root) in finished.iter().rev().enumerate() {
+        if component[root] != usize::MAX {
+            continue;
+        }
+        component[root] = id;
+        let mut stack = vec![root];
+        while let Some(node) = stack.pop() {
+            for &next in &reversed[node] {
+                if component[next] == usize::MAX {
+                    component[next] = id;
+                    stack.push(next);
+                }
+            }
+        }
+    }
+    component
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cycles_collapse() {
+        let mut solver = OutlivesSolver::new();
+        solver.insert_many(0..7);
+        // Two cycles, 0 -> 1 -> 2 -> 0 and 3 -> 4 -> 3, joined by 2 -> 3, and 5 on its own.
+        for (a, b) in [(0, 1), (1, 2), (2, 0), (2, 3), (3, 4), (4, 3)] {
+            solver.add_outlives(a, b);
+        }
+        solver.add_strictly_outlives(0, 4);
+        solver.add_outlives(6, 6);
+        assert_eq!(Ok(()), solver.solve());
+        assert_eq!(4, solver.union_find().num_classes());
+        assert_eq!(Some(true), solver.equiv(&0, &2));
+        assert_eq!(Some(true), solver.equiv(&3, &4));
+        assert_eq!(Some(false), solver.equiv(&2, &3));
+        assert_eq!(Some(true), solver.outlives(&1, &4));
+        assert_eq!(Some(false), solver.outlives(&4, &1));
+        assert_eq!(Some(false), solver.outlives(&5, &0));
+        assert_eq!(Some(true), solver.outlives(&5, &5));
+        assert_eq!(None, solver.outlives(&0, &9));
+    }
+
+    #[test]
+    fn strict_cycle_contradicts() {
+        let mut solver = OutlivesSolver::new();
+        solver.add_outlives("a", "b");
+        solver.add_strictly_outlives("b", "c");
+        assert_eq!(Ok(()), solver.solve());
+        // Closing the cycle forces all three equal, but only once solved.
+        solver.add_outlives("c", "a");
+        assert_eq!(Some(false), solver.equiv(&"a", &"c"));
+        let contradiction = solver.solve().unwrap_err();
+        assert_eq!(Contradiction { longer: "b", shorter: "c" }, contradiction);
+        assert_eq!("\"b\" must strictly outlive \"c\", but they are equal", contradiction.to_string());
+    }
+
+    #[test]
+    fn components() {
+        let adjacency = vec![vec![1], vec![2], vec![0, 3], vec![], vec![4]];
+        let c = strongly_connected(&adjacency);
+        assert!(c[0] == c[1] && c[1] == c[2]);
+        assert!(c[3] != c[0] && c[4] != c[0] && c[3] != c[4]);
+    }
+}
@@ -0,0 +1,219 @@
+use std::{
+    collections::hash_map::RandomState,
+    fmt::Debug,
+    hash::{BuildHasher, Hash},
+    thread,
+};
+
+use indexmap::IndexSet;
+
+use crate::{atomic_forest::AtomicForest, Backend, HashBackend};
+
+use super::{forest::Forest, UnionFind};
+
+impl<T: Hash + Eq + Clone + Debug + Send + Sync> UnionFind<T> {
+    /// Builds the connected components of an edge list using up to `threads` threads.
+    ///
+    /// The edges are split into one chunk per thread and the elements into as many shards by
+    /// hash, every thread numbering the elements of its shard. The threads then link the edges
+    /// of their chunks concurrently with compare and swap on one shared forest, the lock-free
+    /// linking of [ConcurrentUnionFind](crate::ConcurrentUnionFind), read back the roots of
+    /// their shards, and the result is laid out directly from those roots. The only serial
+    /// work left is a linear pass hashing the elements into the table of the result.
+    ///
+    /// Elements are ordered by shard, so their order is unspecified. `threads` is clamped to
+    /// at least 1.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use hash_unionfind::UnionFind;
+    ///
+    /// let edges = vec![(1, 2), (3, 4), (2, 3), (5, 6)];
+    /// let uf = UnionFind::from_edges_par(&edges, 2);
+    /// assert_eq!(uf.find(&1), uf.find(&4));
+    /// assert_ne!(uf.find(&1), uf.find(&5));
+    /// ```
+    pub fn from_edges_par(edges: &[(T, T)], threads: usize) -> Self {
+        let chunks: Vec<&[(T, T)]> = edges.chunks(edges.len().div_ceil(threads.max(1)).max(1)).collect();
+        let shards = chunks.len();
+        let state = RandomState::new();
+        let shard_of = |t: &T| (state.hash_one(t) % shards as u64) as usize;
+
+        // Every chunk sorts its endpoints by shard, remembering the shard of each, then every
+        // shard numbers its elements in order of appearance and hands back the number of every
+        // endpoint it was given, so linking needs no second lookup.
+        let sorted: Vec<(Vec<Vec<&T>>, Vec<u32>)> = thread::scope(|s| {
+            let handles: Vec<_> = chunks
+                .iter()
+                .map(|chunk| {
+                    s.spawn(|| {
+                        let mut buckets = vec![Vec::new(); shards];
+                        let mut shard_ids = Vec::with_capacity(2 * chunk.len());
+                        for t in chunk.iter().flat_map(|(x, y)| [x, y]) {
+                            let shard = shard_of(t);
+                            buckets[shard].push(t);
+                            shard_ids.push(shard as u32);
+                        }
+                        (buckets, shard_ids)
+                    })
+                })
+                .collect();
+            handles.into_iter().map(|h| h.join().unwrap()).collect()
+        });
+        let interned: Vec<(IndexSet<&T>, Vec<Vec<usize>>)> = thread::scope(|s| {
+            let sorted = &sorted;
+            let handles: Vec<_> = (0..shards)
+                .map(|shard| {
+                    s.spawn(move || {
+                        let mut set = IndexSet::new();
+                        let numbers = sorted
+                            .iter()
+                            .map(|(buckets, _)| buckets[shard].iter().map(|&t| set.insert_full(t).0).collect())
+                            .collect();
+                        (set, numbers)
+                    })
+                })
+                .collect();
+            handles.into_iter().map(|h| h.join().unwrap()).collect()
+        });
+        // The elements of a shard are numbered after those of the shards before it.
+        let offsets: Vec<usize> =
+            interned.iter().scan(0, |n, (set, _)| Some(std::mem::replace(n, *n + set.len()))).collect();
+        let n = interned.iter().map(|(set, _)| set.len()).sum();
+
+        let forest = AtomicForest::with_len(n);
+        thread::scope(|s| {
+            for (chunk, (_, shard_ids)) in sorted.iter().enumerate() {
+                let (forest, interned, offsets) = (&forest, &interned, &offsets);
+                s.spawn(move || {
+                    // The next unread number of every shard for this chunk.
+                    let mut cursors = vec![0; shards];
+                    let mut index_of = |shard: u32| {
+                        let shard = shard as usize;
+                        cursors[shard] += 1;
+                        offsets[shard] + interned[shard].1[chunk][cursors[shard] - 1]
+                    };
+                    for pair in shard_ids.chunks_exact(2) {
+                        let (x, y) = (index_of(pair[0]), index_of(pair[1]));
+                        forest.union(x, y);
+                    }
+                });
+            }
+        });
+        drop(sorted);
+
+        // Every shard reads back the roots of its elements and clones them for the result.
+        let mut roots = vec![0; n];
+        let keys: Vec<Vec<T>> = thread::scope(|s| {
+            let mut rest = roots.as_mut_slice();
+            let mut handles = Vec::with_capacity(shards);
+            for ((set, _), &offset) in interned.iter().zip(&offsets) {
+                let (mine, tail) = std::mem::take(&mut rest).split_at_mut(set.len());
+                rest = tail;
+                let forest = &forest;
+                handles.push(s.spawn(move || {
+                    for (i, root) in mine.iter_mut().enumerate() {
+                        *root = forest.root(offset + i);
+                    }
+                    set.iter().map(|&t| t.clone()).collect()
+                }));
+            }
+            handles.into_iter().map(|h| h.join().unwrap()).collect()
+        });
+
+        let mut backend = HashBackend::with_capacity_and_hasher(n, RandomState::new());
+        for t in keys.into_iter().flatten() {
+            backend.insert(t);
+        }
+        Self::from_forest(Forest::from_roots(backend, &roots))
+    }
+
+    /// Builds the partition of elements under the union of every pair, linking the pairs in
+    /// parallel on the rayon thread pool through a [ConcurrentUnionFind](crate::ConcurrentUnionFind).
+    /// Pairs with an element missing from elements are skipped. Elements keep their order.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use hash_unionfind::UnionFind;
+    ///
+    /// let pairs: Vec<_> = (0..10_000u32).map(|i| (i, (i * 7 + 3) % 10_000)).collect();
+    /// let uf = UnionFind::from_pairs_par(0..10_000, &pairs);
+    /// assert_eq!(uf.find(&0), uf.find(&3));
+    /// assert_eq!(10_000, uf.size());
+    /// ```
+    #[cfg(feature = "rayon")]
+    pub fn from_pairs_par<I: IntoIterator<Item = T>>(elements: I, pairs: &[(T, T)]) -> Self {
+        use rayon::prelude::*;
+
+        let uf = crate::ConcurrentUnionFind::new();
+        uf.insert_many(elements);
+        pairs.par_iter().for_each(|(x, y)| {
+            uf.union(x, y);
+        });
+        uf.into_union_find()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parallel_matches_sequential() {
+        // A deterministic pseudo random edge list.
+        let mut state: u64 = 7;
+        let mut next = || {
+            state = state.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+            (state >> 33) % 500
+        };
+        let edges: Vec<(u64, u64)> = (0..300).map(|_| (next(), next())).collect();
+
+        let mut sequential = UnionFind::new();
+        for (x, y) in &edges {
+            sequential.insert(*x);
+            sequential.insert(*y);
+            sequential.union(x, y);
+        }
+
+        for threads in [0, 1, 3, 8] {
+            let mut parallel = UnionFind::from_edges_par(&edges, threads);
+            assert_eq!(sequential.size(), parallel.size());
+            assert_eq!(sequential.num_classes(), parallel.num_classes());
+            for (x, y) in &edges {
+                assert_eq!(parallel.find(x), parallel.find(y));
+            }
+            for (x, _) in &edges {
+                for (y, _) in &edges {
+                    assert_eq!(sequential.find(x) == sequential.find(y), parallel.find(x) == parallel.find(y));
+                }
+                // The classes are laid out directly, so check their sizes and member cycles too.
+                assert_eq!(sequential.class_size(x), parallel.class_size(x));
+                assert_eq!(sequential.class_size(x), Some(parallel.members(x).count()));
+            }
+            assert_eq!(sequential.stats().merges, parallel.stats().merges);
+            let x = &edges[0].0;
+            let y = edges.iter().map(|(y, _)| y).find(|y| parallel.find(x) != parallel.find(*y)).unwrap();
+            parallel.union(x, y);
+            let merged = sequential.class_size(x).unwrap() + sequential.class_size(y).unwrap();
+            assert_eq!(merged, parallel.members(y).count());
+        }
+    }
+
+    #[cfg(feature = "rayon")]
+    #[test]
+    fn rayon_matches_threads() {
+        let edges: Vec<(u32, u32)> = (0..5000).map(|i| (i % 3001, (i * 7919) % 3001)).collect();
+        let threads = UnionFind::from_edges_par(&edges, 4);
+        let rayon = UnionFind::from_pairs_par(threads.elements(), &edges);
+        assert_eq!(threads.to_canonical_vec(), rayon.to_canonical_vec());
+        assert_eq!(threads.elements(), rayon.elements());
+    }
+
+    #[test]
+    fn parallel_empty() {
+        let uf: UnionFind<u32> = UnionFind::from_edges_par(&[], 4);
+        assert_eq!(0, uf.size());
+    }
+}
@@ -0,0 +1,139 @@
+use std::{borrow::Borrow, hash::Hash, ops::{Add, Sub}};
+
+use super::{OffsetConflict, WeightedUnionFind};
+
+/// What a [ParityUnionFind] knows about two elements.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Relation {
+    Same,
+    Opposite,
+    /// In different classes, or missing.
+    Unknown,
+}
+
+// Whether an element is opposite to its parent, the group of offsets of parities.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+struct Parity(bool);
+
+impl Add for Parity {
+    type Output = Parity;
+
+    fn add(self, other: Parity) -> Parity {
+        Parity(self.0 != other.0)
+    }
+}
+
+// Every parity is its own inverse, so subtracting is adding.
+impl Sub for Parity {
+    type Output = Parity;
+
+    fn sub(self, other: Parity) -> Parity {
+        Parity(self.0 != other.0)
+    }
+}
+
+impl From<Parity> for Relation {
+    fn from(parity: Parity) -> Self {
+        if parity.0 { Relation::Opposite } else { Relation::Same }
+    }
+}
+
+/// A union-find whose constraints say whether two elements are the same or opposite, as in
+/// 2-coloring or 2-SAT preprocessing. It is a [WeightedUnionFind] over parities, so the
+/// parities are folded through path compression.
+///
+/// # Examples
+///
+/// ```
+/// use hash_unionfind::{ParityUnionFind, Relation};
+///
+/// let mut colors = ParityUnionFind::new();
+/// colors.union_parity("a", "b", false).unwrap();
+/// colors.union_parity("b", "c", false).unwrap();
+/// assert_eq!(Relation::Same, colors.relation(&"a", &"c"));
+/// assert_eq!(Relation::Unknown, colors.relation(&"a", &"d"));
+///
+/// // An odd cycle cannot be 2-colored.
+/// let conflict = colors.union_parity("c", "a", false).unwrap_err();
+/// assert_eq!((Relation::Same, Relation::Opposite), (conflict.expected, conflict.given));
+/// ```
+#[derive(Debug, Clone)]
+pub struct ParityUnionFind<T> {
+    uf: WeightedUnionFind<T, Parity>,
+}
+
+impl<T> Default for ParityUnionFind<T> {
+    fn default() -> Self {
+        Self { uf: WeightedUnionFind::default() }
+    }
+}
+
+impl<T: Hash + Eq> ParityUnionFind<T> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The number of elements.
+    pub fn size(&self) -> usize {
+        self.uf.size()
+    }
+
+    pub fn num_classes(&self) -> usize {
+        self.uf.num_classes()
+    }
+
+    /// Create a new set from the element t, returning whether it was new.
+    pub fn insert(&mut self, t: T) -> bool {
+        self.uf.insert(t)
+    }
+
+    /// Find the leader of the set that t is in.
+    pub fn find<Q: Hash + Eq + ?Sized>(&self, t: &Q) -> Option<&T>
+    where
+        T: Borrow<Q>,
+    {
+        self.uf.find(t)
+    }
+
+    /// Records that x and y are the same when `same`, opposite otherwise, inserting them if
+    /// missing. Returns whether two classes were merged, or the conflict if the opposite
+    /// relation was already known, in which case nothing changes.
+    pub fn union_parity(&mut self, x: T, y: T, same: bool) -> Result<bool, OffsetConflict<Relation>> {
+        self.uf.union_with(x, y, Parity(!same)).map_err(|conflict| OffsetConflict {
+            expected: conflict.expected.into(),
+            given: conflict.given.into(),
+        })
+    }
+
+    pub fn relation<Q: Hash + Eq + ?Sized>(&self, x: &Q, y: &Q) -> Relation
+    where
+        T: Borrow<Q>,
+    {
+        self.uf.offset(x, y).map_or(Relation::Unknown, Relation::from)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bipartite_check() {
+        // Even cycles and a path are 2-colorable, the odd cycle is not.
+        let mut uf = ParityUnionFind::new();
+        for i in 0..8 {
+            assert_eq!(Ok(true), uf.union_parity(i, i + 1, false));
+        }
+        assert_eq!(Ok(false), uf.union_parity(0, 8, true));
+        assert_eq!(Ok(false), uf.union_parity(3, 0, false));
+        assert!(uf.union_parity(2, 0, false).is_err());
+        assert_eq!(Ok(true), uf.union_parity(20, 21, true));
+        for i in 0..9 {
+            let expected = if i % 2 == 0 { Relation::Same } else { Relation::Opposite };
+            assert_eq!(expected, uf.relation(&0, &i));
+        }
+        assert_eq!(Relation::Same, uf.relation(&21, &20));
+        assert_eq!(Relation::Unknown, uf.relation(&0, &20));
+        assert_eq!((11, 2), (uf.size(), uf.num_classes()));
+    }
+}
@@ -0,0 +1,259 @@
+use std::{borrow::Borrow, fmt::Debug, hash::Hash, rc::Rc};
+
+use crate::Backend;
+
+use super::{Rank, Size, UnionFind};
+
+#[derive(Debug, Clone)]
+struct PersistentNode<T> {
+    parent: Rc<T>,
+    // As in UnionFind, only meaningful on leaders.
+    rank: Rank,
+    size: Size,
+}
+
+/// An immutable union-find, where [PersistentUnionFind::insert] and
+/// [PersistentUnionFind::union] return a new version and leave the old one intact.
+///
+/// Versions share structure through an [im::HashMap], so a union copies O(log n) of the map
+/// and cloning a version is O(1), which makes keeping every version around cheap, as in
+/// congruence closure with non-chronological backtracking. There is no path compression,
+/// it would have to copy the path, and finds rely on union by rank for O(log n) depth.
+///
+/// For speculative search over a large [UnionFind], convert it once with
+/// [PersistentUnionFind::from] and [fork](PersistentUnionFind::fork) it per branch: forks
+/// are O(1), and [PersistentUnionFind::union_mut] on a fork only copies the entries it
+/// touches.
+///
+/// # Examples
+///
+/// ```
+/// use hash_unionfind::PersistentUnionFind;
+///
+/// let base = PersistentUnionFind::new().insert("a").insert("b").insert("c");
+/// let left = base.union(&"a", &"b");
+/// let right = base.union(&"b", &"c");
+/// assert_eq!(Some(true), left.equiv(&"a", &"b"));
+/// assert_eq!(Some(false), right.equiv(&"a", &"b"));
+/// assert_eq!(Some(false), base.equiv(&"b", &"c"));
+/// assert_eq!((3, 2, 2), (base.num_classes(), left.num_classes(), right.num_classes()));
+/// ```
+#[derive(Debug, Clone)]
+pub struct PersistentUnionFind<T: Hash + Eq + Clone> {
+    parents: im::HashMap<T, PersistentNode<T>>,
+    classes: usize,
+}
+
+impl<T: Hash + Eq + Clone> Default for PersistentUnionFind<T> {
+    fn default() -> Self {
+        Self { parents: im::HashMap::new(), classes: 0 }
+    }
+}
+
+impl<T: Hash + Eq + Clone> PersistentUnionFind<T> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn size(&self) -> usize {
+        self.parents.len()
+    }
+
+    pub fn num_classes(&self) -> usize {
+        self.classes
+    }
+
+    /// A logically independent copy in O(1), sharing all entries with this version until
+    /// either of them changes. The same as [Clone::clone], named for search code branching
+    /// on the partition.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use hash_unionfind::{PersistentUnionFind, UnionFind};
+    ///
+    /// let uf = UnionFind::from_edge_list((0..1000).map(|i| (i, i + 1)));
+    /// let mut base = PersistentUnionFind::from(&uf);
+    /// base.insert_mut(-1);
+    /// let mut branch = base.fork();
+    /// branch.union_mut(&-1, &0);
+    /// assert_eq!((2, 1), (base.num_classes(), branch.num_classes()));
+    /// assert_eq!(Some(false), base.equiv(&-1, &1000));
+    /// ```
+    pub fn fork(&self) -> Self {
+        self.clone()
+    }
+
+    /// The version with t inserted as a singleton, the same version if t exists.
+    pub fn insert(&self, t: T) -> Self {
+        let mut version = self.clone();
+        version.insert_mut(t);
+        version
+    }
+
+    /// Inserts t as a singleton into this version, copying only the entries on its way if
+    /// they are shared with other versions. Returns whether t was new.
+    pub fn insert_mut(&mut self, t: T) -> bool {
+        if self.parents.contains_key(&t) {
+            return false;
+        }
+        let parent = Rc::new(t.clone());
+        self.parents.insert(t, PersistentNode { parent, rank: 0, size: 1 });
+        self.classes += 1;
+        true
+    }
+
+    fn root<Q: Hash + Eq + ?Sized>(&self, x: &Q) -> Option<(&Rc<T>, &PersistentNode<T>)>
+    where
+        T: Borrow<Q>,
+    {
+        let (mut key, mut node) = self.parents.get_key_value(x)?;
+        while node.parent.as_ref() != key {
+            (key, node) = self.parents.get_key_value::<T>(node.parent.as_ref()).unwrap();
+        }
+        Some((&node.parent, node))
+    }
+
+    /// The leader of the class of x in this version, or None if x is missing.
+    pub fn find<Q: Hash + Eq + ?Sized>(&self, x: &Q) -> Option<Rc<T>>
+    where
+        T: Borrow<Q>,
+    {
+        self.root(x).map(|(leader, _)| leader.clone())
+    }
+
+    /// Whether x and y are in the same class, None if one of them is missing.
+    pub fn equiv<Q: Hash + Eq + ?Sized>(&self, x: &Q, y: &Q) -> Option<bool>
+    where
+        T: Borrow<Q>,
+    {
+        Some(self.root(x)?.0 == self.root(y)?.0)
+    }
+
+    /// The number of elements in the class of x, or None if x is missing.
+    pub fn class_size<Q: Hash + Eq + ?Sized>(&self, x: &Q) -> Option<usize>
+    where
+        T: Borrow<Q>,
+    {
+        self.root(x).map(|(_, node)| crate::wide(node.size))
+    }
+
+    /// The version with the classes of x and y unioned by rank, as [UnionFind::union] does.
+    /// The same version if they are already equivalent or one of them is missing.
+    pub fn union<Q: Hash + Eq + ?Sized>(&self, x: &Q, y: &Q) -> Self
+    where
+        T: Borrow<Q>,
+    {
+        let mut version = self.clone();
+        version.union_mut(x, y);
+        version
+    }
+
+    /// Unions the classes of x and y in this version by rank, copying only the two leader
+    /// entries if they are shared with other versions. Returns the leader of the merged
+    /// class, or None if one of them is missing.
+    pub fn union_mut<Q: Hash + Eq + ?Sized>(&mut self, x: &Q, y: &Q) -> Option<Rc<T>>
+    where
+        T: Borrow<Q>,
+    {
+        let (mut x, mut y) = (self.root(x)?, self.root(y)?);
+        if x.0 == y.0 {
+            return Some(x.0.clone());
+        }
+        if y.1.rank > x.1.rank {
+            std::mem::swap(&mut x, &mut y);
+        }
+        let ((x, x_node), (y, y_node)) = (x, y);
+        let kept = PersistentNode {
+            parent: x.clone(),
+            rank: x_node.rank.max(y_node.rank + 1),
+            size: x_node.size.checked_add(y_node.size).expect("class size overflow"),
+        };
+        let absorbed = PersistentNode { parent: x.clone(), ..y_node.clone() };
+        let (x, y) = (x.clone(), y.clone());
+        self.parents.insert(x.as_ref().clone(), kept);
+        self.parents.insert(y.as_ref().clone(), absorbed);
+        self.classes -= 1;
+        Some(x)
+    }
+}
+
+impl<T: Hash + Eq + Clone + Debug> From<&PersistentUnionFind<T>> for UnionFind<T> {
+    fn from(persistent: &PersistentUnionFind<T>) -> Self {
+        let mut uf = UnionFind::new();
+        uf.insert_many(persistent.parents.keys().cloned());
+        for t in persistent.parents.keys() {
+            uf.union(t, persistent.root(t).unwrap().0.as_ref());
+        }
+        uf
+    }
+}
+
+/// Copies the partition flattened, every element pointing straight at its leader, in O(n).
+impl<T: Hash + Eq + Clone + Debug, B: Backend<Key = T>> From<&UnionFind<T, B>> for PersistentUnionFind<T> {
+    fn from(uf: &UnionFind<T, B>) -> Self {
+        let mut parents = im::HashMap::new();
+        let ps = uf.parents.borrow();
+        for (i, (t, node)) in ps.iter().enumerate() {
+            let leader = UnionFind::root_index_in(&ps, i);
+            let parent = UnionFind::handle_in(&ps, leader).clone();
+            let node = if i == leader {
+                PersistentNode { parent, rank: if node.size > 1 { 1 } else { 0 }, size: node.size }
+            } else {
+                PersistentNode { parent, rank: 0, size: 1 }
+            };
+            parents.insert(t.clone(), node);
+        }
+        Self { parents, classes: uf.num_classes() }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn versions_are_independent() {
+        let mut versions = vec![(0..16).fold(PersistentUnionFind::new(), |uf, i| uf.insert(i))];
+        let mut expected = vec![UnionFind::from(&versions[0])];
+        for i in 0..15 {
+            let (x, y) = (i * 7 % 16, i * 3 % 16);
+            // Branch from an older version every third union.
+            let base = if i % 3 == 2 { versions.len() - 3 } else { versions.len() - 1 };
+            versions.push(versions[base].union(&x, &y));
+            let mut uf = expected[base].clone();
+            uf.union(&x, &y);
+            expected.push(uf);
+        }
+        for (version, uf) in versions.iter().zip(&expected) {
+            assert_eq!(uf.to_canonical_vec(), UnionFind::from(version).to_canonical_vec());
+            assert_eq!(uf.num_classes(), version.num_classes());
+            assert_eq!(uf.class_size(&7), version.class_size(&7));
+        }
+        assert_eq!(None, versions[5].find(&16));
+        assert_eq!(16, versions[5].union(&0, &16).size());
+    }
+
+    #[test]
+    fn forks_of_a_union_find() {
+        let mut uf = UnionFind::new();
+        uf.insert_many(0..12);
+        for i in 0..8 {
+            uf.union(&(i * 5 % 12), &(i * 7 % 12));
+        }
+        let base = PersistentUnionFind::from(&uf);
+        assert_eq!(uf.to_canonical_vec(), UnionFind::from(&base).to_canonical_vec());
+        assert_eq!(uf.class_size(&5), base.class_size(&5));
+
+        // Each fork gets its own new element, attached under 0.
+        let mut forks: Vec<_> = (0..3).map(|_| base.fork()).collect();
+        for (i, fork) in forks.iter_mut().enumerate() {
+            assert!(fork.insert_mut(12 + i) && !fork.insert_mut(12 + i));
+            assert_eq!(fork.find(&0), fork.union_mut(&0, &(12 + i)));
+            assert_eq!(uf.num_classes(), fork.num_classes());
+        }
+        assert_eq!((None, uf.num_classes()), (base.find(&12), base.num_classes()));
+        assert_eq!(Some(true), forks[0].equiv(&12, &0));
+        assert_eq!((None, Some(true)), (forks[1].find(&12), forks[1].equiv(&13, &0)));
+    }
+}
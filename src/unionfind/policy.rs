@@ -0,0 +1,165 @@
+use std::{borrow::Borrow, fmt::Debug, hash::Hash, rc::Rc};
+
+use crate::Backend;
+
+use super::{ClassInfo, UnionFind};
+
+/// Decides which of two classes keeps its leader in [UnionFind::union_with].
+///
+/// Closures `FnMut(&ClassInfo<&T>, &ClassInfo<&T>) -> bool` are policies, returning whether
+/// the first class keeps its leader. Only [ByRank], the policy of [UnionFind::union], bounds
+/// the tree height, the others are only as balanced as the classes they happen to pick.
+pub trait UnionPolicy<T> {
+    /// Whether the class of x keeps its leader, with the class of y attached under it.
+    fn keeps_first(&mut self, x: &ClassInfo<&T>, y: &ClassInfo<&T>) -> bool;
+}
+
+impl<T, F: FnMut(&ClassInfo<&T>, &ClassInfo<&T>) -> bool> UnionPolicy<T> for F {
+    fn keeps_first(&mut self, x: &ClassInfo<&T>, y: &ClassInfo<&T>) -> bool {
+        self(x, y)
+    }
+}
+
+/// The higher ranked class wins, x on ties, as in [UnionFind::union].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ByRank;
+
+impl<T> UnionPolicy<T> for ByRank {
+    fn keeps_first(&mut self, x: &ClassInfo<&T>, y: &ClassInfo<&T>) -> bool {
+        y.rank <= x.rank
+    }
+}
+
+/// The larger class wins, x on ties.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct BySize;
+
+impl<T> UnionPolicy<T> for BySize {
+    fn keeps_first(&mut self, x: &ClassInfo<&T>, y: &ClassInfo<&T>) -> bool {
+        y.size <= x.size
+    }
+}
+
+/// The class of x always wins.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct KeepFirst;
+
+impl<T> UnionPolicy<T> for KeepFirst {
+    fn keeps_first(&mut self, _: &ClassInfo<&T>, _: &ClassInfo<&T>) -> bool {
+        true
+    }
+}
+
+/// The class with the smaller leader wins, so a class led by its minimum keeps it. See
+/// [MinLeaderUnionFind](crate::MinLeaderUnionFind) to have every class led by its minimum.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ByMin;
+
+impl<T: Ord> UnionPolicy<T> for ByMin {
+    fn keeps_first(&mut self, x: &ClassInfo<&T>, y: &ClassInfo<&T>) -> bool {
+        x.leader <= y.leader
+    }
+}
+
+impl<T: Hash + Eq + Clone + Debug, B: Backend<Key = T>> UnionFind<T, B> {
+    /// Unions the classes of x and y, letting `policy` choose the leader of the merged class.
+    /// If one of the items is missing returns None.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use hash_unionfind::{ClassInfo, KeepFirst, UnionFind};
+    ///
+    /// let mut uf = UnionFind::new();
+    /// uf.insert_many(["f(a)", "a", "g(a, b)"]);
+    /// uf.union(&"g(a, b)", &"f(a)");
+    ///
+    /// // The shortest term leads, whatever the ranks.
+    /// let shortest = |x: &ClassInfo<&&str>, y: &ClassInfo<&&str>| x.leader.len() <= y.leader.len();
+    /// assert_eq!(Some("a".into()), uf.union_with(&"f(a)", &"a", shortest));
+    ///
+    /// uf.insert_many(["b", "c"]);
+    /// assert_eq!(Some("c".into()), uf.union_with(&"c", &"b", KeepFirst));
+    /// assert_eq!(Some("c".into()), uf.union_with(&"c", &"a", KeepFirst));
+    /// ```
+    pub fn union_with<Q, P>(&mut self, x: &Q, y: &Q, mut policy: P) -> Option<Rc<T>>
+    where
+        Q: Hash + Eq + ?Sized,
+        T: Borrow<Q>,
+        P: UnionPolicy<T>,
+    {
+        let x = self.with_leader(x, |_, leader| leader)?;
+        let y = self.with_leader(y, |_, leader| leader)?;
+        let ps = self.parents.get_mut();
+        if x == y {
+            return Some(Self::handle_in(ps, x).clone());
+        }
+        // The policy sees the leaders borrowed from the map, only the kept one is copied out.
+        let weights = self.weights.as_deref();
+        let info = |leader| Self::info_in(ps, weights, leader, ps.get_index(leader).unwrap().0);
+        let keeps_first = policy.keeps_first(&info(x), &info(y));
+        let leader = if keeps_first {
+            self.link(x, y)
+        } else {
+            self.link(y, x)
+        };
+        Some(Self::handle_in(self.parents.get_mut(), leader).clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn policies_pick_the_leader() {
+        let mut by_rank = UnionFind::new();
+        let mut uf = UnionFind::new();
+        by_rank.insert_many(0..16);
+        uf.insert_many(0..16);
+        for i in 0..15 {
+            let (x, y) = (i * 5 % 16, i * 9 % 16);
+            assert_eq!(by_rank.union(&x, &y), uf.union_with(&x, &y, ByRank));
+        }
+
+        // A chain of three under 2, with rank 2, against a star of four under 3, with rank 1.
+        let mut uf = UnionFind::new();
+        uf.insert_many(0..7);
+        uf.union_with(&1, &0, KeepFirst);
+        uf.union_with(&2, &1, KeepFirst);
+        assert_eq!(Some(Rc::new(2)), uf.find(&0));
+        for i in 4..7 {
+            uf.union(&3, &i);
+        }
+        assert_eq!((Some(2), Some(1)), (uf.class_rank(&0), uf.class_rank(&6)));
+        let mut by_size = uf.clone();
+        assert_eq!(Some(Rc::new(2)), uf.union_with(&0, &6, ByRank));
+        assert_eq!(Some(Rc::new(3)), by_size.union_with(&0, &6, BySize));
+        assert_eq!(Some(7), by_size.class_size(&0));
+        assert_eq!(None, by_size.union_with(&3, &9, BySize));
+    }
+
+    #[test]
+    fn policies_borrow_the_leaders() {
+        // Every clone of a key bumps the shared counter.
+        #[derive(Debug, PartialEq, Eq, Hash)]
+        struct Counted(u32);
+
+        thread_local!(static CLONES: std::cell::Cell<usize> = const { std::cell::Cell::new(0) });
+
+        impl Clone for Counted {
+            fn clone(&self) -> Self {
+                CLONES.with(|clones| clones.set(clones.get() + 1));
+                Counted(self.0)
+            }
+        }
+
+        let mut uf = UnionFind::new();
+        uf.insert_many((0..3).map(Counted));
+        let before = CLONES.with(|clones| clones.get());
+        uf.union_with(&Counted(0), &Counted(1), BySize);
+        uf.union_with(&Counted(2), &Counted(1), KeepFirst);
+        // Only the returned leaders were copied.
+        assert_eq!(2, CLONES.with(|clones| clones.get()) - before);
+    }
+}
@@ -0,0 +1,239 @@
+use std::{borrow::Borrow, fmt::Debug, hash::Hash, rc::Rc};
+use indexmap::IndexMap;
+
+use super::UnionFind;
+
+/// A map whose keys are the classes of a [UnionFind] it owns, so every member of a class
+/// reaches the same entry.
+///
+/// Unions go through [QuotientMap::union], which merges the two entries with `combine`, so
+/// entries never fragment across stale leaders.
+///
+/// # Examples
+///
+/// ```
+/// use hash_unionfind::QuotientMap;
+///
+/// let mut owners = QuotientMap::new(|mut a: Vec<&str>, b| {
+///     a.extend(b);
+///     a
+/// });
+/// owners.insert("alice@old", vec!["laptop"]);
+/// owners.insert("alice@new", vec!["phone"]);
+/// owners.insert_key("alice@work");
+///
+/// owners.union(&"alice@old", &"alice@work");
+/// owners.union(&"alice@work", &"alice@new");
+/// assert_eq!(Some(&vec!["laptop", "phone"]), owners.get(&"alice@new"));
+/// assert_eq!(1, owners.len());
+/// ```
+#[derive(Debug, Clone)]
+pub struct QuotientMap<T: Hash + Eq + Clone + Debug, V, C> {
+    uf: UnionFind<T>,
+    // By leader, classes without a value have no entry.
+    values: IndexMap<Rc<T>, V>,
+    combine: C,
+}
+
+impl<T: Hash + Eq + Clone + Debug, V, C> QuotientMap<T, V, C> {
+    /// A map over an existing partition, with no values yet.
+    pub fn from_union_find(uf: UnionFind<T>, combine: C) -> Self {
+        Self {
+            uf,
+            values: IndexMap::new(),
+            combine,
+        }
+    }
+
+    /// Inserts key into the partition without giving its class a value.
+    pub fn insert_key(&mut self, key: T) {
+        self.uf.insert(key);
+    }
+
+    /// Sets the value of the class of key, inserting key as a singleton if missing. Returns
+    /// the previous value of the class.
+    pub fn insert(&mut self, key: T, value: V) -> Option<V> {
+        self.uf.insert(key.clone());
+        let leader = self.uf.find(&key).unwrap();
+        self.values.insert(leader, value)
+    }
+
+    /// The value of the class of key, None if the class has none or key is missing.
+    pub fn get<Q: Hash + Eq + ?Sized>(&self, key: &Q) -> Option<&V>
+    where
+        T: Borrow<Q>,
+    {
+        self.values.get(&self.uf.find(key)?)
+    }
+
+    pub fn get_mut<Q: Hash + Eq + ?Sized>(&mut self, key: &Q) -> Option<&mut V>
+    where
+        T: Borrow<Q>,
+    {
+        self.values.get_mut(&self.uf.find(key)?)
+    }
+
+    /// The value of the class of key, inserting key and `default()` first if needed.
+    pub fn get_or_insert_with<F: FnOnce() -> V>(&mut self, key: T, default: F) -> &mut V {
+        self.uf.insert(key.clone());
+        let leader = self.uf.find(&key).unwrap();
+        self.values.entry(leader).or_insert_with(default)
+    }
+
+    /// Removes and returns the value of the class of key. The class itself stays.
+    pub fn remove<Q: Hash + Eq + ?Sized>(&mut self, key: &Q) -> Option<V>
+    where
+        T: Borrow<Q>,
+    {
+        self.values.swap_remove(&self.uf.find(key)?)
+    }
+
+    /// Like [QuotientMap::union], but merges the absorbed value into the kept one in place
+    /// with merge(&mut kept, absorbed) instead of combine, for values that are costly to move.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use hash_unionfind::QuotientMap;
+    ///
+    /// let mut tags = QuotientMap::new(|mut a: Vec<u8>, b| {
+    ///     a.extend(b);
+    ///     a
+    /// });
+    /// tags.insert("a", vec![1]);
+    /// tags.insert("b", vec![2]);
+    /// tags.union_with(&"a", &"b", |kept, absorbed| kept.extend(absorbed));
+    /// assert_eq!(2, tags.get(&"a").unwrap().len());
+    /// ```
+    pub fn union_with<Q: Hash + Eq + ?Sized, F: FnOnce(&mut V, V)>(&mut self, a: &Q, b: &Q, merge: F) -> Option<Rc<T>>
+    where
+        T: Borrow<Q>,
+    {
+        let (leader, absorbed) = self.link(a, b)?;
+        if let Some(absorbed) = absorbed {
+            match self.values.get_mut(&leader) {
+                Some(kept) => merge(kept, absorbed),
+                None => {
+                    self.values.insert(leader.clone(), absorbed);
+                }
+            }
+        }
+        Some(leader)
+    }
+
+    // Unions the classes of a and b, returning the new leader and the value taken from the
+    // absorbed class, for the caller to merge.
+    fn link<Q: Hash + Eq + ?Sized>(&mut self, a: &Q, b: &Q) -> Option<(Rc<T>, Option<V>)>
+    where
+        T: Borrow<Q>,
+    {
+        let (a, b) = (self.uf.find(a)?, self.uf.find(b)?);
+        if a == b {
+            return Some((a, None));
+        }
+        let leader = self.uf.union::<T>(&a, &b).unwrap();
+        let absorbed = if leader == a { b } else { a };
+        let absorbed = self.values.swap_remove(&absorbed);
+        Some((leader, absorbed))
+    }
+
+    /// The number of classes with a value.
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    /// The classes with a value, by leader.
+    pub fn iter(&self) -> impl Iterator<Item = (&Rc<T>, &V)> {
+        self.values.iter()
+    }
+
+    pub fn union_find(&self) -> &UnionFind<T> {
+        &self.uf
+    }
+
+    /// The partition and the value of every class by leader.
+    pub fn into_parts(self) -> (UnionFind<T>, IndexMap<Rc<T>, V>) {
+        (self.uf, self.values)
+    }
+}
+
+impl<T, V, C> QuotientMap<T, V, C>
+where
+    T: Hash + Eq + Clone + Debug,
+    C: FnMut(V, V) -> V,
+{
+    /// An empty map, merging the values of unioned classes with combine(kept, absorbed).
+    pub fn new(combine: C) -> Self {
+        Self::from_union_find(UnionFind::new(), combine)
+    }
+
+    /// Unions the classes of a and b, combining their values if both have one. Returns the
+    /// new leader, or None if one of them is missing.
+    pub fn union<Q: Hash + Eq + ?Sized>(&mut self, a: &Q, b: &Q) -> Option<Rc<T>>
+    where
+        T: Borrow<Q>,
+    {
+        let (leader, absorbed) = self.link(a, b)?;
+        if let Some(absorbed) = absorbed {
+            let value = match self.values.swap_remove(&leader) {
+                Some(kept) => (self.combine)(kept, absorbed),
+                None => absorbed,
+            };
+            self.values.insert(leader.clone(), value);
+        }
+        Some(leader)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn values_follow_unions() {
+        let mut totals = QuotientMap::new(|a: u32, b| a + b);
+        totals.insert(1, 10);
+        totals.insert(2, 20);
+        totals.insert_key(3);
+        totals.insert_key(4);
+        assert_eq!(Some(10), totals.insert(1, 1));
+
+        // One side without a value keeps the other's.
+        assert_eq!(Some(Rc::new(3)), totals.union(&3, &2));
+        assert_eq!(Some(&20), totals.get(&3));
+        totals.union(&4, &1);
+        totals.union(&4, &2);
+        assert_eq!(Some(&21), totals.get(&2));
+        assert_eq!(None, totals.union(&4, &9));
+        *totals.get_mut(&1).unwrap() += 1;
+        assert_eq!(&mut 22, totals.get_or_insert_with(3, || 0));
+
+        assert_eq!(Some(22), totals.remove(&4));
+        assert!(totals.is_empty());
+        assert_eq!(&mut 0, totals.get_or_insert_with(5, || 0));
+        totals.union(&5, &1);
+        assert_eq!(vec![(&Rc::new(4), &0)], totals.iter().collect::<Vec<_>>());
+        let (uf, _) = totals.into_parts();
+        assert_eq!(1, uf.num_classes());
+    }
+
+    #[test]
+    fn union_with_merges_in_place() {
+        let mut lists = QuotientMap::from_union_find(UnionFind::new(), ());
+        lists.insert(1, vec![1]);
+        lists.insert(2, vec![2]);
+        lists.insert_key(3);
+        let append = |kept: &mut Vec<u32>, absorbed| kept.extend(absorbed);
+        lists.union_with(&1, &2, append);
+        assert_eq!(Some(3), lists.get(&2).map(|list| list.iter().sum()));
+        // Unioning a class without a value keeps the value of the other.
+        let leader = lists.union_with(&3, &1, append).unwrap();
+        assert_eq!(Some(2), lists.get(leader.as_ref()).map(Vec::len));
+        assert_eq!(None, lists.union_with(&3, &4, append));
+        assert_eq!(1, lists.len());
+    }
+}
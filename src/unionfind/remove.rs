@@ -0,0 +1,193 @@
+use std::{borrow::Borrow, fmt::Debug, hash::Hash};
+
+use crate::Backend;
+
+use super::UnionFind;
+
+impl<T: Hash + Eq + Clone + Debug, B: Backend<Key = T>> UnionFind<T, B> {
+    /// Splits x out of its class into a singleton, returning whether it was in a larger
+    /// class. False if x is missing or already alone.
+    ///
+    /// The rest of the class is flattened onto its leader, or onto another member if x led
+    /// it, so this takes time proportional to the class size. Both classes get new versions.
+    /// The merge history, if recorded, keeps the merges x took part in. A
+    /// [LeaderHandle](crate::LeaderHandle) taken at x follows x into its singleton, not the
+    /// rest of the class.
+    ///
+    /// Panics while a checkpoint is open, see [UnionFind::snapshot].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use hash_unionfind::UnionFind;
+    ///
+    /// let mut uf = UnionFind::new();
+    /// uf.insert_many(0..3);
+    /// uf.union(&0, &1);
+    /// uf.union(&0, &2);
+    /// assert!(uf.make_singleton(&0));
+    /// assert_eq!(uf.find(&1), uf.find(&2));
+    /// assert_eq!((Some(1), Some(2)), (uf.class_size(&0), uf.class_size(&1)));
+    /// assert!(!uf.make_singleton(&0));
+    /// ```
+    pub fn make_singleton<Q: Hash + Eq + ?Sized>(&mut self, x: &Q) -> bool
+    where
+        T: Borrow<Q>,
+    {
+        assert!(self.journal.is_none(), "cannot split a class while a checkpoint is open");
+        let ps = self.parents.get_mut();
+        let Some(x) = ps.get_index_of(x) else {
+            return false;
+        };
+        let leader = Self::find_index_in(ps, x);
+        let members = Self::member_indices_in(ps, leader);
+        if members.len() == 1 {
+            return false;
+        }
+        let at = members.iter().position(|&m| m == x).unwrap();
+        let rest: Vec<usize> = members.iter().copied().filter(|&m| m != x).collect();
+        let kept = if leader == x { rest[0] } else { leader };
+
+        // Unhook x from the member cycle, which member_indices_in followed from the leader.
+        let x_next = ps[x].next;
+        ps[members[(at + members.len() - 1) % members.len()]].next = x_next;
+        for &member in &rest {
+            ps[member].parent = crate::small(kept);
+        }
+        self.clock += 1;
+        let kept_node = &mut ps[kept];
+        kept_node.rank = if rest.len() > 1 { 1 } else { 0 };
+        kept_node.size = crate::small(rest.len());
+        kept_node.version = self.clock;
+        let x_node = &mut ps[x];
+        x_node.parent = crate::small(x);
+        x_node.next = crate::small(x);
+        x_node.rank = 0;
+        x_node.size = 1;
+        x_node.version = self.clock;
+        self.classes += 1;
+        if let Some(weights) = &mut self.weights {
+            let x_weight = weights[x].own;
+            weights[kept].class = weights[leader].class - x_weight;
+            weights[x].class = x_weight;
+        }
+        if let Some(dirty) = &mut self.dirty {
+            dirty.extend([crate::small(kept), crate::small(x)]);
+        }
+
+        let key = |i: usize| ps.get_index(i).unwrap().0;
+        if let Some(updates) = &mut self.updates {
+            let moved = if leader == x { rest.as_slice() } else { std::slice::from_ref(&x) };
+            for &member in moved {
+                updates.push(((key(member).clone(), key(leader).clone()), -1));
+                let new_leader = if member == x { x } else { kept };
+                updates.push(((key(member).clone(), key(new_leader).clone()), 1));
+            }
+        }
+        if let Some(index) = &mut self.member_index {
+            let (kept, x) = (crate::small(kept), crate::small(x));
+            let mut members = index.swap_remove(&crate::small(leader)).unwrap();
+            members.retain(|&m| m != x);
+            index.insert(kept, members);
+            index.insert(x, vec![x]);
+        }
+        true
+    }
+
+    /// Removes x, returning its key, or None if x is missing. The rest of its class stays
+    /// together as with [UnionFind::make_singleton].
+    ///
+    /// Keeps the insertion order of the other elements, which moves the elements inserted
+    /// after x and takes time proportional to the size of the union-find. A
+    /// [LeaderHandle](crate::LeaderHandle) taken at x resolves to None afterwards.
+    ///
+    /// Panics while a checkpoint is open, see [UnionFind::snapshot].
+    pub fn remove<Q: Hash + Eq + ?Sized>(&mut self, x: &Q) -> Option<T>
+    where
+        T: Borrow<Q>,
+    {
+        assert!(self.journal.is_none(), "cannot remove while a checkpoint is open");
+        self.make_singleton(x);
+        let ps = self.parents.get_mut();
+        let (removed, key) = ps.shift_remove_full(x)?;
+        // The links past x move down with the elements they point at.
+        let shift = |i: &mut crate::Small| {
+            if crate::wide(*i) > removed {
+                *i -= 1;
+            }
+        };
+        for node in ps.values_mut() {
+            shift(&mut node.parent);
+            shift(&mut node.next);
+        }
+        for pending in self.deposed.iter_mut().chain(&mut self.dirty) {
+            pending.retain(|&d| crate::wide(d) != removed);
+            pending.iter_mut().for_each(shift);
+        }
+        self.classes -= 1;
+        if let Some(weights) = &mut self.weights {
+            weights.remove(removed);
+        }
+        if let Some(updates) = &mut self.updates {
+            updates.push(((key.clone(), key.clone()), -1));
+        }
+        if let Some(index) = &mut self.member_index {
+            index.swap_remove(&crate::small(removed));
+            *index = index
+                .drain(..)
+                .map(|(mut leader, mut members)| {
+                    shift(&mut leader);
+                    members.iter_mut().for_each(shift);
+                    (leader, members)
+                })
+                .collect();
+        }
+        Some(key)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn split_and_remove() {
+        let mut uf = UnionFind::new();
+        uf.insert_many(0..8);
+        for (x, y) in [(0, 1), (2, 3), (0, 2), (4, 5), (0, 4), (6, 7)] {
+            uf.union(&x, &y);
+        }
+        uf.index_members();
+        uf.track_updates();
+        let before = uf.take_updates().unwrap();
+        let version = uf.class_version(&5).unwrap();
+
+        // A leader and a member deep in the tree.
+        assert!(uf.make_singleton(&0));
+        assert!(uf.make_singleton(&3));
+        assert_eq!(vec![vec![0], vec![1, 2, 4, 5], vec![3], vec![6, 7]], uf.to_canonical_vec());
+        assert_eq!(Some(4), uf.class_size(&5));
+        assert!(uf.class_version(&5).unwrap() > version);
+        assert_eq!(4, uf.members(&1).count());
+
+        assert_eq!(Some(7), uf.remove(&7));
+        assert_eq!(Some(4), uf.remove(&4));
+        assert_eq!(None, uf.remove(&4));
+        assert_eq!(vec![vec![0], vec![1, 2, 5], vec![3], vec![6]], uf.to_canonical_vec());
+        assert_eq!(vec![0, 1, 2, 3, 5, 6], uf.elements());
+        assert_eq!(4, uf.num_classes());
+        uf.union(&6, &3);
+        assert_eq!(Some(2), uf.class_size(&3));
+
+        // The updates bring the first batch to the current partition.
+        let mut pairs = std::collections::HashMap::new();
+        for (pair, diff) in before.into_iter().chain(uf.take_updates().unwrap()) {
+            *pairs.entry(pair).or_insert(0) += diff;
+        }
+        pairs.retain(|_, diff| *diff != 0);
+        let mut pairs: Vec<_> = pairs.into_iter().map(|((t, leader), diff)| (t, leader, diff)).collect();
+        pairs.sort();
+        let expected: Vec<_> = uf.elements().into_iter().map(|t| (t, *uf.find(&t).unwrap(), 1)).collect();
+        assert_eq!(expected, pairs);
+    }
+}
@@ -0,0 +1,78 @@
+use std::{fmt::Debug, hash::Hash, rc::Rc};
+use rand::{seq::IteratorRandom, Rng};
+
+use crate::Backend;
+
+use super::UnionFind;
+
+impl<T: Hash + Eq + Clone + Debug, B: Backend<Key = T>> UnionFind<T, B> {
+    /// Samples a class with probability proportional to its size and returns its leader.
+    /// Returns None if the union-find is empty.
+    ///
+    /// Picking an element uniformly and taking its leader is exactly size-weighted
+    /// class sampling, so this is O(1) besides the [UnionFind::find] call.
+    pub fn sample_class_by_size<R: Rng + ?Sized>(&self, rng: &mut R) -> Option<Rc<T>> {
+        let mut ps = self.access();
+        if ps.is_empty() {
+            return None;
+        }
+        let leader = ps.leader(rng.gen_range(0..ps.len()));
+        Some(Self::handle_in(&ps, leader).clone())
+    }
+
+    /// Samples a class uniformly over all classes and returns its leader.
+    /// Returns None if the union-find is empty.
+    ///
+    /// This scans all elements once, O(n).
+    pub fn sample_class_uniform<R: Rng + ?Sized>(&self, rng: &mut R) -> Option<Rc<T>> {
+        let ps = self.parents.borrow();
+        (0..ps.len())
+            .filter(|&i| crate::wide(ps[i].parent) == i)
+            .choose(rng)
+            .map(|leader| Self::handle_in(&ps, leader).clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::{rngs::StdRng, SeedableRng};
+
+    #[test]
+    fn sample_empty() {
+        let uf: UnionFind<usize> = UnionFind::new();
+        let mut rng = StdRng::seed_from_u64(0);
+        assert_eq!(None, uf.sample_class_by_size(&mut rng));
+        assert_eq!(None, uf.sample_class_uniform(&mut rng));
+    }
+
+    #[test]
+    fn sample_weighted_and_uniform() {
+        // One class of 9 elements and one singleton.
+        let mut uf = UnionFind::new();
+        for i in 0..10 {
+            uf.insert(i);
+        }
+        for i in 1..9 {
+            uf.union(&0, &i);
+        }
+        let big = uf.find(&0).unwrap();
+        let mut rng = StdRng::seed_from_u64(42);
+
+        let trials = 10000;
+        let by_size = (0..trials)
+            .filter(|_| uf.sample_class_by_size(&mut rng).unwrap() == big)
+            .count();
+        let uniform = (0..trials)
+            .filter(|_| uf.sample_class_uniform(&mut rng).unwrap() == big)
+            .count();
+        // Expected 9000 and 5000 respectively.
+        assert!((8700..9300).contains(&by_size), "{}", by_size);
+        assert!((4700..5300).contains(&uniform), "{}", uniform);
+
+        // Under a live shared borrow sampling walks instead of compressing.
+        let ps = uf.parents.borrow();
+        assert!(uf.sample_class_by_size(&mut rng).is_some());
+        drop(ps);
+    }
+}
@@ -0,0 +1,89 @@
+use std::{fmt::Debug, hash::Hash};
+
+use crate::Backend;
+
+use super::UnionFind;
+
+/// A summary of the shape of a [UnionFind], see [UnionFind::stats].
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Stats {
+    pub elements: usize,
+    pub classes: usize,
+    /// The size of the largest class, 0 when empty.
+    pub largest_class: usize,
+    /// The number of effective unions since creation, which only ever grows. Unions undone by
+    /// a rollback still count, and splits do not.
+    pub merges: u64,
+    /// The mean number of parents followed from an element to its leader, what a find on a
+    /// random element would walk before compressing.
+    pub average_find_depth: f64,
+}
+
+impl<T: Hash + Eq + Clone + Debug, B: Backend<Key = T>> UnionFind<T, B> {
+    /// Computes [Stats], walking every element to its leader without compressing, so this
+    /// leaves the forest as it is and takes time proportional to the total find depth. That
+    /// is O(n log n) at worst when linking by rank or size, but can reach O(n²) when the
+    /// leader is chosen otherwise, as with [KeepFirst](crate::KeepFirst) or
+    /// [MinLeaderUnionFind](crate::MinLeaderUnionFind).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use hash_unionfind::uf;
+    ///
+    /// let stats = uf! { {0, 1, 2}, {3} }.stats();
+    /// assert_eq!((4, 2, 3, 2), (stats.elements, stats.classes, stats.largest_class, stats.merges));
+    /// assert_eq!(0.5, stats.average_find_depth);
+    /// ```
+    pub fn stats(&self) -> Stats {
+        let ps = self.parents.borrow();
+        let mut largest_class = 0;
+        let mut depths = 0;
+        for i in 0..ps.len() {
+            let mut current = i;
+            if crate::wide(ps[i].parent) == i {
+                largest_class = largest_class.max(crate::wide(ps[i].size));
+            }
+            while crate::wide(ps[current].parent) != current {
+                depths += 1;
+                current = crate::wide(ps[current].parent);
+            }
+        }
+        Stats {
+            elements: ps.len(),
+            classes: self.classes,
+            largest_class,
+            merges: self.unions,
+            average_find_depth: if ps.is_empty() { 0.0 } else { depths as f64 / ps.len() as f64 },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn stats() {
+        let mut uf = UnionFind::new();
+        assert_eq!(Stats::default(), uf.stats());
+        uf.insert_many(0..8);
+        for (x, y) in [(0, 1), (2, 3), (0, 2), (4, 5), (0, 2)] {
+            uf.union(&x, &y);
+        }
+        // 1 and 2 are one step from 0, 3 is two, 5 is one step from 4.
+        let expected = Stats { elements: 8, classes: 4, largest_class: 4, merges: 4, average_find_depth: 5.0 / 8.0 };
+        assert_eq!(expected, uf.stats());
+
+        // Stats do not compress, a find does.
+        assert_eq!(expected, uf.stats());
+        uf.find(&3);
+        assert_eq!(0.5, uf.stats().average_find_depth);
+
+        uf.make_singleton(&5);
+        let checkpoint = uf.snapshot();
+        uf.union(&6, &7);
+        uf.rollback(checkpoint);
+        assert_eq!(5, uf.stats().merges);
+    }
+}
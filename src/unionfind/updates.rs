@@ -0,0 +1,104 @@
+use std::{fmt::Debug, hash::Hash};
+
+use crate::Backend;
+
+use super::UnionFind;
+
+/// A change to the element → leader relation in the `(data, diff)` shape consumed by
+/// differential dataflow: `((element, leader), 1)` asserts a pair, `-1` retracts it.
+pub type Update<T> = ((T, T), isize);
+
+impl<T: Hash + Eq + Clone + Debug, B: Backend<Key = T>> UnionFind<T, B> {
+    /// Starts tracking changes to the partition as [Update]s. The first batch asserts the
+    /// current leader of every element, so the stream of batches fully describes the
+    /// partition. Does nothing if already tracking.
+    ///
+    /// Unions cost time proportional to the size of the absorbed class while tracking.
+    pub fn track_updates(&mut self) {
+        if self.updates.is_some() {
+            return;
+        }
+        let ps = self.parents.get_mut();
+        let mut updates = Vec::with_capacity(ps.len());
+        for i in 0..ps.len() {
+            let leader = Self::find_index_in(ps, i);
+            updates.push(((ps.get_index(i).unwrap().0.clone(), ps.get_index(leader).unwrap().0.clone()), 1));
+        }
+        self.updates = Some(updates);
+    }
+
+    /// Returns the updates since the last call, or None if not tracking. Elements of an
+    /// absorbed class retract their old leader and assert the new one.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use hash_unionfind::UnionFind;
+    ///
+    /// let mut uf = UnionFind::new();
+    /// uf.insert_many([1, 2]);
+    /// uf.track_updates();
+    /// assert_eq!(Some(vec![((1, 1), 1), ((2, 2), 1)]), uf.take_updates());
+    ///
+    /// uf.union(&1, &2);
+    /// assert_eq!(Some(vec![((2, 2), -1), ((2, 1), 1)]), uf.take_updates());
+    /// ```
+    pub fn take_updates(&mut self) -> Option<Vec<Update<T>>> {
+        self.updates.as_mut().map(std::mem::take)
+    }
+
+    /// Stops tracking and drops pending updates.
+    pub fn stop_updates(&mut self) {
+        self.updates = None;
+    }
+
+    pub(super) fn record_updates(&mut self, kept: usize, absorbed: usize) {
+        if let Some(updates) = &mut self.updates {
+            let ps = self.parents.get_mut();
+            let (kept_key, absorbed_key) = (ps.get_index(kept).unwrap().0, ps.get_index(absorbed).unwrap().0);
+            for member in Self::member_indices_in(ps, absorbed) {
+                let member = ps.get_index(member).unwrap().0;
+                updates.push(((member.clone(), absorbed_key.clone()), -1));
+                updates.push(((member.clone(), kept_key.clone()), 1));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    #[test]
+    fn updates_replay_to_partition() {
+        let mut uf = UnionFind::new();
+        uf.insert_many(0..4);
+        uf.union(&0, &1);
+        assert_eq!(None, uf.take_updates());
+        uf.track_updates();
+
+        let mut relation: HashMap<(u32, u32), isize> = HashMap::new();
+        let mut apply = |batch: Vec<Update<u32>>| {
+            for (pair, diff) in batch {
+                *relation.entry(pair).or_default() += diff;
+            }
+            relation.retain(|_, diff| *diff != 0);
+            let mut pairs: Vec<_> = relation.keys().copied().collect();
+            pairs.sort();
+            pairs
+        };
+
+        assert_eq!(apply(uf.take_updates().unwrap()), vec![(0, 0), (1, 0), (2, 2), (3, 3)]);
+        uf.union(&2, &3);
+        uf.insert(4);
+        uf.union(&3, &0);
+        uf.union(&1, &0);
+        assert_eq!(apply(uf.take_updates().unwrap()), vec![(0, 2), (1, 2), (2, 2), (3, 2), (4, 4)]);
+        assert_eq!(Some(vec![]), uf.take_updates());
+
+        uf.stop_updates();
+        uf.union(&4, &0);
+        assert_eq!(None, uf.take_updates());
+    }
+}
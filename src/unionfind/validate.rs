@@ -0,0 +1,201 @@
+use std::{
+    fmt::{self, Debug, Display},
+    hash::Hash,
+};
+
+use crate::Backend;
+
+use super::UnionFind;
+
+/// A key found to break the `Hash` and `Eq` contract the union-find relies on, typically
+/// because it was mutated through interior mutability after insertion.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum KeyError<T> {
+    /// The key is not equal to itself or to its clone.
+    NotReflexive(T),
+    /// The key cannot be found under its current hash any more.
+    HashChanged(T),
+    /// Two stored keys now compare equal.
+    Collision(T, T),
+}
+
+impl<T: Debug> Display for KeyError<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            KeyError::NotReflexive(t) => write!(f, "key {:?} is not equal to itself or its clone", t),
+            KeyError::HashChanged(t) => write!(f, "key {:?} changed its hash or equality after insertion", t),
+            KeyError::Collision(a, b) => write!(f, "keys {:?} and {:?} became equal after insertion", a, b),
+        }
+    }
+}
+
+impl<T: Debug> std::error::Error for KeyError<T> {}
+
+impl<T: Hash + Eq + Clone + Debug, B: Backend<Key = T>> UnionFind<T, B> {
+    /// Checks that every stored key can still be found under its current hash and equality,
+    /// and that no two stored keys became equal. This is O(n), and meant for debugging key
+    /// types with interior mutability or hand written `Hash` and `Eq` impls. A key whose hash
+    /// changed may still be found by chance for a while, and is reported once it is not.
+    ///
+    /// Debug builds additionally check every new key on [UnionFind::insert]. The forest links
+    /// elements by position, so a broken key only breaks lookups by that key.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::{cell::Cell, hash::{Hash, Hasher}, rc::Rc};
+    /// use hash_unionfind::{KeyError, UnionFind};
+    ///
+    /// // Clones share the cell, so the stored key changes along with this one.
+    /// #[derive(Debug, Clone, PartialEq, Eq)]
+    /// struct Shared(Rc<Cell<u32>>);
+    /// impl Hash for Shared {
+    ///     fn hash<H: Hasher>(&self, state: &mut H) {
+    ///         self.0.get().hash(state)
+    ///     }
+    /// }
+    ///
+    /// let key = Shared(Rc::new(Cell::new(1)));
+    /// let mut uf = UnionFind::new();
+    /// uf.insert(key.clone());
+    /// uf.insert(Shared(Rc::new(Cell::new(2))));
+    /// assert_eq!(Ok(()), uf.validate_keys());
+    ///
+    /// key.0.set(2);
+    /// assert!(matches!(uf.validate_keys(), Err(KeyError::Collision(_, _))));
+    /// ```
+    pub fn validate_keys(&self) -> Result<(), KeyError<T>> {
+        let ps = self.parents.borrow();
+        for (i, t) in ps.keys().enumerate() {
+            #[allow(clippy::eq_op)]
+            if t != t || *t != t.clone() {
+                return Err(KeyError::NotReflexive(t.clone()));
+            }
+            match ps.get_index_of(t) {
+                Some(j) if j == i => {}
+                Some(j) => return Err(KeyError::Collision(ps.get_index(j).unwrap().0.clone(), t.clone())),
+                None => return Err(KeyError::HashChanged(t.clone())),
+            }
+        }
+        Ok(())
+    }
+
+    // Catches keys that are broken from the start, their clones are handed out as leaders.
+    // Only run on keys the backend just took in, duplicates were checked when first added.
+    #[cfg(debug_assertions)]
+    pub(super) fn check_new_key(t: &T) {
+        use std::{collections::hash_map::DefaultHasher, hash::{BuildHasher, BuildHasherDefault}};
+
+        let clone = t.clone();
+        let hasher = BuildHasherDefault::<DefaultHasher>::default();
+        let same_hash = hasher.hash_one(t) == hasher.hash_one(&clone);
+        assert!(
+            *t == clone && same_hash,
+            "{}",
+            KeyError::NotReflexive(clone),
+        );
+    }
+}
+
+// A read API found the map mutably borrowed by an operation of the same union-find further
+// up the stack, such as a find running the Hash, Eq or Clone of a key that calls back in.
+#[cold]
+pub(super) fn reentered() -> ! {
+    panic!(
+        "the union-find was used while one of its own operations was updating it, \
+         for example from the Hash, Eq or Clone impl of its key type"
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::{cell::Cell, hash::Hasher, rc::Rc};
+
+    // Clones share the cell, so changing one changes the stored key.
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    struct Shared(Rc<Cell<u32>>);
+
+    impl Hash for Shared {
+        fn hash<H: Hasher>(&self, state: &mut H) {
+            self.0.get().hash(state)
+        }
+    }
+
+    fn key(i: u32) -> Shared {
+        Shared(Rc::new(Cell::new(i)))
+    }
+
+    #[test]
+    fn detects_changed_keys() {
+        let keys: Vec<_> = (0..4).map(key).collect();
+        let mut uf = UnionFind::new();
+        uf.insert_many(keys.iter().cloned());
+        uf.union(&key(0), &key(1));
+        assert_eq!(Ok(()), uf.validate_keys());
+
+        keys[2].0.set(3);
+        assert_eq!(Err(KeyError::Collision(key(3), key(3))), uf.validate_keys());
+        // The old slot may still be probed by chance, but not for all of these.
+        let changed = (9..14).find_map(|i| {
+            keys[2].0.set(i);
+            uf.validate_keys().err()
+        });
+        assert!(matches!(changed, Some(KeyError::HashChanged(_))));
+        assert_eq!(
+            "key Shared(Cell { value: 9 }) changed its hash or equality after insertion",
+            KeyError::HashChanged(key(9)).to_string(),
+        );
+    }
+
+    #[test]
+    fn duplicates_skip_the_key_check() {
+        // Every hash of a key bumps the shared counter.
+        #[derive(Debug, Clone)]
+        struct Counted(u32, Rc<Cell<u32>>);
+
+        impl PartialEq for Counted {
+            fn eq(&self, other: &Self) -> bool {
+                self.0 == other.0
+            }
+        }
+
+        impl Eq for Counted {}
+
+        impl Hash for Counted {
+            fn hash<H: Hasher>(&self, state: &mut H) {
+                self.1.set(self.1.get() + 1);
+                self.0.hash(state)
+            }
+        }
+
+        let hashes = Rc::new(Cell::new(0));
+        let mut uf = UnionFind::new();
+        uf.insert(Counted(0, hashes.clone()));
+        let before = hashes.get();
+        uf.insert(Counted(0, hashes.clone()));
+        assert_eq!(1, hashes.get() - before);
+    }
+
+    #[test]
+    fn changed_leader_keeps_the_forest() {
+        // Clones copy the cell, so only the returned copy of the leader changes, and the
+        // forest, which links positions, still holds the class together.
+        #[derive(Debug, Clone, PartialEq, Eq)]
+        struct Copied(Cell<u32>);
+
+        impl Hash for Copied {
+            fn hash<H: Hasher>(&self, state: &mut H) {
+                self.0.get().hash(state)
+            }
+        }
+
+        let mut uf = UnionFind::new();
+        uf.insert_many((0..3).map(|i| Copied(Cell::new(i))));
+        uf.union(&Copied(Cell::new(0)), &Copied(Cell::new(1)));
+        uf.union(&Copied(Cell::new(2)), &Copied(Cell::new(0)));
+        uf.find(&Copied(Cell::new(0))).unwrap().0.set(5);
+        assert_eq!(Some(true), uf.same_set(&Copied(Cell::new(1)), &Copied(Cell::new(2))));
+        assert_eq!(Ok(()), uf.validate_keys());
+    }
+}
@@ -0,0 +1,136 @@
+use std::{collections::{HashMap, HashSet}, fmt::{self, Debug, Display}, hash::Hash, rc::Rc};
+
+use crate::Backend;
+
+use super::UnionFind;
+
+/// The first difference found between a proposed grouping and a [UnionFind].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PartitionMismatch<T> {
+    /// The element is in a group but not in the union-find.
+    Unknown(T),
+    /// The element appears more than once in the groups.
+    Repeated(T),
+    /// The element of the union-find is in no group.
+    Missing(T),
+    /// The two elements are grouped together but are in different classes.
+    NotEquivalent(T, T),
+    /// The two elements are in the same class but in different groups. Only reported when
+    /// the grouping has to match exactly.
+    NotGrouped(T, T),
+}
+
+impl<T: Debug> Display for PartitionMismatch<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PartitionMismatch::Unknown(t) => write!(f, "{:?} is not in the union-find", t),
+            PartitionMismatch::Repeated(t) => write!(f, "{:?} appears in more than one group", t),
+            PartitionMismatch::Missing(t) => write!(f, "{:?} is in no group", t),
+            PartitionMismatch::NotEquivalent(a, b) => write!(f, "{:?} and {:?} are grouped but not equivalent", a, b),
+            PartitionMismatch::NotGrouped(a, b) => write!(f, "{:?} and {:?} are equivalent but not grouped", a, b),
+        }
+    }
+}
+
+impl<T: Debug> std::error::Error for PartitionMismatch<T> {}
+
+impl<T: Hash + Eq + Clone + Debug, B: Backend<Key = T>> UnionFind<T, B> {
+    /// Checks that `groups` is exactly the partition: every element appears in exactly one
+    /// group, and two elements share a group iff they share a class. Groups are checked in
+    /// order and the first mismatch is reported.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use hash_unionfind::{uf, PartitionMismatch};
+    ///
+    /// let uf = uf! { {1, 2, 3}, {4} };
+    /// assert_eq!(Ok(()), uf.verify_partition([vec![3, 1, 2], vec![4]]));
+    /// assert_eq!(Err(PartitionMismatch::NotEquivalent(1, 4)), uf.verify_partition([vec![1, 2, 3, 4]]));
+    /// assert_eq!(Err(PartitionMismatch::NotGrouped(1, 3)), uf.verify_partition([vec![1, 2], vec![3], vec![4]]));
+    /// assert_eq!(Ok(()), uf.verify_refinement([vec![1, 2], vec![3], vec![4]]));
+    /// ```
+    pub fn verify_partition<I, G>(&self, groups: I) -> Result<(), PartitionMismatch<T>>
+    where
+        I: IntoIterator<Item = G>,
+        G: IntoIterator<Item = T>,
+    {
+        self.verify_groups(groups, true)
+    }
+
+    /// Like [UnionFind::verify_partition], but accepts groupings that refine the partition:
+    /// every group has to lie inside one class, and a class may be split across groups.
+    pub fn verify_refinement<I, G>(&self, groups: I) -> Result<(), PartitionMismatch<T>>
+    where
+        I: IntoIterator<Item = G>,
+        G: IntoIterator<Item = T>,
+    {
+        self.verify_groups(groups, false)
+    }
+
+    fn verify_groups<I, G>(&self, groups: I, exact: bool) -> Result<(), PartitionMismatch<T>>
+    where
+        I: IntoIterator<Item = G>,
+        G: IntoIterator<Item = T>,
+    {
+        let mut seen = HashSet::new();
+        // The group every class was first seen in, with the element it was seen through.
+        let mut class_group: HashMap<Rc<T>, (usize, T)> = HashMap::new();
+        for (i, group) in groups.into_iter().enumerate() {
+            let mut first: Option<(T, Rc<T>)> = None;
+            for t in group {
+                let Some(leader) = self.find(&t) else {
+                    return Err(PartitionMismatch::Unknown(t));
+                };
+                if !seen.insert(t.clone()) {
+                    return Err(PartitionMismatch::Repeated(t));
+                }
+                match &first {
+                    Some((first, first_leader)) if *first_leader != leader => {
+                        return Err(PartitionMismatch::NotEquivalent(first.clone(), t));
+                    }
+                    Some(_) => {}
+                    None => first = Some((t.clone(), leader.clone())),
+                }
+                match class_group.get(&leader) {
+                    Some((group, other)) if exact && *group != i => {
+                        return Err(PartitionMismatch::NotGrouped(other.clone(), t));
+                    }
+                    Some(_) => {}
+                    None => {
+                        class_group.insert(leader, (i, t));
+                    }
+                }
+            }
+        }
+        if seen.len() < self.size() {
+            let missing = self.parents.borrow().keys().find(|t| !seen.contains(*t)).unwrap().clone();
+            return Err(PartitionMismatch::Missing(missing));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn mismatches() {
+        let uf = crate::uf! { {"a", "b"}, {"c", "d", "e"}, {"f"} };
+        let exact = [vec!["b", "a"], vec!["e", "d", "c"], vec!["f"]];
+        assert_eq!(Ok(()), uf.verify_partition(exact.clone()));
+        assert_eq!(Ok(()), uf.verify_refinement(exact));
+
+        use PartitionMismatch::*;
+        assert_eq!(Err(Unknown("x")), uf.verify_partition([vec!["a", "x"]]));
+        assert_eq!(Err(Repeated("a")), uf.verify_refinement([vec!["a", "b"], vec!["a"]]));
+        assert_eq!(Err(Missing("f")), uf.verify_partition([vec!["a", "b"], vec!["c", "d", "e"]]));
+        assert_eq!(Err(Missing("c")), uf.verify_refinement([vec!["a", "b"], vec!["f"]]));
+        assert_eq!(Err(NotEquivalent("d", "f")), uf.verify_refinement([vec!["a", "b"], vec!["d", "c", "f"]]));
+        let split = [vec!["a", "b"], vec!["c"], vec!["f"], vec!["d", "e"]];
+        assert_eq!(Err(NotGrouped("c", "d")), uf.verify_partition(split.clone()));
+        assert_eq!(Ok(()), uf.verify_refinement(split));
+        assert_eq!("\"c\" and \"d\" are equivalent but not grouped", NotGrouped("c", "d").to_string());
+    }
+}
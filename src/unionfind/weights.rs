@@ -0,0 +1,105 @@
+use std::{borrow::Borrow, fmt::Debug, hash::Hash};
+
+use crate::Backend;
+
+use super::UnionFind;
+
+// The weight of an element, and the total weight of the class on leaders.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub(super) struct Weight {
+    pub(super) own: f64,
+    pub(super) class: f64,
+}
+
+impl<T: Hash + Eq + Clone + Debug, B: Backend<Key = T>> UnionFind<T, B> {
+    /// Like [UnionFind::insert], also giving the element a weight which is summed into the
+    /// total weight of its class. Elements inserted without a weight weigh 0.
+    /// Does nothing if t already exists, use [UnionFind::set_weight] to change a weight.
+    ///
+    /// Weights are kept in a table next to the map, made by the first call to this or
+    /// [UnionFind::set_weight], so union-finds that never weigh an element do not pay for it.
+    pub fn insert_weighted(&mut self, t: T, weight: f64) {
+        if self.parents.get_mut().contains_key(&t) {
+            return;
+        }
+        let index = self.insert(t).index();
+        self.weights_mut()[index] = Weight { own: weight, class: weight };
+    }
+
+    /// Sets the weight of x, updating the total of its class. Returns the previous weight,
+    /// or None if x is missing.
+    pub fn set_weight<Q: Hash + Eq + ?Sized>(&mut self, x: &Q, weight: f64) -> Option<f64>
+    where
+        T: Borrow<Q>,
+    {
+        let ps = self.parents.get_mut();
+        let index = ps.get_index_of(x)?;
+        let leader = Self::find_index_in(ps, index);
+        self.journal_weight(index, leader);
+        let weights = self.weights_mut();
+        let old = std::mem::replace(&mut weights[index].own, weight);
+        weights[leader].class += weight - old;
+        Some(old)
+    }
+
+    /// The weight of x itself, or None if x is missing.
+    pub fn weight<Q: Hash + Eq + ?Sized>(&self, x: &Q) -> Option<f64>
+    where
+        T: Borrow<Q>,
+    {
+        let index = self.parents.borrow().get_index_of(x)?;
+        Some(self.weights.as_ref().map_or(0.0, |weights| weights[index].own))
+    }
+
+    /// The total weight of the class of x, maintained incrementally so this is as cheap as
+    /// [UnionFind::find]. Returns None if x is missing.
+    pub fn class_weight<Q: Hash + Eq + ?Sized>(&self, x: &Q) -> Option<f64>
+    where
+        T: Borrow<Q>,
+    {
+        self.with_leader(x, |_, leader| self.weights.as_ref().map_or(0.0, |weights| weights[leader].class))
+    }
+
+    // The weight table, made on first use with every element weighing 0.
+    fn weights_mut(&mut self) -> &mut Vec<Weight> {
+        let len = self.parents.get_mut().len();
+        self.weights.get_or_insert_with(|| vec![Weight::default(); len])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn class_weights() {
+        let mut uf = UnionFind::new();
+        uf.insert_weighted("a", 1.5);
+        uf.insert_weighted("b", 2.0);
+        uf.insert("c");
+        uf.insert_weighted("a", 100.0);
+        assert_eq!(Some(1.5), uf.class_weight(&"a"));
+        assert_eq!(Some(0.0), uf.class_weight(&"c"));
+        assert_eq!(None, uf.class_weight(&"d"));
+
+        uf.union(&"a", &"b");
+        uf.union(&"c", &"b");
+        assert_eq!(Some(3.5), uf.class_weight(&"c"));
+
+        assert_eq!(Some(0.0), uf.set_weight(&"c", 4.0));
+        assert_eq!(Some(2.0), uf.set_weight(&"b", 1.0));
+        assert_eq!(Some(6.5), uf.class_weight(&"a"));
+        assert_eq!(Some(1.0), uf.weight(&"b"));
+        assert_eq!(None, uf.set_weight(&"d", 1.0));
+
+        // Unweighted union-finds keep no table, and weigh their elements 0.
+        let mut uf = UnionFind::new();
+        uf.insert_many(0..3);
+        uf.union(&0, &1);
+        assert!(uf.weights.is_none());
+        assert_eq!((Some(0.0), Some(0.0)), (uf.weight(&0), uf.class_weight(&1)));
+        uf.set_weight(&2, 1.0);
+        uf.union(&2, &1);
+        assert_eq!(Some(1.0), uf.class_weight(&0));
+    }
+}